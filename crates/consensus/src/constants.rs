@@ -59,6 +59,13 @@ pub const EMPTY_OMMER_ROOT_HASH: B256 =
 pub const EMPTY_ROOT_HASH: B256 =
     b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
 
+/// The bound divisor of the gas limit, used in the allowed delta between the gas limits of a block
+/// and its parent.
+pub const GAS_LIMIT_BOUND_DIVISOR: u128 = 1024;
+
+/// The minimum gas limit of a block.
+pub const MINIMUM_GAS_LIMIT: u128 = 5000;
+
 /// Transactions root of empty receipts set.
 pub const EMPTY_RECEIPTS: B256 = EMPTY_ROOT_HASH;
 
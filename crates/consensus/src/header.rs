@@ -1,3 +1,4 @@
+use crate::constants::{GAS_LIMIT_BOUND_DIVISOR, MINIMUM_GAS_LIMIT};
 use alloy_eips::{
     eip1559::{calc_next_block_base_fee, BaseFeeParams},
     eip4844::{calc_blob_gasprice, calc_excess_blob_gas},
@@ -373,6 +374,151 @@ impl Header {
     pub const fn exceeds_allowed_future_timestamp(&self, present_timestamp: u64) -> bool {
         self.timestamp > present_timestamp + ALLOWED_FUTURE_BLOCK_TIME_SECONDS
     }
+
+    /// Validates this header against its parent.
+    ///
+    /// Checks that the header links to the parent, that its timestamp is after the parent's, that
+    /// its gas limit is within the allowed delta of the parent's, and that its base fee and excess
+    /// blob gas are the ones derived from the parent with [Self::next_block_base_fee] and
+    /// [Self::next_block_excess_blob_gas].
+    ///
+    /// The base fee and excess blob gas of the first London and Cancun blocks are not derived from
+    /// the parent, and are therefore not checked.
+    pub fn validate_against_parent(
+        &self,
+        parent: &Self,
+        base_fee_params: BaseFeeParams,
+    ) -> Result<(), HeaderValidationError> {
+        if self.number != parent.number + 1 {
+            return Err(HeaderValidationError::NumberMismatch {
+                parent: parent.number,
+                number: self.number,
+            });
+        }
+        let parent_hash = parent.hash_slow();
+        if self.parent_hash != parent_hash {
+            return Err(HeaderValidationError::ParentHashMismatch {
+                expected: parent_hash,
+                got: self.parent_hash,
+            });
+        }
+        if self.timestamp <= parent.timestamp {
+            return Err(HeaderValidationError::TimestampNotAfterParent {
+                parent: parent.timestamp,
+                timestamp: self.timestamp,
+            });
+        }
+
+        // The London fork block doubles the gas limit of its parent.
+        let parent_gas_limit =
+            if parent.base_fee_per_gas.is_none() && self.base_fee_per_gas.is_some() {
+                parent.gas_limit * base_fee_params.elasticity_multiplier
+            } else {
+                parent.gas_limit
+            };
+        if self.gas_limit.abs_diff(parent_gas_limit) >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR
+            || self.gas_limit < MINIMUM_GAS_LIMIT
+        {
+            return Err(HeaderValidationError::GasLimitOutOfBounds {
+                parent: parent_gas_limit,
+                gas_limit: self.gas_limit,
+            });
+        }
+
+        if let Some(expected) = parent.next_block_base_fee(base_fee_params) {
+            if self.base_fee_per_gas != Some(expected) {
+                return Err(HeaderValidationError::BaseFeeMismatch {
+                    expected,
+                    got: self.base_fee_per_gas,
+                });
+            }
+        }
+        if let Some(expected) = parent.next_block_excess_blob_gas() {
+            if self.excess_blob_gas != Some(expected) {
+                return Err(HeaderValidationError::ExcessBlobGasMismatch {
+                    expected,
+                    got: self.excess_blob_gas,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error returned by [Header::validate_against_parent].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderValidationError {
+    /// The header number does not follow the parent's.
+    NumberMismatch {
+        /// The number of the parent.
+        parent: BlockNumber,
+        /// The number of the header.
+        number: BlockNumber,
+    },
+    /// The parent hash does not match the hash of the parent.
+    ParentHashMismatch {
+        /// The hash of the parent.
+        expected: B256,
+        /// The parent hash of the header.
+        got: B256,
+    },
+    /// The timestamp is not after the parent's.
+    TimestampNotAfterParent {
+        /// The timestamp of the parent.
+        parent: u64,
+        /// The timestamp of the header.
+        timestamp: u64,
+    },
+    /// The gas limit is not within the allowed delta of the parent's, or below the minimum.
+    GasLimitOutOfBounds {
+        /// The gas limit of the parent.
+        parent: u128,
+        /// The gas limit of the header.
+        gas_limit: u128,
+    },
+    /// The base fee is not the one derived from the parent.
+    BaseFeeMismatch {
+        /// The base fee derived from the parent.
+        expected: u128,
+        /// The base fee of the header.
+        got: Option<u128>,
+    },
+    /// The excess blob gas is not the one derived from the parent.
+    ExcessBlobGasMismatch {
+        /// The excess blob gas derived from the parent.
+        expected: u128,
+        /// The excess blob gas of the header.
+        got: Option<u128>,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HeaderValidationError {}
+
+impl core::fmt::Display for HeaderValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NumberMismatch { parent, number } => {
+                write!(f, "block number {} does not follow parent number {}", number, parent)
+            }
+            Self::ParentHashMismatch { expected, got } => {
+                write!(f, "parent hash mismatch: expected {}, got {}", expected, got)
+            }
+            Self::TimestampNotAfterParent { parent, timestamp } => {
+                write!(f, "timestamp {} is not after parent timestamp {}", timestamp, parent)
+            }
+            Self::GasLimitOutOfBounds { parent, gas_limit } => {
+                write!(f, "gas limit {} is out of bounds of parent gas limit {}", gas_limit, parent)
+            }
+            Self::BaseFeeMismatch { expected, got } => {
+                write!(f, "base fee mismatch: expected {}, got {:?}", expected, got)
+            }
+            Self::ExcessBlobGasMismatch { expected, got } => {
+                write!(f, "excess blob gas mismatch: expected {}, got {:?}", expected, got)
+            }
+        }
+    }
 }
 
 impl Encodable for Header {
@@ -653,4 +799,56 @@ mod tests {
         let decoded: Header = serde_json::from_str(&json).unwrap();
         assert_eq!(decoded, header);
     }
+
+    #[test]
+    fn validates_against_parent() {
+        let params = BaseFeeParams::ethereum();
+        let parent = Header {
+            number: 1,
+            timestamp: 12,
+            gas_limit: 30_000_000,
+            gas_used: 20_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            blob_gas_used: Some(786_432),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        let child = Header {
+            number: 2,
+            parent_hash: parent.hash_slow(),
+            timestamp: 24,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: parent.next_block_base_fee(params),
+            blob_gas_used: Some(0),
+            excess_blob_gas: parent.next_block_excess_blob_gas(),
+            ..Default::default()
+        };
+        assert_eq!(child.validate_against_parent(&parent, params), Ok(()));
+
+        let bad = Header { parent_hash: B256::ZERO, ..child.clone() };
+        assert!(matches!(
+            bad.validate_against_parent(&parent, params),
+            Err(HeaderValidationError::ParentHashMismatch { .. })
+        ));
+        let bad = Header { gas_limit: 30_000_000 + 30_000_000 / 1024, ..child.clone() };
+        assert!(matches!(
+            bad.validate_against_parent(&parent, params),
+            Err(HeaderValidationError::GasLimitOutOfBounds { .. })
+        ));
+        let bad = Header { base_fee_per_gas: Some(1_000_000_000), ..child.clone() };
+        assert!(matches!(
+            bad.validate_against_parent(&parent, params),
+            Err(HeaderValidationError::BaseFeeMismatch { .. })
+        ));
+        let bad = Header { excess_blob_gas: Some(0), ..child.clone() };
+        assert!(matches!(
+            bad.validate_against_parent(&parent, params),
+            Err(HeaderValidationError::ExcessBlobGasMismatch { .. })
+        ));
+        let bad = Header { number: 3, ..child };
+        assert!(matches!(
+            bad.validate_against_parent(&parent, params),
+            Err(HeaderValidationError::NumberMismatch { .. })
+        ));
+    }
 }
@@ -19,7 +19,7 @@ mod encodable_signature;
 pub use encodable_signature::EncodableSignature;
 
 mod header;
-pub use header::{Header, EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH};
+pub use header::{Header, HeaderValidationError, EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH};
 
 mod receipt;
 pub use receipt::{
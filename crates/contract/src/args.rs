@@ -0,0 +1,281 @@
+//! Conversions of function arguments and return values from and to strings and JSON, following
+//! the ABI types of the parameters.
+//!
+//! These are used by [`ContractInstance::function_from_strs`],
+//! [`ContractInstance::function_from_json`] and [`ContractInstance::call_json`].
+//!
+//! [`ContractInstance::function_from_strs`]: crate::ContractInstance::function_from_strs
+//! [`ContractInstance::function_from_json`]: crate::ContractInstance::function_from_json
+//! [`ContractInstance::call_json`]: crate::ContractInstance::call_json
+
+use crate::Result;
+use alloy_dyn_abi::{DynSolType, DynSolValue, Error as AbiError, Specifier};
+use alloy_json_abi::Param;
+use alloy_primitives::hex;
+use serde_json::{Map, Value};
+
+/// Parses human-friendly string arguments against the types of the parameters.
+///
+/// Arguments are parsed with [`DynSolType::coerce_str`]. Numbers may have a unit, as in
+/// `"1.5 ether"` or `"10 gwei"`, arrays are written as `"[1, 2, 3]"` and tuples as `"(1, true)"`.
+pub fn parse_args<S: AsRef<str>>(params: &[Param], args: &[S]) -> Result<Vec<DynSolValue>> {
+    check_len(params.len(), args.len())?;
+    params
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| Ok(param.resolve()?.coerce_str(arg.as_ref())?))
+        .collect()
+}
+
+/// Converts JSON arguments to values of the types of the parameters.
+///
+/// The arguments are either an array, or an object keyed by the parameter names. See
+/// [`from_json`] for the conversion of each argument.
+pub fn args_from_json(params: &[Param], args: &Value) -> Result<Vec<DynSolValue>> {
+    match args {
+        Value::Array(args) => {
+            check_len(params.len(), args.len())?;
+            params.iter().zip(args).map(|(param, arg)| from_json(param, arg)).collect()
+        }
+        Value::Object(args) => {
+            check_len(params.len(), args.len())?;
+            params.iter().map(|param| from_json(param, named(args, &param.name)?)).collect()
+        }
+        _ => Err(mismatch("array or object", args)),
+    }
+}
+
+/// Converts a JSON value to a value of the type of the parameter.
+///
+/// Strings are parsed as in [`parse_args`], so that integers may be given as strings to preserve
+/// their precision. Tuples are either arrays, or objects keyed by the component names.
+pub fn from_json(param: &Param, value: &Value) -> Result<DynSolValue> {
+    from_json_inner(&param.resolve()?, &param.components, value)
+}
+
+fn from_json_inner(ty: &DynSolType, components: &[Param], value: &Value) -> Result<DynSolValue> {
+    Ok(match (ty, value) {
+        (DynSolType::String, Value::String(s)) => DynSolValue::String(s.clone()),
+        (_, Value::String(s)) => ty.coerce_str(s)?,
+        (DynSolType::Bool, Value::Bool(b)) => DynSolValue::Bool(*b),
+        (DynSolType::Uint(_) | DynSolType::Int(_), Value::Number(n)) => {
+            ty.coerce_str(&n.to_string())?
+        }
+        (DynSolType::Array(inner), Value::Array(values)) => DynSolValue::Array(
+            values
+                .iter()
+                .map(|value| from_json_inner(inner, components, value))
+                .collect::<Result<_>>()?,
+        ),
+        (DynSolType::FixedArray(inner, len), Value::Array(values)) => {
+            check_len(*len, values.len())?;
+            DynSolValue::FixedArray(
+                values
+                    .iter()
+                    .map(|value| from_json_inner(inner, components, value))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        (DynSolType::Tuple(types), Value::Array(values)) => {
+            check_len(types.len(), values.len())?;
+            DynSolValue::Tuple(
+                types
+                    .iter()
+                    .zip(values)
+                    .enumerate()
+                    .map(|(i, (ty, value))| {
+                        let components = components.get(i).map_or(&[][..], |c| &c.components);
+                        from_json_inner(ty, components, value)
+                    })
+                    .collect::<Result<_>>()?,
+            )
+        }
+        (DynSolType::Tuple(types), Value::Object(values)) if types.len() == components.len() => {
+            check_len(types.len(), values.len())?;
+            DynSolValue::Tuple(
+                types
+                    .iter()
+                    .zip(components)
+                    .map(|(ty, component)| {
+                        let value = named(values, &component.name)?;
+                        from_json_inner(ty, &component.components, value)
+                    })
+                    .collect::<Result<_>>()?,
+            )
+        }
+        _ => return Err(mismatch(&ty.sol_type_name(), value)),
+    })
+}
+
+/// Converts values of the types of the parameters to JSON, such as the return values of a
+/// function.
+///
+/// The values are converted to an object keyed by the parameter names if all parameters are
+/// named, and to an array otherwise. See [`to_json`] for the conversion of each value.
+pub fn values_to_json(params: &[Param], values: &[DynSolValue]) -> Value {
+    tuple_to_json(params, values)
+}
+
+/// Converts a value of the type of the parameter to JSON.
+///
+/// Integers are converted to decimal strings to preserve their precision, and bytes and
+/// addresses to hex strings. Tuples are converted as in [`values_to_json`].
+pub fn to_json(param: &Param, value: &DynSolValue) -> Value {
+    to_json_inner(&param.components, value)
+}
+
+fn to_json_inner(components: &[Param], value: &DynSolValue) -> Value {
+    match value {
+        DynSolValue::Bool(b) => Value::Bool(*b),
+        DynSolValue::Int(i, _) => Value::String(i.to_string()),
+        DynSolValue::Uint(u, _) => Value::String(u.to_string()),
+        DynSolValue::FixedBytes(word, size) => Value::String(hex::encode_prefixed(&word[..*size])),
+        DynSolValue::Address(address) => Value::String(address.to_checksum(None)),
+        DynSolValue::Function(function) => Value::String(function.to_string()),
+        DynSolValue::Bytes(bytes) => Value::String(hex::encode_prefixed(bytes)),
+        DynSolValue::String(s) => Value::String(s.clone()),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            Value::Array(values.iter().map(|value| to_json_inner(components, value)).collect())
+        }
+        DynSolValue::Tuple(values) => tuple_to_json(components, values),
+        #[allow(unreachable_patterns)]
+        _ => Value::Null,
+    }
+}
+
+fn tuple_to_json(components: &[Param], values: &[DynSolValue]) -> Value {
+    let component = |i: usize| components.get(i).map_or(&[][..], |c| &c.components);
+    let named = components.len() == values.len()
+        && !components.is_empty()
+        && components.iter().all(|c| !c.name.is_empty());
+    if named {
+        Value::Object(
+            components
+                .iter()
+                .zip(values)
+                .map(|(c, value)| (c.name.clone(), to_json_inner(&c.components, value)))
+                .collect(),
+        )
+    } else {
+        Value::Array(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| to_json_inner(component(i), value))
+                .collect(),
+        )
+    }
+}
+
+fn named<'a>(values: &'a Map<String, Value>, name: &str) -> Result<&'a Value> {
+    values.get(name).ok_or_else(|| {
+        AbiError::TypeMismatch { expected: format!("field {name:?}"), actual: "none".into() }.into()
+    })
+}
+
+fn check_len(expected: usize, actual: usize) -> Result<()> {
+    if expected != actual {
+        return Err(AbiError::EncodeLengthMismatch { expected, actual }.into());
+    }
+    Ok(())
+}
+
+fn mismatch(expected: &str, actual: &Value) -> crate::Error {
+    AbiError::TypeMismatch { expected: expected.into(), actual: actual.to_string() }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_json_abi::Function;
+    use alloy_primitives::{address, Address, B256, I256, U256};
+    use serde_json::json;
+
+    fn function(signature: &str) -> Function {
+        Function::parse(signature).unwrap()
+    }
+
+    #[test]
+    fn parses_str_args() {
+        let f = function("function f(uint256 amount, address to, uint8[] ids, (bool,string) t)");
+        let to = "0x00000000000000000000000000000000000000aa";
+        let args = parse_args(&f.inputs, &["1.5 ether", to, "[1, 2, 3]", "(true, hi)"]).unwrap();
+        assert_eq!(
+            args,
+            [
+                DynSolValue::Uint(U256::from(1_500_000_000_000_000_000u128), 256),
+                address!("00000000000000000000000000000000000000aa").into(),
+                DynSolValue::Array(vec![
+                    DynSolValue::Uint(U256::from(1), 8),
+                    DynSolValue::Uint(U256::from(2), 8),
+                    DynSolValue::Uint(U256::from(3), 8),
+                ]),
+                DynSolValue::Tuple(vec![true.into(), DynSolValue::String("hi".into())]),
+            ]
+        );
+
+        assert!(parse_args(&f.inputs, &["1"]).is_err());
+        assert!(parse_args(&f.inputs, &["x", to, "[]", "(true, hi)"]).is_err());
+    }
+
+    #[test]
+    fn converts_json() {
+        let transfer = json!([
+            {"name": "amount", "type": "uint256"},
+            {"name": "memo", "type": "string"}
+        ]);
+        let f: Function = serde_json::from_value(json!({
+            "type": "function",
+            "name": "f",
+            "inputs": [
+                {"name": "delta", "type": "int16"},
+                {"name": "to", "type": "address"},
+                {"name": "transfers", "type": "tuple[]", "components": transfer}
+            ],
+            "outputs": [
+                {"name": "ok", "type": "bool"},
+                {"name": "last", "type": "tuple", "components": transfer}
+            ],
+            "stateMutability": "nonpayable"
+        }))
+        .unwrap();
+        let args = json!({
+            "to": Address::ZERO,
+            "delta": -3,
+            "transfers": [{"amount": "2 gwei", "memo": "a"}, ["1", "b"]],
+        });
+        let values = args_from_json(&f.inputs, &args).unwrap();
+        assert_eq!(
+            values,
+            [
+                DynSolValue::Int(I256::try_from(-3).unwrap(), 16),
+                Address::ZERO.into(),
+                DynSolValue::Array(vec![
+                    DynSolValue::Tuple(vec![
+                        U256::from(2_000_000_000u64).into(),
+                        DynSolValue::String("a".into())
+                    ]),
+                    DynSolValue::Tuple(vec![U256::from(1).into(), DynSolValue::String("b".into())]),
+                ]),
+            ]
+        );
+        assert_eq!(args_from_json(&f.inputs, &json!([-3, Address::ZERO, []])).unwrap().len(), 3);
+
+        assert!(args_from_json(&f.inputs, &json!([true, Address::ZERO, []])).is_err());
+        assert!(args_from_json(&f.inputs, &json!({"delta": 1})).is_err());
+        assert!(args_from_json(&f.inputs, &json!("x")).is_err());
+
+        let outputs = [
+            true.into(),
+            DynSolValue::Tuple(vec![U256::from(u128::MAX).into(), DynSolValue::String("c".into())]),
+        ];
+        assert_eq!(
+            values_to_json(&f.outputs, &outputs),
+            json!({"ok": true, "last": {"amount": u128::MAX.to_string(), "memo": "c"}})
+        );
+
+        let f = function("function g() returns (bytes4, bytes)");
+        let outputs = [DynSolValue::FixedBytes(B256::ZERO, 4), DynSolValue::Bytes(vec![1, 2])];
+        assert_eq!(values_to_json(&f.outputs, &outputs), json!(["0x00000000", "0x0102"]));
+    }
+}
@@ -1,17 +1,22 @@
-use crate::{CallDecoder, Error, EthCall, Result};
+use crate::{deterministic, trace::TraceDecoder, CallDecoder, CallPreview, Error, EthCall, Result};
 use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
-use alloy_json_abi::Function;
+use alloy_json_abi::{Function, JsonAbi};
 use alloy_network::{Ethereum, Network, TransactionBuilder};
 use alloy_network_primitives::ReceiptResponse;
-use alloy_primitives::{Address, Bytes, ChainId, TxKind, U256};
+use alloy_primitives::{Address, Bytes, ChainId, TxKind, B256, U256};
 use alloy_provider::{PendingTransactionBuilder, Provider};
 use alloy_rpc_types_eth::{state::StateOverride, AccessList, BlobTransactionSidecar, BlockId};
+use alloy_rpc_types_trace::geth::{
+    CallConfig, CallFrame, DiffMode, GethDebugBuiltInTracerType, GethDebugTracingCallOptions,
+    GethDebugTracingOptions, PreStateConfig,
+};
 use alloy_sol_types::SolCall;
 use alloy_transport::Transport;
 use std::{
     future::{Future, IntoFuture},
     marker::PhantomData,
     pin::Pin,
+    sync::Arc,
 };
 
 /// [`CallBuilder`] using a [`SolCall`] type as the call decoder.
@@ -129,6 +134,8 @@ pub struct CallBuilder<T, P, D, N: Network = Ethereum> {
     // NOTE: This is public due to usage in `sol!`, please avoid changing it.
     pub provider: P,
     decoder: D,
+    /// The ABI whose custom errors are used to decode reverts.
+    abi: Option<Arc<JsonAbi>>,
     transport: PhantomData<T>,
 }
 
@@ -152,13 +159,16 @@ impl<T: Transport + Clone, P: Provider<T, N>, N: Network> DynCallBuilder<T, P, N
         address: &Address,
         function: &Function,
         args: &[DynSolValue],
+        abi: Arc<JsonAbi>,
     ) -> Result<Self> {
-        Ok(Self::new_inner_call(
+        let mut builder = Self::new_inner_call(
             provider,
             function.abi_encode_input(args)?.into(),
             function.clone(),
         )
-        .to(*address))
+        .to(*address);
+        builder.abi = Some(abi);
+        Ok(builder)
     }
 
     /// Clears the decoder, returning a raw call builder.
@@ -170,6 +180,7 @@ impl<T: Transport + Clone, P: Provider<T, N>, N: Network> DynCallBuilder<T, P, N
             state: self.state,
             provider: self.provider,
             decoder: (),
+            abi: self.abi,
             transport: PhantomData,
         }
     }
@@ -196,6 +207,7 @@ impl<T: Transport + Clone, P: Provider<T, N>, C: SolCall, N: Network> SolCallBui
             state: self.state,
             provider: self.provider,
             decoder: (),
+            abi: self.abi,
             transport: PhantomData,
         }
     }
@@ -261,6 +273,7 @@ impl<T: Transport + Clone, P: Provider<T, N>, N: Network> RawCallBuilder<T, P, N
             state: self.state,
             provider: self.provider,
             decoder: PhantomData::<C>,
+            abi: self.abi,
             transport: PhantomData,
         }
     }
@@ -294,6 +307,7 @@ impl<T: Transport + Clone, P: Provider<T, N>, D: CallDecoder, N: Network> CallBu
             provider,
             block: BlockId::default(),
             state: None,
+            abi: None,
             transport: PhantomData,
         }
     }
@@ -305,6 +319,7 @@ impl<T: Transport + Clone, P: Provider<T, N>, D: CallDecoder, N: Network> CallBu
             provider,
             block: BlockId::default(),
             state: None,
+            abi: None,
             transport: PhantomData,
         }
     }
@@ -431,7 +446,64 @@ impl<T: Transport + Clone, P: Provider<T, N>, D: CallDecoder, N: Network> CallBu
         if let Some(state) = &self.state {
             estimate = estimate.overrides(state);
         }
-        estimate.block(self.block).await.map_err(Into::into)
+        estimate.block(self.block).await.map_err(|e| self.map_err(e.into()))
+    }
+
+    /// Previews what the transaction would change without submitting it to the network.
+    /// If [`state overrides`](Self::state) are set, they will be applied to the preview.
+    ///
+    /// The transaction is traced with `debug_traceCall`, with the `prestateTracer` in diff mode
+    /// for the balance and storage changes, and with the `callTracer` for the call trace and logs,
+    /// which are decoded with the ABI of the contract. Its gas is estimated with
+    /// `eth_estimateGas`.
+    ///
+    /// Storage changes can be decoded with the storage layout of a contract with
+    /// [`CallPreview::decode_storage`].
+    ///
+    /// # Note
+    ///
+    /// Not all client implementations support `debug_traceCall`.
+    pub async fn preview(&self) -> Result<CallPreview> {
+        let options = |tracing_options| GethDebugTracingCallOptions {
+            tracing_options,
+            state_overrides: self.state.clone(),
+            block_overrides: None,
+        };
+        let prestate = options(
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugBuiltInTracerType::PreStateTracer.into())
+                .with_prestate_config(PreStateConfig { diff_mode: Some(true) }),
+        );
+        let calls = options(
+            GethDebugTracingOptions::default()
+                .with_tracer(GethDebugBuiltInTracerType::CallTracer.into())
+                .with_call_config(CallConfig::default().with_log()),
+        );
+
+        let client = self.provider.client();
+        let (estimate, diff, frame) = futures::join!(
+            self.estimate_gas(),
+            client.request::<_, DiffMode>("debug_traceCall", (&self.request, self.block, prestate)),
+            client.request::<_, CallFrame>("debug_traceCall", (&self.request, self.block, calls)),
+        );
+        let gas_estimate = match estimate {
+            Ok(gas) => Some(gas),
+            Err(Error::Revert(_)) => None,
+            Err(err) => return Err(err),
+        };
+
+        // The ABI of a deployment is only known to be the one of the created contract, so its
+        // signatures are used instead.
+        let mut decoder = TraceDecoder::new();
+        if let Some(abi) = &self.abi {
+            let abi = JsonAbi::clone(abi);
+            decoder = match self.request.to() {
+                Some(to) => decoder.with_abi(to, abi),
+                None => decoder.with_signature_abi(abi),
+            };
+        }
+        let trace = decoder.decode_call_frame(&frame?);
+        Ok(CallPreview::new(gas_estimate, diff?, trace))
     }
 
     /// Queries the blockchain via an `eth_call` without submitting a transaction to the network.
@@ -457,7 +529,7 @@ impl<T: Transport + Clone, P: Provider<T, N>, D: CallDecoder, N: Network> CallBu
             Some(state) => call.overrides(state),
             None => call,
         };
-        call.into()
+        EthCall::from(call).with_abi(self.abi.clone())
     }
 
     /// Decodes the output of a contract function using the provided decoder.
@@ -490,7 +562,10 @@ impl<T: Transport + Clone, P: Provider<T, N>, D: CallDecoder, N: Network> CallBu
     /// Returns a builder for configuring the pending transaction watcher.
     /// See [`Provider::send_transaction`] for more information.
     pub async fn send(&self) -> Result<PendingTransactionBuilder<'_, T, N>> {
-        Ok(self.provider.send_transaction(self.request.clone()).await?)
+        self.provider
+            .send_transaction(self.request.clone())
+            .await
+            .map_err(|e| self.map_err(e.into()))
     }
 
     /// Calculates the address that will be created by the transaction, if any.
@@ -500,6 +575,109 @@ impl<T: Transport + Clone, P: Provider<T, N>, D: CallDecoder, N: Network> CallBu
     pub fn calculate_create_address(&self) -> Option<Address> {
         self.request.calculate_create_address()
     }
+
+    /// Deploys the contract with `CREATE2` through the
+    /// [deterministic deployer](crate::deterministic::DETERMINISTIC_DEPLOYER), returning the
+    /// address of the deployed contract.
+    ///
+    /// The address only depends on the salt and the init code, see
+    /// [`calculate_create2_address`](Self::calculate_create2_address). The deployment is skipped
+    /// if the contract is deployed already.
+    ///
+    /// If `runtime_code_hash` is given, the hash of the code at the address is compared to it,
+    /// whether the contract was deployed or found, and [`Error::DeployedCodeMismatch`] is
+    /// returned if they differ.
+    pub async fn deploy_create2(
+        &self,
+        salt: B256,
+        runtime_code_hash: Option<B256>,
+    ) -> Result<Address> {
+        let init_code = self.init_code()?;
+        let address = deterministic::create2_address(salt, init_code);
+        let deploy = self
+            .request
+            .clone()
+            .with_to(deterministic::DETERMINISTIC_DEPLOYER)
+            .with_input(deterministic::create2_calldata(salt, init_code));
+        deterministic::deploy(
+            &self.provider,
+            deterministic::DETERMINISTIC_DEPLOYER,
+            deploy,
+            address,
+            runtime_code_hash,
+            true,
+        )
+        .await
+        .map_err(|e| self.map_err(e))
+    }
+
+    /// Deploys the contract with `CREATE3` through the
+    /// [CREATE3 factory](crate::deterministic::CREATE3_FACTORY), returning the address of the
+    /// deployed contract.
+    ///
+    /// The address only depends on the `from` address and the salt, see
+    /// [`calculate_create3_address`](Self::calculate_create3_address). The deployment is skipped
+    /// if a contract is deployed already.
+    ///
+    /// As the address does not depend on the init code, an existing contract may have been
+    /// deployed with another init code. It is therefore only accepted if its code hash matches
+    /// `runtime_code_hash`; without it, [`Error::UnverifiedCode`] is returned. The code of a newly
+    /// deployed contract is compared to `runtime_code_hash` as well, if given.
+    pub async fn deploy_create3(
+        &self,
+        salt: B256,
+        runtime_code_hash: Option<B256>,
+    ) -> Result<Address> {
+        let init_code = self.init_code()?;
+        let from = self.request.from().ok_or(Error::MissingFrom)?;
+        let deploy = self
+            .request
+            .clone()
+            .with_to(deterministic::CREATE3_FACTORY)
+            .with_input(deterministic::create3_calldata(salt, init_code));
+        let address = deterministic::create3_address(from, salt);
+        deterministic::deploy(
+            &self.provider,
+            deterministic::CREATE3_FACTORY,
+            deploy,
+            address,
+            runtime_code_hash,
+            false,
+        )
+        .await
+        .map_err(|e| self.map_err(e))
+    }
+
+    /// Calculates the address of the contract deployed with
+    /// [`deploy_create2`](Self::deploy_create2).
+    ///
+    /// Returns `None` if the transaction is not a contract creation.
+    pub fn calculate_create2_address(&self, salt: B256) -> Option<Address> {
+        Some(deterministic::create2_address(salt, self.init_code().ok()?))
+    }
+
+    /// Calculates the address of the contract deployed with
+    /// [`deploy_create3`](Self::deploy_create3).
+    ///
+    /// Returns `None` if the transaction is not a contract creation, or if the `from` field is not
+    /// set.
+    pub fn calculate_create3_address(&self, salt: B256) -> Option<Address> {
+        self.init_code().ok()?;
+        Some(deterministic::create3_address(self.request.from()?, salt))
+    }
+
+    /// Returns the init code of a deployment transaction.
+    fn init_code(&self) -> Result<&Bytes> {
+        if !self.request.kind().is_some_and(|to| to.is_create()) {
+            return Err(Error::NotADeploymentTransaction);
+        }
+        self.request.input().ok_or(Error::NotADeploymentTransaction)
+    }
+
+    /// Decodes reverts with the custom errors of the ABI, if any.
+    fn map_err(&self, e: Error) -> Error {
+        e.decode_with_abi(self.abi.as_deref())
+    }
 }
 
 impl<T: Transport, P: Clone, D, N: Network> CallBuilder<T, &P, D, N> {
@@ -511,6 +689,7 @@ impl<T: Transport, P: Clone, D, N: Network> CallBuilder<T, &P, D, N> {
             state: self.state,
             provider: self.provider.clone(),
             decoder: self.decoder,
+            abi: self.abi,
             transport: PhantomData,
         }
     }
@@ -561,7 +740,7 @@ impl<T, P, D: CallDecoder, N: Network> std::fmt::Debug for CallBuilder<T, P, D,
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy_primitives::{address, b256, bytes, hex, utils::parse_units, B256};
+    use alloy_primitives::{address, b256, bytes, hex, keccak256, utils::parse_units, B256};
     use alloy_provider::{
         layers::AnvilProvider, Provider, ProviderBuilder, RootProvider, WalletProvider,
     };
@@ -752,6 +931,87 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn deploy_create2() {
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+
+        let salt = B256::repeat_byte(1);
+        let deploy_builder = MyContract::deploy_builder(&provider, true);
+        let expected_address = deploy_builder.calculate_create2_address(salt).unwrap();
+        let address = deploy_builder.deploy_create2(salt, None).await.unwrap();
+        assert_eq!(address, expected_address);
+
+        let result = MyContract::new(address, &provider).myState().call().await.unwrap();
+        assert!(result.myState);
+
+        // Deploying again is a no-op, and verifies the existing code if asked to.
+        let block = provider.get_block_number().await.unwrap();
+        let code_hash = keccak256(provider.get_code_at(address).await.unwrap());
+        assert_eq!(deploy_builder.deploy_create2(salt, None).await.unwrap(), address);
+        assert_eq!(deploy_builder.deploy_create2(salt, Some(code_hash)).await.unwrap(), address);
+        let err = deploy_builder.deploy_create2(salt, Some(B256::ZERO)).await.unwrap_err();
+        assert!(matches!(err, Error::DeployedCodeMismatch(a) if a == address));
+        assert_eq!(provider.get_block_number().await.unwrap(), block);
+
+        // Other constructor arguments result in another address.
+        let other_builder = MyContract::deploy_builder(&provider, false);
+        assert_ne!(other_builder.calculate_create2_address(salt), Some(address));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn deploy_create3_without_factory() {
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+
+        // Anvil does not predeploy the CREATE3 factory.
+        let deploy_builder = MyContract::deploy_builder(&provider, true).from(from);
+        let err = deploy_builder.deploy_create3(B256::repeat_byte(1), None).await.unwrap_err();
+        assert!(
+            matches!(err, Error::MissingDeployer(address) if address == deterministic::CREATE3_FACTORY)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn deploy_create3_verifies_existing_code() {
+        use alloy_provider::ext::AnvilApi;
+
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+        let salt = B256::repeat_byte(1);
+
+        // The code at the address may come from any init code.
+        let deploy_builder = MyContract::deploy_builder(&provider, true).from(from);
+        let address = deploy_builder.calculate_create3_address(salt).unwrap();
+        let code = bytes!("6942");
+        provider.anvil_set_code(address, code.clone()).await.unwrap();
+
+        let err = deploy_builder.deploy_create3(salt, None).await.unwrap_err();
+        assert!(matches!(err, Error::UnverifiedCode(a) if a == address));
+        let err = deploy_builder.deploy_create3(salt, Some(B256::ZERO)).await.unwrap_err();
+        assert!(matches!(err, Error::DeployedCodeMismatch(a) if a == address));
+        assert_eq!(
+            deploy_builder.deploy_create3(salt, Some(keccak256(&code))).await.unwrap(),
+            address
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn preview() {
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+
+        let counter = Counter::deploy(&provider).await.unwrap();
+        let preview = counter.increment().preview().await.unwrap();
+        assert!(preview.is_success());
+        assert!(preview.gas_estimate.is_some_and(|gas| gas > 21000));
+
+        let changes = &preview.storage_changes[counter.address()];
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].before, changes[0].after), (B256::ZERO, B256::with_last_byte(1)));
+
+        // Nothing is sent.
+        assert_eq!(counter.counter().call().await.unwrap().counter, 0);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn deploy_and_call_with_priority() {
         let provider = ProviderBuilder::new().on_anvil();
@@ -0,0 +1,296 @@
+//! [EIP-3668] CCIP-Read: secure offchain data retrieval.
+//!
+//! Querying the gateways requires the `reqwest` feature. Without it, offchain lookups fail with
+//! [`CcipError::GatewayError`].
+//!
+//! [EIP-3668]: https://eips.ethereum.org/EIPS/eip-3668
+
+use alloy_network::{Network, TransactionBuilder};
+use alloy_primitives::{Address, Bytes};
+use alloy_provider::EthCall;
+use alloy_rpc_client::WeakClient;
+use alloy_rpc_types_eth::{state::StateOverride, BlockId};
+use alloy_sol_types::{sol, SolValue};
+use alloy_transport::{Transport, TransportError};
+
+sol! {
+    interface IOffchainLookup {
+        error OffchainLookup(
+            address sender,
+            string[] urls,
+            bytes callData,
+            bytes4 callbackFunction,
+            bytes extraData
+        );
+    }
+}
+
+use IOffchainLookup::{IOffchainLookupErrors, OffchainLookup};
+
+/// The default maximum number of offchain lookups performed by a single call.
+pub const MAX_CCIP_REDIRECTS: usize = 4;
+
+/// An error that occurred during a CCIP-Read offchain lookup.
+#[derive(Debug, thiserror::Error)]
+pub enum CcipError {
+    /// The `OffchainLookup` sender is not the called contract.
+    #[error("offchain lookup sender {sender} is not the called contract {to}")]
+    SenderMismatch {
+        /// The sender of the `OffchainLookup` error.
+        sender: Address,
+        /// The called contract.
+        to: Address,
+    },
+    /// The call reverted with `OffchainLookup` too many times.
+    #[error("too many offchain lookups, the limit is {0}")]
+    TooManyRedirects(usize),
+    /// No gateway returned a successful response.
+    #[error("offchain lookup failed: {0}")]
+    GatewayError(String),
+    /// An error occurred calling the contract.
+    #[error(transparent)]
+    TransportError(#[from] TransportError),
+}
+
+/// A gateway response.
+#[cfg(feature = "reqwest")]
+#[derive(serde::Deserialize)]
+struct GatewayResponse {
+    data: Option<Bytes>,
+    message: Option<String>,
+}
+
+/// An `eth_call` following `OffchainLookup` reverts.
+///
+/// Every lookup queries the gateways, then calls the callback function of the contract with the
+/// gateway response, until the contract returns without reverting with `OffchainLookup`.
+pub(crate) struct CcipCall<T, N: Network> {
+    client: WeakClient<T>,
+    tx: N::TransactionRequest,
+    block: Option<BlockId>,
+    overrides: Option<StateOverride>,
+    max_redirects: usize,
+}
+
+impl<T, N> CcipCall<T, N>
+where
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Creates a new CCIP-Read call from the `eth_call` request.
+    pub(crate) fn new(call: &EthCall<'_, '_, T, N, Bytes>, max_redirects: usize) -> Self {
+        Self {
+            client: call.client().clone(),
+            tx: call.data().clone(),
+            block: call.block_id(),
+            overrides: call.state_overrides().cloned(),
+            max_redirects,
+        }
+    }
+
+    /// Performs the call, following offchain lookups.
+    pub(crate) async fn run(mut self) -> Result<Bytes, CcipError> {
+        let to = self.tx.to().unwrap_or_default();
+        let gateways = Gateways::default();
+        for _ in 0..=self.max_redirects {
+            let mut call = EthCall::<T, N, Bytes>::new(self.client.clone(), &self.tx);
+            if let Some(block) = self.block {
+                call = call.block(block);
+            }
+            if let Some(overrides) = &self.overrides {
+                call = call.overrides(overrides);
+            }
+            let err = match call.await {
+                Ok(output) => return Ok(output),
+                Err(err) => err,
+            };
+            let Some(IOffchainLookupErrors::OffchainLookup(lookup)) = err
+                .as_error_resp()
+                .and_then(|payload| payload.as_decoded_error::<IOffchainLookupErrors>(false))
+            else {
+                return Err(err.into());
+            };
+            if lookup.sender != to {
+                return Err(CcipError::SenderMismatch { sender: lookup.sender, to });
+            }
+
+            let response = gateways.fetch(&lookup).await?;
+            let mut input = lookup.callbackFunction.to_vec();
+            input.extend((response, lookup.extraData).abi_encode_params());
+            self.tx.set_input(input);
+        }
+        Err(CcipError::TooManyRedirects(self.max_redirects))
+    }
+}
+
+/// The HTTP client querying the gateways.
+#[derive(Default)]
+struct Gateways {
+    #[cfg(feature = "reqwest")]
+    client: reqwest::Client,
+}
+
+impl Gateways {
+    #[cfg(feature = "reqwest")]
+    async fn fetch(&self, lookup: &OffchainLookup) -> Result<Bytes, CcipError> {
+        fetch(&self.client, lookup).await
+    }
+
+    #[cfg(not(feature = "reqwest"))]
+    async fn fetch(&self, _lookup: &OffchainLookup) -> Result<Bytes, CcipError> {
+        Err(CcipError::GatewayError("querying gateways requires the `reqwest` feature".into()))
+    }
+}
+
+/// Queries the gateways of the lookup in order, returning the first successful response.
+///
+/// URLs containing `{data}` are queried with `GET`, after substituting `{sender}` and `{data}`.
+/// Other URLs are queried with `POST`, with a JSON body containing `data` and `sender`. A client
+/// error stops the lookup, while a server or network error moves on to the next gateway.
+#[cfg(feature = "reqwest")]
+async fn fetch(client: &reqwest::Client, lookup: &OffchainLookup) -> Result<Bytes, CcipError> {
+    let sender = lookup.sender.to_string().to_lowercase();
+    let data = alloy_primitives::hex::encode_prefixed(&lookup.callData);
+
+    let mut error = String::from("no gateway URLs");
+    for url in &lookup.urls {
+        let request = if url.contains("{data}") {
+            client.get(url.replace("{sender}", &sender).replace("{data}", &data))
+        } else {
+            let body = serde_json::json!({ "data": data, "sender": sender });
+            client.post(url.replace("{sender}", &sender)).json(&body)
+        };
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(err) => {
+                error = format!("{url}: {err}");
+                continue;
+            }
+        };
+        let status = response.status();
+        let body = response.json::<GatewayResponse>().await;
+
+        if status.is_success() {
+            match body {
+                Ok(GatewayResponse { data: Some(data), .. }) => return Ok(data),
+                Ok(_) => error = format!("{url}: missing response data"),
+                Err(err) => error = format!("{url}: {err}"),
+            }
+        } else {
+            let message = body.ok().and_then(|body| body.message);
+            error = format!("{url}: {status}: {}", message.unwrap_or_default());
+            if status.is_client_error() {
+                break;
+            }
+        }
+    }
+    Err(CcipError::GatewayError(error))
+}
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, bytes};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    /// Serves the responses to consecutive requests, returning the received requests.
+    async fn gateway(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, content)) = text.split_once("\r\n\r\n") {
+                        let len = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_lowercase()
+                                    .strip_prefix("content-length: ")
+                                    .map(|len| len.parse().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if content.len() >= len {
+                            break;
+                        }
+                    }
+                }
+                requests.push(String::from_utf8(request).unwrap());
+                let response = format!(
+                    "HTTP/1.1 {status} Status\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    fn lookup(urls: Vec<String>) -> OffchainLookup {
+        OffchainLookup {
+            sender: address!("00000000000000000000000000000000000000aA"),
+            urls,
+            callData: bytes!("1234"),
+            callbackFunction: Default::default(),
+            extraData: Bytes::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_with_get() {
+        let (url, server) = gateway(vec![(200, r#"{"data":"0xabcd"}"#)]).await;
+        let lookup = lookup(vec![format!("{url}/{{sender}}/{{data}}.json")]);
+
+        let data = fetch(&reqwest::Client::new(), &lookup).await.unwrap();
+        assert_eq!(data, bytes!("abcd"));
+
+        let requests = server.await.unwrap();
+        assert!(requests[0]
+            .starts_with("GET /0x00000000000000000000000000000000000000aa/0x1234.json HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn fetches_with_post_after_server_error() {
+        let (url, server) = gateway(vec![(500, "{}"), (200, r#"{"data":"0xabcd"}"#)]).await;
+        let lookup = lookup(vec![url.clone(), format!("{url}/{{sender}}")]);
+
+        let data = fetch(&reqwest::Client::new(), &lookup).await.unwrap();
+        assert_eq!(data, bytes!("abcd"));
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("POST / HTTP/1.1"));
+        assert!(
+            requests[1].starts_with("POST /0x00000000000000000000000000000000000000aa HTTP/1.1")
+        );
+        let body: serde_json::Value =
+            serde_json::from_str(requests[1].split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "data": "0x1234",
+                "sender": "0x00000000000000000000000000000000000000aa"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_on_client_error() {
+        let (url, server) = gateway(vec![(404, r#"{"message":"not found"}"#)]).await;
+        let lookup = lookup(vec![url.clone(), url]);
+
+        let err = fetch(&reqwest::Client::new(), &lookup).await.unwrap_err();
+        assert!(matches!(err, CcipError::GatewayError(message) if message.contains("not found")));
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+}
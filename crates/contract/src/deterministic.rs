@@ -0,0 +1,151 @@
+//! Deterministic contract deployments, at addresses that are identical on every chain.
+//!
+//! Contracts are deployed with `CREATE2` through the canonical [deterministic deployment proxy],
+//! or with `CREATE3` through the [CREATE3 factory], see [`CallBuilder::deploy_create2`] and
+//! [`CallBuilder::deploy_create3`].
+//!
+//! [deterministic deployment proxy]: https://github.com/Arachnid/deterministic-deployment-proxy
+//! [CREATE3 factory]: https://github.com/ZeframLou/create3-factory
+//! [`CallBuilder::deploy_create2`]: crate::CallBuilder::deploy_create2
+//! [`CallBuilder::deploy_create3`]: crate::CallBuilder::deploy_create3
+
+use crate::{Error, Result};
+use alloy_network::{Network, ReceiptResponse};
+use alloy_primitives::{address, b256, keccak256, Address, Bytes, B256};
+use alloy_provider::Provider;
+use alloy_transport::Transport;
+
+/// The address of the canonical deterministic deployment proxy.
+///
+/// The proxy deploys the init code following the 32-byte salt of the calldata with `CREATE2`.
+pub const DETERMINISTIC_DEPLOYER: Address = address!("4e59b44847b379578588920ca78fbf26c0b4956c");
+
+/// The address of the CREATE3 factory.
+pub const CREATE3_FACTORY: Address = address!("9fbb3df7c40da2e5a0de984ffe2ccb7c47cd0abf");
+
+/// The hash of the init code of the proxy deployed by the CREATE3 factory, which deploys its
+/// calldata with `CREATE`.
+const CREATE3_PROXY_INIT_CODE_HASH: B256 =
+    b256!("21c35dbe1b344a2488cf3321d6ce542f8e9f305544ff09e4993a62319a497c1f");
+
+mod abi {
+    alloy_sol_types::sol! {
+        interface ICreate3Factory {
+            function deploy(bytes32 salt, bytes memory creationCode)
+                external
+                payable
+                returns (address deployed);
+        }
+    }
+}
+
+/// Computes the address of a contract deployed with `CREATE2` through the
+/// [deterministic deployer](DETERMINISTIC_DEPLOYER).
+pub fn create2_address(salt: B256, init_code: &[u8]) -> Address {
+    DETERMINISTIC_DEPLOYER.create2_from_code(salt, init_code)
+}
+
+/// Computes the address of a contract deployed by `deployer` with `CREATE3` through the
+/// [CREATE3 factory](CREATE3_FACTORY).
+///
+/// The address only depends on the deployer and the salt, not on the init code. The factory
+/// namespaces salts by deployer, so that deployers cannot take each other's addresses.
+pub fn create3_address(deployer: Address, salt: B256) -> Address {
+    create3_proxy(deployer, salt).create(1)
+}
+
+/// Computes the address of the proxy deploying the contract of [`create3_address`].
+fn create3_proxy(deployer: Address, salt: B256) -> Address {
+    let salt = keccak256([deployer.as_slice(), salt.as_slice()].concat());
+    CREATE3_FACTORY.create2(salt, CREATE3_PROXY_INIT_CODE_HASH)
+}
+
+/// Returns the calldata of the deterministic deployer for the salt and init code.
+pub(crate) fn create2_calldata(salt: B256, init_code: &[u8]) -> Bytes {
+    [salt.as_slice(), init_code].concat().into()
+}
+
+/// Returns the calldata of the CREATE3 factory for the salt and init code.
+pub(crate) fn create3_calldata(salt: B256, init_code: &Bytes) -> Bytes {
+    use alloy_sol_types::SolCall;
+
+    abi::ICreate3Factory::deployCall { salt, creationCode: init_code.clone() }.abi_encode().into()
+}
+
+/// Deploys a contract at its precomputed deterministic address.
+///
+/// `deploy` is the transaction to `deployer`, the deterministic deployer or CREATE3 factory, that
+/// creates the contract at `address`. The deployment is skipped if code exists at the address
+/// already.
+///
+/// The runtime code of contracts with immutables depending on their address or on the block, such
+/// as EIP-712 domain separators, cannot be computed from the init code. The caller may therefore
+/// pass the hash of the expected runtime code, which is compared to the code at the address
+/// whether it was deployed or found. Without it, existing code is only accepted if
+/// `address_binds_init_code` is set, i.e. if the address commits to the init code as with
+/// `CREATE2`.
+pub(crate) async fn deploy<T, P, N>(
+    provider: &P,
+    deployer: Address,
+    deploy: N::TransactionRequest,
+    address: Address,
+    runtime_code_hash: Option<B256>,
+    address_binds_init_code: bool,
+) -> Result<Address>
+where
+    T: Transport + Clone,
+    P: Provider<T, N>,
+    N: Network,
+{
+    let verify = |code: &Bytes| match runtime_code_hash {
+        Some(hash) if keccak256(code) != hash => Err(Error::DeployedCodeMismatch(address)),
+        _ => Ok(address),
+    };
+
+    let code = provider.get_code_at(address).await?;
+    if !code.is_empty() {
+        if runtime_code_hash.is_none() && !address_binds_init_code {
+            return Err(Error::UnverifiedCode(address));
+        }
+        return verify(&code);
+    }
+    if provider.get_code_at(deployer).await?.is_empty() {
+        return Err(Error::MissingDeployer(deployer));
+    }
+
+    let receipt = provider.send_transaction(deploy).await?.get_receipt().await?;
+    let code = provider.get_code_at(address).await?;
+    if !receipt.status() || code.is_empty() {
+        return Err(Error::ContractNotDeployed);
+    }
+    verify(&code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{bytes, hex};
+
+    #[test]
+    fn create3_proxy_init_code_hash() {
+        let init_code = hex!("67363d3d37363d34f03d5260086018f3");
+        assert_eq!(keccak256(init_code), CREATE3_PROXY_INIT_CODE_HASH);
+    }
+
+    #[test]
+    fn computes_deterministic_addresses() {
+        let salt = B256::repeat_byte(1);
+        let init_code = bytes!("6080604052");
+        assert_eq!(
+            create2_address(salt, &init_code),
+            DETERMINISTIC_DEPLOYER.create2(salt, keccak256(&init_code))
+        );
+        assert_eq!(create2_calldata(salt, &init_code).len(), 32 + init_code.len());
+
+        let alice = Address::repeat_byte(0xaa);
+        let bob = Address::repeat_byte(0xbb);
+        assert_eq!(create3_address(alice, salt), create3_address(alice, salt));
+        assert_ne!(create3_address(alice, salt), create3_address(bob, salt));
+        assert_ne!(create3_address(alice, salt), create3_address(alice, B256::ZERO));
+    }
+}
@@ -0,0 +1,301 @@
+use crate::Error;
+use alloy_dyn_abi::{DecodedEvent, EventExt};
+use alloy_json_abi::Event as JsonEvent;
+use alloy_network::Ethereum;
+use alloy_primitives::{Address, B256};
+use alloy_provider::{FilterPollerBuilder, Network, Provider};
+use alloy_rpc_types_eth::{BlockNumberOrTag, Filter, FilterBlockOption, Log, Topic, ValueOrArray};
+use alloy_transport::{Transport, TransportResult};
+use futures::Stream;
+use futures_util::StreamExt;
+use std::{fmt, marker::PhantomData};
+
+/// Helper for managing the filter of an event known only at runtime, before querying or streaming
+/// its logs.
+///
+/// This is the dynamic counterpart of [`Event`](crate::Event): logs are decoded according to a
+/// [`JsonEvent`] into [`DecodedEvent`]s, which hold the indexed and body values separately.
+#[must_use = "event filters do nothing unless you `query`, `watch`, or `stream` them"]
+pub struct DynEvent<T, P, N = Ethereum> {
+    /// The provider to use for querying or streaming logs.
+    pub provider: P,
+    /// The filter to use for querying or streaming logs.
+    pub filter: Filter,
+    event: JsonEvent,
+    _phantom: PhantomData<(T, N)>,
+}
+
+impl<T, P: fmt::Debug, N> fmt::Debug for DynEvent<T, P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynEvent")
+            .field("provider", &self.provider)
+            .field("filter", &self.filter)
+            .field("event", &format_args!("{}", self.event.signature()))
+            .finish()
+    }
+}
+
+impl<T: Transport + Clone, P: Provider<T, N>, N: Network> DynEvent<T, P, N> {
+    /// Creates a new event with the provided provider, filter and event definition.
+    ///
+    /// The event signature (`topic0`) of non-anonymous events is added to the filter.
+    pub fn new(provider: P, filter: Filter, event: JsonEvent) -> Self {
+        let filter =
+            if event.anonymous { filter } else { filter.event_signature(event.selector()) };
+        Self { provider, filter, event, _phantom: PhantomData }
+    }
+
+    /// Returns the event definition used to decode the logs.
+    pub const fn event(&self) -> &JsonEvent {
+        &self.event
+    }
+
+    /// Queries the blockchain for the selected filter and returns a vector of matching event logs.
+    pub async fn query(&self) -> Result<Vec<(DecodedEvent, Log)>, Error> {
+        let logs = self.query_raw().await?;
+        logs.into_iter().map(|log| Ok((decode_log(&self.event, &log)?, log))).collect()
+    }
+
+    /// Queries the blockchain for the selected filter and returns a vector of matching event logs,
+    /// without decoding them.
+    pub async fn query_raw(&self) -> TransportResult<Vec<Log>> {
+        self.provider.get_logs(&self.filter).await
+    }
+
+    /// Watches for events that match the filter.
+    ///
+    /// Returns a stream of decoded events and raw logs.
+    #[doc(alias = "stream")]
+    pub async fn watch(&self) -> TransportResult<DynEventPoller<T>> {
+        let poller = self.provider.watch_logs(&self.filter).await?;
+        Ok(DynEventPoller { poller, event: self.event.clone() })
+    }
+
+    /// Subscribes to the stream of events that match the filter.
+    ///
+    /// Returns a stream of decoded events and raw logs.
+    #[cfg(feature = "pubsub")]
+    pub async fn subscribe(&self) -> TransportResult<subscription::DynEventSubscription> {
+        let sub = self.provider.subscribe_logs(&self.filter).await?;
+        Ok(subscription::DynEventSubscription { sub, event: self.event.clone() })
+    }
+
+    /// Sets the inner filter object
+    ///
+    /// See [`Filter::select`].
+    pub fn select(mut self, filter: impl Into<FilterBlockOption>) -> Self {
+        self.filter.block_option = filter.into();
+        self
+    }
+
+    /// Sets the from block number
+    pub fn from_block<B: Into<BlockNumberOrTag>>(mut self, block: B) -> Self {
+        self.filter.block_option = self.filter.block_option.with_from_block(block.into());
+        self
+    }
+
+    /// Sets the to block number
+    pub fn to_block<B: Into<BlockNumberOrTag>>(mut self, block: B) -> Self {
+        self.filter.block_option = self.filter.block_option.with_to_block(block.into());
+        self
+    }
+
+    /// Pins the block hash for the filter
+    pub fn at_block_hash<A: Into<B256>>(mut self, hash: A) -> Self {
+        self.filter.block_option = self.filter.block_option.with_block_hash(hash.into());
+        self
+    }
+
+    /// Sets the address to query with this filter.
+    ///
+    /// See [`Filter::address`].
+    pub fn address<A: Into<ValueOrArray<Address>>>(mut self, address: A) -> Self {
+        self.filter.address = address.into().into();
+        self
+    }
+
+    /// Sets the 1st indexed topic
+    pub fn topic1<TO: Into<Topic>>(mut self, topic: TO) -> Self {
+        self.filter.topics[1] = topic.into();
+        self
+    }
+
+    /// Sets the 2nd indexed topic
+    pub fn topic2<TO: Into<Topic>>(mut self, topic: TO) -> Self {
+        self.filter.topics[2] = topic.into();
+        self
+    }
+
+    /// Sets the 3rd indexed topic
+    pub fn topic3<TO: Into<Topic>>(mut self, topic: TO) -> Self {
+        self.filter.topics[3] = topic.into();
+        self
+    }
+}
+
+impl<T, P: Clone, N> DynEvent<T, &P, N> {
+    /// Clones the provider and returns a new event with the cloned provider.
+    pub fn with_cloned_provider(self) -> DynEvent<T, P, N> {
+        DynEvent {
+            provider: self.provider.clone(),
+            filter: self.filter,
+            event: self.event,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A dynamic event poller.
+///
+/// Polling configuration is available through the [`poller`](Self::poller) field.
+pub struct DynEventPoller<T> {
+    /// The inner poller.
+    pub poller: FilterPollerBuilder<T, Log>,
+    event: JsonEvent,
+}
+
+impl<T> AsRef<FilterPollerBuilder<T, Log>> for DynEventPoller<T> {
+    #[inline]
+    fn as_ref(&self) -> &FilterPollerBuilder<T, Log> {
+        &self.poller
+    }
+}
+
+impl<T> AsMut<FilterPollerBuilder<T, Log>> for DynEventPoller<T> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut FilterPollerBuilder<T, Log> {
+        &mut self.poller
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DynEventPoller<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynEventPoller")
+            .field("poller", &self.poller)
+            .field("event", &format_args!("{}", self.event.signature()))
+            .finish()
+    }
+}
+
+impl<T: Transport + Clone> DynEventPoller<T> {
+    /// Starts the poller and returns a stream that yields the decoded event and the raw log.
+    ///
+    /// Note that this stream will not return `None` until the provider is dropped.
+    pub fn into_stream(
+        self,
+    ) -> impl Stream<Item = alloy_dyn_abi::Result<(DecodedEvent, Log)>> + Unpin {
+        let event = self.event;
+        self.poller
+            .into_stream()
+            .flat_map(futures_util::stream::iter)
+            .map(move |log| decode_log(&event, &log).map(|e| (e, log)))
+    }
+}
+
+fn decode_log(event: &JsonEvent, log: &Log) -> alloy_dyn_abi::Result<DecodedEvent> {
+    event.decode_log(log.as_ref(), false)
+}
+
+#[cfg(feature = "pubsub")]
+pub(crate) mod subscription {
+    use super::*;
+    use alloy_pubsub::Subscription;
+
+    /// A dynamic event subscription.
+    ///
+    /// Underlying subscription is available through the [`sub`](Self::sub) field.
+    pub struct DynEventSubscription {
+        /// The inner subscription.
+        pub sub: Subscription<Log>,
+        pub(super) event: JsonEvent,
+    }
+
+    impl AsRef<Subscription<Log>> for DynEventSubscription {
+        #[inline]
+        fn as_ref(&self) -> &Subscription<Log> {
+            &self.sub
+        }
+    }
+
+    impl AsMut<Subscription<Log>> for DynEventSubscription {
+        #[inline]
+        fn as_mut(&mut self) -> &mut Subscription<Log> {
+            &mut self.sub
+        }
+    }
+
+    impl fmt::Debug for DynEventSubscription {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("DynEventSubscription")
+                .field("sub", &self.sub)
+                .field("event", &format_args!("{}", self.event.signature()))
+                .finish()
+        }
+    }
+
+    impl DynEventSubscription {
+        /// Converts the subscription into a stream.
+        pub fn into_stream(
+            self,
+        ) -> impl Stream<Item = alloy_dyn_abi::Result<(DecodedEvent, Log)>> + Unpin {
+            let event = self.event;
+            self.sub.into_stream().map(move |log| decode_log(&event, &log).map(|e| (e, log)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{event::tests::MyContract, ContractInstance, Interface};
+    use alloy_dyn_abi::DynSolValue;
+    use alloy_json_abi::JsonAbi;
+    use alloy_primitives::{b256, U256};
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn dyn_event_filters() {
+        let anvil = alloy_node_bindings::Anvil::new().spawn();
+        let provider = alloy_provider::ProviderBuilder::new().on_http(anvil.endpoint_url());
+
+        let contract = MyContract::deploy(&provider).await.unwrap();
+        let abi = JsonAbi::parse([
+            "event MyEvent(uint64 indexed, string, bool, bytes32)",
+            "event WrongEvent(uint64 indexed, string, bool, bytes32)",
+        ])
+        .unwrap();
+        let instance: ContractInstance<_, _, _> =
+            ContractInstance::new(*contract.address(), &provider, Interface::new(abi));
+
+        let event = instance.dyn_event("MyEvent").unwrap();
+        let poller = event.watch().await.unwrap();
+
+        contract.doEmit().send().await.unwrap().get_receipt().await.unwrap();
+        contract.doEmitWrongEvent().send().await.unwrap().get_receipt().await.unwrap();
+
+        let expected_indexed = vec![DynSolValue::Uint(U256::from(42), 64)];
+        let expected_body = vec![
+            DynSolValue::String("hello".into()),
+            DynSolValue::Bool(true),
+            DynSolValue::FixedBytes(
+                b256!("00000000000000000000000000000000000000000000000000000000deadbeef"),
+                32,
+            ),
+        ];
+
+        let mut stream = poller.into_stream();
+        let (decoded, log) = stream.next().await.unwrap().unwrap();
+        assert_eq!(decoded.indexed, expected_indexed);
+        assert_eq!(decoded.body, expected_body);
+        assert_eq!(log.address(), *contract.address());
+
+        let all = event.query().await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].0, decoded);
+
+        // Logs of any event of the ABI are decoded by the interface.
+        let logs = instance.dyn_event("WrongEvent").unwrap().query_raw().await.unwrap();
+        let (wrong_event, decoded) = instance.decode_log(&logs[0]).unwrap();
+        assert_eq!(wrong_event.name, "WrongEvent");
+        assert_eq!(decoded.body, expected_body);
+    }
+}
@@ -0,0 +1,390 @@
+//! [ENS] name resolution.
+//!
+//! Names are expected to be normalized according to [ENSIP-15].
+//!
+//! [ENS]: https://docs.ens.domains
+//! [ENSIP-15]: https://docs.ens.domains/ensip/15
+
+use crate::{
+    ccip::{CcipCall, MAX_CCIP_REDIRECTS},
+    Error, SolCallBuilder,
+};
+use alloy_network::{Ethereum, Network, TransactionBuilder};
+use alloy_primitives::{address, fixed_bytes, keccak256, Address, Bytes, FixedBytes, B256};
+use alloy_provider::Provider;
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+/// The address of the ENS registry on Ethereum mainnet and testnets.
+pub const ENS_REGISTRY: Address = address!("00000000000C2E074eC69A0dFb2997BA6C7d2e1e");
+
+/// The [ENSIP-10] extended resolver interface ID.
+///
+/// [ENSIP-10]: https://docs.ens.domains/ensip/10
+pub const EXTENDED_RESOLVER_INTERFACE_ID: FixedBytes<4> = fixed_bytes!("9061b923");
+
+/// The suffix of reverse resolution names.
+const REVERSE_SUFFIX: &str = "addr.reverse";
+
+mod abi {
+    use alloy_sol_types::sol;
+
+    sol! {
+        interface IEnsRegistry {
+            function resolver(bytes32 node) external view returns (address);
+        }
+
+        interface IEnsResolver {
+            function addr(bytes32 node) external view returns (address);
+            function name(bytes32 node) external view returns (string);
+            function text(bytes32 node, string key) external view returns (string);
+            function supportsInterface(bytes4 interfaceId) external view returns (bool);
+            function resolve(bytes name, bytes data) external view returns (bytes);
+        }
+    }
+}
+
+use abi::{IEnsRegistry, IEnsResolver};
+
+/// An error that occurred while resolving an ENS name.
+#[derive(Debug, thiserror::Error)]
+pub enum EnsError {
+    /// The name is not a valid DNS name.
+    #[error("invalid name: {0}")]
+    InvalidName(String),
+    /// No resolver is set for the name or any of its parents.
+    #[error("no resolver found for {0}")]
+    ResolverNotFound(String),
+    /// The name does not resolve to an address.
+    #[error("{0} does not resolve to an address")]
+    AddressNotFound(String),
+    /// No primary name is set for the address.
+    #[error("no name found for {0}")]
+    NameNotFound(Address),
+    /// The primary name of the address does not resolve back to the address.
+    #[error("{name} does not resolve back to {address}")]
+    ReverseMismatch {
+        /// The address.
+        address: Address,
+        /// The primary name of the address.
+        name: String,
+    },
+    /// An error occurred calling the registry or a resolver.
+    #[error(transparent)]
+    Contract(#[from] Error),
+}
+
+impl From<alloy_sol_types::Error> for EnsError {
+    #[inline]
+    fn from(e: alloy_sol_types::Error) -> Self {
+        Self::Contract(e.into())
+    }
+}
+
+/// Computes the [namehash] of the name.
+///
+/// [namehash]: https://docs.ens.domains/resolution/names#namehash
+pub fn namehash(name: &str) -> B256 {
+    if name.is_empty() {
+        return B256::ZERO;
+    }
+    name.rsplit('.').fold(B256::ZERO, |node, label| {
+        keccak256([node.as_slice(), keccak256(label).as_slice()].concat())
+    })
+}
+
+/// Encodes the name in the DNS wire format, as used by [ENSIP-10] wildcard resolution.
+///
+/// [ENSIP-10]: https://docs.ens.domains/ensip/10
+pub fn dns_encode(name: &str) -> Result<Bytes, EnsError> {
+    let mut encoded = Vec::with_capacity(name.len() + 2);
+    if !name.is_empty() {
+        for label in name.split('.') {
+            let len = u8::try_from(label.len())
+                .ok()
+                .filter(|len| *len > 0)
+                .ok_or_else(|| EnsError::InvalidName(name.to_string()))?;
+            encoded.push(len);
+            encoded.extend_from_slice(label.as_bytes());
+        }
+    }
+    encoded.push(0);
+    Ok(encoded.into())
+}
+
+/// Returns the reverse resolution name of the address, `<address>.addr.reverse`.
+pub fn reverse_name(address: Address) -> String {
+    format!("{:x}.{REVERSE_SUFFIX}", address)
+}
+
+/// An ENS name or an address.
+///
+/// Parsing a string yields an address if it is a hex address, and a name otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameOrAddress {
+    /// An ENS name.
+    Name(String),
+    /// An address.
+    Address(Address),
+}
+
+impl NameOrAddress {
+    /// Resolves the name to an address, or returns the address.
+    pub async fn resolve<P, T, N>(&self, ens: &Ens<P, T, N>) -> Result<Address, EnsError>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        match self {
+            Self::Name(name) => ens.resolve_name(name).await,
+            Self::Address(address) => Ok(*address),
+        }
+    }
+}
+
+impl From<Address> for NameOrAddress {
+    fn from(address: Address) -> Self {
+        Self::Address(address)
+    }
+}
+
+impl From<String> for NameOrAddress {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl FromStr for NameOrAddress {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse().map_or_else(|_| Self::Name(s.to_string()), Self::Address))
+    }
+}
+
+impl fmt::Display for NameOrAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => f.write_str(name),
+            Self::Address(address) => address.fmt(f),
+        }
+    }
+}
+
+/// An ENS client, resolving names through the registry and their resolvers.
+///
+/// Resolution supports [ENSIP-10] wildcard resolvers, and [EIP-3668] CCIP-Read offchain lookups.
+///
+/// [ENSIP-10]: https://docs.ens.domains/ensip/10
+/// [EIP-3668]: https://eips.ethereum.org/EIPS/eip-3668
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn test(url: reqwest::Url) -> Result<(), Box<dyn std::error::Error>> {
+/// use alloy_contract::ens::Ens;
+/// use alloy_provider::ProviderBuilder;
+///
+/// let ens = Ens::new(ProviderBuilder::new().on_http(url));
+/// let address = ens.resolve_name("vitalik.eth").await?;
+/// let name = ens.lookup_address(address).await?;
+/// let avatar = ens.text("vitalik.eth", "avatar").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Ens<P, T, N = Ethereum> {
+    provider: P,
+    registry: Address,
+    _pd: PhantomData<fn() -> (T, N)>,
+}
+
+impl<P, T, N> Ens<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Creates a new ENS client using the [mainnet registry](ENS_REGISTRY).
+    pub const fn new(provider: P) -> Self {
+        Self::with_registry(provider, ENS_REGISTRY)
+    }
+
+    /// Creates a new ENS client using the given registry.
+    pub const fn with_registry(provider: P, registry: Address) -> Self {
+        Self { provider, registry, _pd: PhantomData }
+    }
+
+    /// Returns a reference to the provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Returns the address of the registry.
+    pub const fn registry(&self) -> Address {
+        self.registry
+    }
+
+    /// Resolves the name to an address.
+    pub async fn resolve_name(&self, name: &str) -> Result<Address, EnsError> {
+        let call = IEnsResolver::addrCall { node: namehash(name) };
+        let address = self.resolve(name, call).await?._0;
+        if address.is_zero() {
+            return Err(EnsError::AddressNotFound(name.to_string()));
+        }
+        Ok(address)
+    }
+
+    /// Looks up the primary name of the address.
+    ///
+    /// The name is verified to resolve back to the address.
+    pub async fn lookup_address(&self, address: Address) -> Result<String, EnsError> {
+        let reverse = reverse_name(address);
+        let call = IEnsResolver::nameCall { node: namehash(&reverse) };
+        let name = match self.resolve(&reverse, call).await {
+            Ok(name) if !name._0.is_empty() => name._0,
+            Ok(_) | Err(EnsError::ResolverNotFound(_)) => {
+                return Err(EnsError::NameNotFound(address))
+            }
+            Err(err) => return Err(err),
+        };
+
+        match self.resolve_name(&name).await {
+            Ok(resolved) if resolved == address => Ok(name),
+            Ok(_) | Err(EnsError::AddressNotFound(_)) => {
+                Err(EnsError::ReverseMismatch { address, name })
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the text record of the name with the given key, e.g. `avatar` or `url`.
+    pub async fn text(&self, name: &str, key: &str) -> Result<String, EnsError> {
+        let call = IEnsResolver::textCall { node: namehash(name), key: key.to_string() };
+        Ok(self.resolve(name, call).await?._0)
+    }
+
+    /// Sets the recipient of the transaction request, resolving it if it is a name.
+    pub async fn fill_to(
+        &self,
+        request: &mut N::TransactionRequest,
+        to: &NameOrAddress,
+    ) -> Result<(), EnsError> {
+        request.set_to(to.resolve(self).await?);
+        Ok(())
+    }
+
+    /// Returns the resolver of the name.
+    ///
+    /// If the name has no resolver, its parents are searched for a wildcard resolver supporting
+    /// the [ENSIP-10] extended resolver interface.
+    ///
+    /// [ENSIP-10]: https://docs.ens.domains/ensip/10
+    pub async fn resolver(&self, name: &str) -> Result<Address, EnsError> {
+        let not_found = || EnsError::ResolverNotFound(name.to_string());
+        let mut current = name;
+        loop {
+            let call = IEnsRegistry::resolverCall { node: namehash(current) };
+            let resolver =
+                SolCallBuilder::new_sol(&self.provider, &self.registry, &call).call().await?._0;
+            if !resolver.is_zero() {
+                if current != name && !self.supports_extended_resolver(resolver).await? {
+                    return Err(not_found());
+                }
+                return Ok(resolver);
+            }
+            current = match current.split_once('.') {
+                Some((_, parent)) if !parent.is_empty() => parent,
+                _ => return Err(not_found()),
+            };
+        }
+    }
+
+    /// Calls the resolver of the name, through `resolve(bytes,bytes)` if it is an extended
+    /// resolver.
+    async fn resolve<C: SolCall>(&self, name: &str, call: C) -> Result<C::Return, EnsError> {
+        let resolver = self.resolver(name).await?;
+        let output = if self.supports_extended_resolver(resolver).await? {
+            let resolve = IEnsResolver::resolveCall {
+                name: dns_encode(name)?,
+                data: call.abi_encode().into(),
+            };
+            let output = self.call(resolver, resolve.abi_encode()).await?;
+            IEnsResolver::resolveCall::abi_decode_returns(&output, true)?._0
+        } else {
+            self.call(resolver, call.abi_encode()).await?
+        };
+        Ok(C::abi_decode_returns(&output, true)?)
+    }
+
+    /// Returns whether the resolver supports the extended resolver interface.
+    ///
+    /// Resolvers reverting on `supportsInterface` do not support it.
+    async fn supports_extended_resolver(&self, resolver: Address) -> Result<bool, EnsError> {
+        let call =
+            IEnsResolver::supportsInterfaceCall { interfaceId: EXTENDED_RESOLVER_INTERFACE_ID };
+        match SolCallBuilder::new_sol(&self.provider, &resolver, &call).call().await {
+            Ok(supported) => Ok(supported._0),
+            Err(Error::Revert(_)) => Ok(false),
+            Err(Error::TransportError(err)) if err.is_error_resp() => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Calls the resolver, following offchain lookups.
+    async fn call(&self, resolver: Address, input: Vec<u8>) -> Result<Bytes, EnsError> {
+        let tx = N::TransactionRequest::default().with_to(resolver).with_input(input);
+        CcipCall::new(&self.provider.call(&tx), MAX_CCIP_REDIRECTS)
+            .run()
+            .await
+            .map_err(|e| Error::from(e).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, bytes};
+
+    #[test]
+    fn computes_namehash() {
+        assert_eq!(namehash(""), B256::ZERO);
+        assert_eq!(
+            namehash("eth"),
+            b256!("93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae")
+        );
+        assert_eq!(
+            namehash("foo.eth"),
+            b256!("de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f")
+        );
+    }
+
+    #[test]
+    fn encodes_dns_names() {
+        assert_eq!(dns_encode("vitalik.eth").unwrap(), bytes!("07766974616c696b0365746800"));
+        assert_eq!(dns_encode("").unwrap(), bytes!("00"));
+        assert!(matches!(dns_encode("foo..eth"), Err(EnsError::InvalidName(_))));
+        assert!(dns_encode(&format!("{}.eth", "a".repeat(256))).is_err());
+    }
+
+    #[test]
+    fn reverse_names() {
+        assert_eq!(
+            reverse_name(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045")),
+            "d8da6bf26964af9d7eed9e03e53415d37aa96045.addr.reverse"
+        );
+    }
+
+    #[test]
+    fn parses_name_or_address() {
+        let vitalik = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        assert_eq!(
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse(),
+            Ok(NameOrAddress::Address(vitalik))
+        );
+        assert_eq!("vitalik.eth".parse(), Ok(NameOrAddress::Name("vitalik.eth".into())));
+        assert_eq!(NameOrAddress::from(vitalik).to_string(), vitalik.to_string());
+    }
+}
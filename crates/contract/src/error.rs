@@ -1,6 +1,9 @@
+use crate::{CcipError, Revert};
 use alloy_dyn_abi::Error as AbiError;
-use alloy_primitives::Selector;
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, Selector, B256};
 use alloy_provider::PendingTransactionError;
+use alloy_sol_types::SolInterface;
 use alloy_transport::TransportError;
 use thiserror::Error;
 
@@ -13,24 +16,99 @@ pub enum Error {
     /// Unknown function referenced.
     #[error("unknown function: function {0} does not exist")]
     UnknownFunction(String),
+    /// The arguments match several overloads of the function.
+    #[error("ambiguous function: arguments match several overloads of {0}")]
+    AmbiguousFunction(String),
     /// Unknown function selector referenced.
     #[error("unknown function: function with selector {0} does not exist")]
     UnknownSelector(Selector),
+    /// Unknown event referenced.
+    #[error("unknown event: event {0} does not exist")]
+    UnknownEvent(String),
+    /// Unknown event signature referenced.
+    #[error("unknown event: event with signature {0} does not exist")]
+    UnknownEventSelector(B256),
     /// Called `deploy` with a transaction that is not a deployment transaction.
     #[error("transaction is not a deployment transaction")]
     NotADeploymentTransaction,
     /// `contractAddress` was not found in the deployment transaction’s receipt.
     #[error("missing `contractAddress` from deployment transaction receipt")]
     ContractNotDeployed,
+    /// The code at a deterministic deployment address does not have the expected hash.
+    #[error("code at {0} does not match the expected runtime code")]
+    DeployedCodeMismatch(Address),
+    /// Code exists at a CREATE3 deployment address, but no expected runtime code hash was given
+    /// to verify it.
+    #[error("code at {0} cannot be verified without its expected runtime code hash")]
+    UnverifiedCode(Address),
+    /// The deterministic deployer or CREATE3 factory is not deployed on the chain.
+    #[error("no deployer at {0}")]
+    MissingDeployer(Address),
+    /// No ABI is known for the contract.
+    #[error("no ABI for contract {0}")]
+    MissingAbi(Address),
+    /// The `from` field is required to compute the deployment address.
+    #[error("missing `from` address of deterministic deployment")]
+    MissingFrom,
     /// An error occurred ABI encoding or decoding.
     #[error(transparent)]
     AbiError(#[from] AbiError),
     /// An error occurred interacting with a contract over RPC.
     #[error(transparent)]
-    TransportError(#[from] TransportError),
+    TransportError(TransportError),
     /// An error occured while waiting for a pending transaction.
     #[error(transparent)]
     PendingTransactionError(#[from] PendingTransactionError),
+    /// An error occurred during a CCIP-Read offchain lookup.
+    #[error(transparent)]
+    CcipError(CcipError),
+    /// The contract call reverted.
+    #[error("{0}")]
+    Revert(Revert),
+}
+
+impl Error {
+    /// Returns the revert, if the contract call reverted.
+    pub const fn as_revert(&self) -> Option<&Revert> {
+        match self {
+            Self::Revert(revert) => Some(revert),
+            _ => None,
+        }
+    }
+
+    /// Decodes the revert data as one of the errors of the interface, if the contract call
+    /// reverted.
+    ///
+    /// See [`Revert::decode`].
+    pub fn as_decoded_error<E: SolInterface>(&self) -> Option<E> {
+        self.as_revert()?.decode()
+    }
+
+    /// Decodes the revert data with the custom errors of the ABI, if the contract call reverted.
+    pub(crate) fn decode_with_abi(self, abi: Option<&JsonAbi>) -> Self {
+        match (self, abi) {
+            (Self::Revert(revert), Some(abi)) => Self::Revert(revert.decode_with_abi(abi)),
+            (err, _) => err,
+        }
+    }
+}
+
+impl From<TransportError> for Error {
+    fn from(e: TransportError) -> Self {
+        if let Some(data) = e.as_error_resp().and_then(|payload| payload.as_revert_data()) {
+            return Self::Revert(Revert::new(data));
+        }
+        Self::TransportError(e)
+    }
+}
+
+impl From<CcipError> for Error {
+    fn from(e: CcipError) -> Self {
+        match e {
+            CcipError::TransportError(e) => e.into(),
+            e => Self::CcipError(e),
+        }
+    }
 }
 
 impl From<alloy_sol_types::Error> for Error {
@@ -39,3 +117,27 @@ impl From<alloy_sol_types::Error> for Error {
         Self::AbiError(e.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_reverts() {
+        let payload = serde_json::from_str(
+            r#"{
+                "code": 3,
+                "message": "execution reverted: not owner",
+                "data": "0x08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000096e6f74206f776e65720000000000000000000000000000000000000000000000"
+            }"#,
+        )
+        .unwrap();
+        let err = Error::from(TransportError::ErrorResp(payload));
+        assert_eq!(err.as_revert().and_then(Revert::reason), Some("not owner"));
+        assert_eq!(err.to_string(), "execution reverted: not owner");
+
+        let payload = serde_json::from_str(r#"{"code":-32000,"message":"nonce too low"}"#).unwrap();
+        let err = Error::from(TransportError::ErrorResp(payload));
+        assert!(matches!(err, Error::TransportError(_)));
+    }
+}
@@ -1,14 +1,17 @@
-use std::{future::IntoFuture, marker::PhantomData};
+use std::{future::IntoFuture, marker::PhantomData, sync::Arc};
 
 use alloy_dyn_abi::{DynSolValue, FunctionExt};
-use alloy_json_abi::Function;
+use alloy_json_abi::{Function, JsonAbi};
 use alloy_network::Network;
 use alloy_primitives::Bytes;
 use alloy_rpc_types_eth::{state::StateOverride, BlockId};
 use alloy_sol_types::SolCall;
-use alloy_transport::Transport;
+use alloy_transport::{BoxFuture, Transport};
 
-use crate::{Error, Result};
+use crate::{
+    ccip::{CcipCall, MAX_CCIP_REDIRECTS},
+    CcipError, Error, Result,
+};
 
 /// Raw coder.
 const RAW_CODER: () = ();
@@ -33,6 +36,12 @@ where
     inner: alloy_provider::EthCall<'req, 'state, T, N, Bytes>,
 
     decoder: &'coder D,
+
+    /// The maximum number of CCIP-Read offchain lookups, if enabled.
+    ccip_read: Option<usize>,
+
+    /// The ABI whose custom errors are used to decode reverts.
+    abi: Option<Arc<JsonAbi>>,
 }
 
 impl<'req, 'state, 'coder, D, T, N> EthCall<'req, 'state, 'coder, D, T, N>
@@ -46,7 +55,7 @@ where
         inner: alloy_provider::EthCall<'req, 'state, T, N, Bytes>,
         decoder: &'coder D,
     ) -> Self {
-        Self { inner, decoder }
+        Self { inner, decoder, ccip_read: None, abi: None }
     }
 }
 
@@ -75,7 +84,7 @@ where
     where
         E: CallDecoder,
     {
-        EthCall { inner: self.inner, decoder }
+        EthCall { inner: self.inner, decoder, ccip_read: self.ccip_read, abi: self.abi }
     }
 
     /// Set the state overrides for this call.
@@ -89,6 +98,32 @@ where
         self.inner = self.inner.block(block);
         self
     }
+
+    /// Enables [EIP-3668] CCIP-Read offchain lookups for this call.
+    ///
+    /// When the contract reverts with `OffchainLookup`, the gateways are queried and the callback
+    /// function is called with their response, up to [`MAX_CCIP_REDIRECTS`] times. Querying the
+    /// gateways requires the `reqwest` feature.
+    ///
+    /// [EIP-3668]: https://eips.ethereum.org/EIPS/eip-3668
+    pub const fn ccip_read(self) -> Self {
+        self.max_ccip_redirects(MAX_CCIP_REDIRECTS)
+    }
+
+    /// Enables CCIP-Read offchain lookups for this call, with the given maximum number of
+    /// lookups.
+    ///
+    /// See [`ccip_read`](Self::ccip_read) for more information.
+    pub const fn max_ccip_redirects(mut self, max_redirects: usize) -> Self {
+        self.ccip_read = Some(max_redirects);
+        self
+    }
+
+    /// Sets the ABI whose custom errors are used to decode reverts.
+    pub(crate) fn with_abi(mut self, abi: Option<Arc<JsonAbi>>) -> Self {
+        self.abi = abi;
+        self
+    }
 }
 
 impl<'req, 'state, T, N> From<alloy_provider::EthCall<'req, 'state, T, N, Bytes>>
@@ -98,7 +133,7 @@ where
     N: Network,
 {
     fn from(inner: alloy_provider::EthCall<'req, 'state, T, N, Bytes>) -> Self {
-        Self { inner, decoder: &RAW_CODER }
+        Self { inner, decoder: &RAW_CODER, ccip_read: None, abi: None }
     }
 }
 
@@ -114,14 +149,19 @@ where
     type IntoFuture = EthCallFut<'req, 'state, 'coder, D, T, N>;
 
     fn into_future(self) -> Self::IntoFuture {
-        EthCallFut { inner: self.inner.into_future(), decoder: self.decoder }
+        let inner = match self.ccip_read {
+            Some(max_redirects) => {
+                EthCallFutInner::CcipRead(Box::pin(CcipCall::new(&self.inner, max_redirects).run()))
+            }
+            None => EthCallFutInner::Call(self.inner.into_future()),
+        };
+        EthCallFut { inner, decoder: self.decoder, abi: self.abi }
     }
 }
 
 /// Future for the [`EthCall`] type. This future wraps an RPC call with an abi
 /// decoder.
 #[must_use = "futures do nothing unless you `.await` or poll them"]
-#[derive(Clone, Debug)]
 #[allow(unnameable_types)]
 pub struct EthCallFut<'req, 'state, 'coder, D, T, N>
 where
@@ -129,8 +169,36 @@ where
     N: Network,
     D: CallDecoder,
 {
-    inner: <alloy_provider::EthCall<'req, 'state, T, N, Bytes> as IntoFuture>::IntoFuture,
+    inner: EthCallFutInner<'req, 'state, T, N>,
     decoder: &'coder D,
+    abi: Option<Arc<JsonAbi>>,
+}
+
+enum EthCallFutInner<'req, 'state, T, N>
+where
+    T: Transport + Clone,
+    N: Network,
+{
+    Call(<alloy_provider::EthCall<'req, 'state, T, N, Bytes> as IntoFuture>::IntoFuture),
+    CcipRead(BoxFuture<'static, Result<Bytes, CcipError>>),
+}
+
+impl<'req, 'state, 'coder, D, T, N> std::fmt::Debug for EthCallFut<'req, 'state, 'coder, D, T, N>
+where
+    T: Transport + Clone,
+    N: Network,
+    D: CallDecoder,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = match &self.inner {
+            EthCallFutInner::Call(_) => "Call",
+            EthCallFutInner::CcipRead(_) => "CcipRead",
+        };
+        f.debug_struct("EthCallFut")
+            .field("inner", &inner)
+            .field("decoder", &self.decoder.as_debug_field())
+            .finish()
+    }
 }
 
 impl<'req, 'state, 'coder, D, T, N> std::future::Future
@@ -147,12 +215,17 @@ where
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Self::Output> {
         let this = self.get_mut();
-        let pin = std::pin::pin!(&mut this.inner);
-        match pin.poll(cx) {
+        let poll = match &mut this.inner {
+            EthCallFutInner::Call(inner) => std::pin::pin!(inner).poll(cx).map_err(Error::from),
+            EthCallFutInner::CcipRead(inner) => inner.as_mut().poll(cx).map_err(Error::from),
+        };
+        match poll {
             std::task::Poll::Ready(Ok(data)) => {
                 std::task::Poll::Ready(this.decoder.abi_decode_output(data, true))
             }
-            std::task::Poll::Ready(Err(e)) => std::task::Poll::Ready(Err(e.into())),
+            std::task::Poll::Ready(Err(e)) => {
+                std::task::Poll::Ready(Err(e.decode_with_abi(this.abi.as_deref())))
+            }
             std::task::Poll::Pending => std::task::Poll::Pending,
         }
     }
@@ -1,6 +1,6 @@
-use crate::Error;
+use crate::{Error, EventStream};
 use alloy_network::Ethereum;
-use alloy_primitives::{Address, LogData, B256};
+use alloy_primitives::{Address, BlockNumber, LogData, B256};
 use alloy_provider::{FilterPollerBuilder, Network, Provider};
 use alloy_rpc_types_eth::{BlockNumberOrTag, Filter, FilterBlockOption, Log, Topic, ValueOrArray};
 use alloy_sol_types::SolEvent;
@@ -81,6 +81,18 @@ impl<T: Transport + Clone, P: Provider<T, N>, E: SolEvent, N: Network> Event<T,
         Ok(sub.into())
     }
 
+    /// Streams the events that match the filter from the given block: the historical events are
+    /// queried first, followed by the live events.
+    ///
+    /// The block range of the filter is ignored. Logs removed by reorgs are yielded as
+    /// [`EventStreamItem::Removed`](crate::EventStreamItem::Removed).
+    ///
+    /// The stream sends its requests through [`Provider::root`], bypassing the layers of the
+    /// provider.
+    pub async fn stream_from(&self, block: BlockNumber) -> TransportResult<EventStream<T, E, N>> {
+        EventStream::new(&self.provider, &self.filter, block).await
+    }
+
     /// Sets the inner filter object
     ///
     /// See [`Filter::select`].
@@ -218,7 +230,7 @@ impl<T: Transport + Clone, E: SolEvent> EventPoller<T, E> {
     }
 }
 
-fn decode_log<E: SolEvent>(log: &Log) -> alloy_sol_types::Result<E> {
+pub(crate) fn decode_log<E: SolEvent>(log: &Log) -> alloy_sol_types::Result<E> {
     let log_data: &LogData = log.as_ref();
 
     E::decode_raw_log(log_data.topics().iter().copied(), &log_data.data, false)
@@ -276,7 +288,7 @@ pub(crate) mod subscription {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use alloy_primitives::U256;
     use alloy_sol_types::sol;
@@ -503,4 +515,34 @@ mod tests {
             assert_eq!(all.len(), 0);
         }
     }
+
+    #[tokio::test]
+    async fn event_stream_from() {
+        let anvil = alloy_node_bindings::Anvil::new().spawn();
+        let provider = alloy_provider::ProviderBuilder::new().on_http(anvil.endpoint_url());
+
+        let contract = MyContract::deploy(&provider).await.unwrap();
+        for _ in 0..2 {
+            contract.doEmit().send().await.unwrap().get_receipt().await.unwrap();
+            contract.doEmitWrongEvent().send().await.unwrap().get_receipt().await.unwrap();
+        }
+
+        let event = contract.MyEvent_filter();
+        let stream = event.stream_from(0).await.unwrap().page_size(2);
+        assert_eq!(stream.head(), 5);
+        let mut stream = stream.into_stream();
+
+        // Backfilled, one page at a time.
+        for block in [2, 4] {
+            let item = stream.next().await.unwrap().unwrap();
+            assert!(!item.is_removed());
+            assert_eq!(item.log().block_number, Some(block));
+            assert_eq!(item.event()._0, 42);
+        }
+
+        // Live.
+        contract.doEmit().send().await.unwrap().get_receipt().await.unwrap();
+        let item = stream.next().await.unwrap().unwrap();
+        assert_eq!(item.log().block_number, Some(6));
+    }
 }
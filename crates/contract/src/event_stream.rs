@@ -0,0 +1,298 @@
+use crate::{event::decode_log, Error};
+use alloy_network::Network;
+use alloy_primitives::{BlockNumber, B256};
+use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_types_eth::{Filter, FilterBlockOption, Log};
+use alloy_sol_types::SolEvent;
+use alloy_transport::{Transport, TransportResult};
+use futures::{stream::BoxStream, Stream};
+use futures_util::StreamExt;
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    marker::PhantomData,
+};
+
+/// The default number of blocks queried by a single `eth_getLogs` request while backfilling.
+const DEFAULT_PAGE_SIZE: u64 = 1000;
+
+/// An item of an [`EventStream`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventStreamItem<E> {
+    /// A log was emitted.
+    Added(E, Log),
+    /// A previously emitted log was removed by a reorg.
+    Removed(E, Log),
+}
+
+impl<E> EventStreamItem<E> {
+    /// Returns the decoded event.
+    pub const fn event(&self) -> &E {
+        match self {
+            Self::Added(event, _) | Self::Removed(event, _) => event,
+        }
+    }
+
+    /// Returns the raw log.
+    pub const fn log(&self) -> &Log {
+        match self {
+            Self::Added(_, log) | Self::Removed(_, log) => log,
+        }
+    }
+
+    /// Returns `true` if the log was removed by a reorg.
+    pub const fn is_removed(&self) -> bool {
+        matches!(self, Self::Removed(..))
+    }
+}
+
+/// A stream of historical then live events, created with
+/// [`Event::stream_from`](crate::Event::stream_from).
+///
+/// The live logs are watched before the historical logs are queried, so that no log is missed
+/// in between. Logs returned by both are only yielded once.
+///
+/// The stream outlives the [`Event`](crate::Event) it was created from, so it sends its requests
+/// through the [`RootProvider`] of the provider, bypassing the layers of the provider.
+#[must_use = "streams do nothing unless converted with `into_stream`"]
+pub struct EventStream<T, E, N> {
+    provider: RootProvider<T, N>,
+    filter: Filter,
+    from: BlockNumber,
+    seam: BlockNumber,
+    head: BlockNumber,
+    page_size: u64,
+    live: BoxStream<'static, Log>,
+    _phantom: PhantomData<E>,
+}
+
+impl<T, E, N> fmt::Debug for EventStream<T, E, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStream")
+            .field("filter", &self.filter)
+            .field("from", &self.from)
+            .field("head", &self.head)
+            .field("page_size", &self.page_size)
+            .field("event_type", &format_args!("{}", std::any::type_name::<E>()))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Transport + Clone, E: SolEvent, N: Network> EventStream<T, E, N> {
+    /// Starts watching the live logs of the filter, and records the block to backfill up to.
+    ///
+    /// Subscriptions are used if the provider supports them, and polling otherwise.
+    pub(crate) async fn new<P: Provider<T, N>>(
+        provider: &P,
+        filter: &Filter,
+        from: BlockNumber,
+    ) -> TransportResult<Self> {
+        let provider = provider.root().clone();
+        let live_filter = filter.clone().select(FilterBlockOption::default());
+
+        let seam = provider.get_block_number().await?;
+        let live = Self::watch(&provider, &live_filter).await?;
+        let head = provider.get_block_number().await?;
+
+        Ok(Self {
+            provider,
+            filter: filter.clone(),
+            from,
+            seam,
+            head,
+            page_size: DEFAULT_PAGE_SIZE,
+            live,
+            _phantom: PhantomData,
+        })
+    }
+
+    #[cfg(feature = "pubsub")]
+    async fn watch(
+        provider: &RootProvider<T, N>,
+        filter: &Filter,
+    ) -> TransportResult<BoxStream<'static, Log>> {
+        use alloy_transport::{RpcError, TransportErrorKind};
+
+        match provider.subscribe_logs(filter).await {
+            Ok(sub) => Ok(sub.into_stream().boxed()),
+            Err(RpcError::Transport(TransportErrorKind::PubsubUnavailable)) => Ok(provider
+                .watch_logs(filter)
+                .await?
+                .into_stream()
+                .flat_map(futures::stream::iter)
+                .boxed()),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[cfg(not(feature = "pubsub"))]
+    async fn watch(
+        provider: &RootProvider<T, N>,
+        filter: &Filter,
+    ) -> TransportResult<BoxStream<'static, Log>> {
+        Ok(provider.watch_logs(filter).await?.into_stream().flat_map(futures::stream::iter).boxed())
+    }
+
+    /// Sets the number of blocks queried by a single `eth_getLogs` request while backfilling.
+    ///
+    /// Defaults to 1000.
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Returns the last block that is backfilled. Later logs are streamed live.
+    pub const fn head(&self) -> BlockNumber {
+        self.head
+    }
+
+    /// Converts into a stream of events.
+    ///
+    /// Historical logs are yielded first in pages of [`page_size`](Self::page_size) blocks,
+    /// followed by live logs. A failed page is retried when the stream is polled again.
+    pub fn into_stream(self) -> impl Stream<Item = Result<EventStreamItem<E>, Error>> + Unpin {
+        let state =
+            State { next: self.from, buffer: VecDeque::new(), seen: HashSet::new(), stream: self };
+        Box::pin(futures::stream::unfold(state, |mut state| async move {
+            let item = state.next_item().await?;
+            Some((item, state))
+        }))
+    }
+}
+
+/// The state of the stream of an [`EventStream`].
+struct State<T, E, N> {
+    stream: EventStream<T, E, N>,
+    /// The next block to backfill.
+    next: BlockNumber,
+    /// The backfilled logs not yielded yet.
+    buffer: VecDeque<Log>,
+    /// The backfilled logs that may also be returned live.
+    seen: HashSet<(B256, u64)>,
+}
+
+impl<T: Transport + Clone, E: SolEvent, N: Network> State<T, E, N> {
+    async fn next_item(&mut self) -> Option<Result<EventStreamItem<E>, Error>> {
+        loop {
+            if let Some(log) = self.buffer.pop_front() {
+                if log.block_number.is_some_and(|number| number >= self.stream.seam) {
+                    self.seen.extend(key(&log));
+                }
+                return Some(added(log));
+            }
+
+            if self.next <= self.stream.head {
+                let to = self.stream.head.min(self.next.saturating_add(self.stream.page_size - 1));
+                let filter = self.stream.filter.clone().from_block(self.next).to_block(to);
+                match self.stream.provider.get_logs(&filter).await {
+                    Ok(logs) => {
+                        self.buffer.extend(logs);
+                        self.next = to + 1;
+                    }
+                    Err(err) => return Some(Err(err.into())),
+                }
+                continue;
+            }
+
+            let log = self.stream.live.next().await?;
+            if log.removed {
+                if let Some(key) = key(&log) {
+                    self.seen.remove(&key);
+                }
+                return Some(
+                    decode_log(&log).map(|e| EventStreamItem::Removed(e, log)).map_err(Into::into),
+                );
+            }
+            if log.block_number.is_some_and(|number| number <= self.stream.head)
+                && key(&log).is_some_and(|key| self.seen.contains(&key))
+            {
+                continue;
+            }
+            return Some(added(log));
+        }
+    }
+}
+
+fn added<E: SolEvent>(log: Log) -> Result<EventStreamItem<E>, Error> {
+    Ok(EventStreamItem::Added(decode_log(&log)?, log))
+}
+
+/// Returns the key identifying the log, if it was mined.
+fn key(log: &Log) -> Option<(B256, u64)> {
+    Some((log.block_hash?, log.log_index?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::tests::MyContract::MyEvent;
+    use alloy_network::Ethereum;
+    use alloy_primitives::Address;
+    use alloy_transport_http::Http;
+
+    fn log(block: u64, index: u64, removed: bool) -> Log {
+        let event = MyEvent { _0: 42, _1: "hello".into(), _2: true, _3: B256::ZERO };
+        Log {
+            inner: alloy_primitives::Log { address: Address::ZERO, data: event.encode_log_data() },
+            block_hash: Some(B256::with_last_byte(block as u8)),
+            block_number: Some(block),
+            log_index: Some(index),
+            removed,
+            ..Default::default()
+        }
+    }
+
+    /// Creates the state of a stream that has backfilled the `buffer` logs up to `head`.
+    fn state(
+        seam: BlockNumber,
+        head: BlockNumber,
+        buffer: Vec<Log>,
+        live: Vec<Log>,
+    ) -> State<Http<reqwest::Client>, MyEvent, Ethereum> {
+        let stream = EventStream {
+            provider: RootProvider::new_http("http://localhost:1".parse().unwrap()),
+            filter: Filter::new(),
+            from: head + 1,
+            seam,
+            head,
+            page_size: DEFAULT_PAGE_SIZE,
+            live: futures::stream::iter(live).boxed(),
+            _phantom: PhantomData,
+        };
+        State { stream, next: head + 1, buffer: buffer.into(), seen: HashSet::new() }
+    }
+
+    async fn collect(
+        mut state: State<Http<reqwest::Client>, MyEvent, Ethereum>,
+    ) -> Vec<(bool, u64, u64)> {
+        let mut items = Vec::new();
+        while let Some(item) = state.next_item().await {
+            let item = item.unwrap();
+            let log = item.log();
+            items.push((item.is_removed(), log.block_number.unwrap(), log.log_index.unwrap()));
+        }
+        items
+    }
+
+    #[tokio::test]
+    async fn skips_logs_returned_twice() {
+        // Block 2 was mined between watching the live logs and reading the head, so its logs are
+        // both backfilled and returned live.
+        let buffer = vec![log(1, 0, false), log(2, 0, false)];
+        let live = vec![log(2, 0, false), log(2, 1, false), log(3, 0, false)];
+        assert_eq!(
+            collect(state(2, 2, buffer, live)).await,
+            [(false, 1, 0), (false, 2, 0), (false, 2, 1), (false, 3, 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn yields_removed_logs() {
+        let buffer = vec![log(1, 0, false)];
+        let live = vec![log(1, 0, false), log(1, 0, true), log(1, 0, false)];
+        assert_eq!(
+            collect(state(1, 1, buffer, live)).await,
+            [(false, 1, 0), (true, 1, 0), (false, 1, 0)]
+        );
+    }
+}
@@ -1,8 +1,8 @@
-use crate::{CallBuilder, Event, Interface, Result};
+use crate::{args, proxy::ProxyInspector, CallBuilder, DynEvent, Error, Event, Interface, Result};
 use alloy_dyn_abi::DynSolValue;
 use alloy_json_abi::{Function, JsonAbi};
 use alloy_network::{Ethereum, Network};
-use alloy_primitives::{Address, Selector};
+use alloy_primitives::{Address, Selector, B256};
 use alloy_provider::Provider;
 use alloy_rpc_types_eth::Filter;
 use alloy_sol_types::SolEvent;
@@ -50,7 +50,7 @@ impl<T, P, N> ContractInstance<T, P, N> {
 
     /// Returns a reference to the contract's ABI.
     #[inline]
-    pub const fn abi(&self) -> &JsonAbi {
+    pub fn abi(&self) -> &JsonAbi {
         self.interface.abi()
     }
 
@@ -76,6 +76,30 @@ impl<T, P: Clone, N> ContractInstance<T, &P, N> {
 }
 
 impl<T: Transport + Clone, P: Provider<T, N>, N: Network> ContractInstance<T, P, N> {
+    /// Creates a new contract at the address of a proxy, using the ABI of its implementation.
+    ///
+    /// The proxy is detected with [`ProxyInspector`], and `abi_of` is called with the address of
+    /// each implementation, such as each facet of a diamond. The ABIs of multiple implementations
+    /// are merged. If the contract is not a proxy, `abi_of` is called with its own address.
+    pub async fn new_proxy(
+        address: Address,
+        provider: P,
+        mut abi_of: impl FnMut(Address) -> Option<JsonAbi>,
+    ) -> Result<Self> {
+        let proxy = ProxyInspector::new(&provider).detect(address).await?;
+        let implementations = proxy.map_or_else(|| vec![address], |proxy| proxy.implementations());
+        let mut abis = implementations
+            .into_iter()
+            .map(|implementation| abi_of(implementation).ok_or(Error::MissingAbi(implementation)))
+            .collect::<Result<Vec<_>>>()?;
+        let abi = if abis.len() == 1 {
+            abis.remove(0)
+        } else {
+            abis.into_iter().flat_map(JsonAbi::into_items).collect()
+        };
+        Ok(Self::new(address, provider, Interface::new(abi)))
+    }
+
     /// Returns a transaction builder for the provided function name.
     ///
     /// If there are multiple functions with the same name due to overloading, consider using
@@ -87,7 +111,13 @@ impl<T: Transport + Clone, P: Provider<T, N>, N: Network> ContractInstance<T, P,
         args: &[DynSolValue],
     ) -> Result<CallBuilder<T, &P, Function, N>> {
         let function = self.interface.get_from_name(name)?;
-        CallBuilder::new_dyn(&self.provider, &self.address, function, args)
+        CallBuilder::new_dyn(
+            &self.provider,
+            &self.address,
+            function,
+            args,
+            self.interface.abi_arc(),
+        )
     }
 
     /// Returns a transaction builder for the provided function selector.
@@ -97,7 +127,126 @@ impl<T: Transport + Clone, P: Provider<T, N>, N: Network> ContractInstance<T, P,
         args: &[DynSolValue],
     ) -> Result<CallBuilder<T, &P, Function, N>> {
         let function = self.interface.get_from_selector(selector)?;
-        CallBuilder::new_dyn(&self.provider, &self.address, function, args)
+        CallBuilder::new_dyn(
+            &self.provider,
+            &self.address,
+            function,
+            args,
+            self.interface.abi_arc(),
+        )
+    }
+
+    /// Returns a transaction builder for the provided function name, parsing the human-friendly
+    /// string arguments against its parameter types.
+    ///
+    /// Overloaded functions are resolved to the one the arguments can be parsed for, and an
+    /// error is returned if they can be parsed for several. See [`args::parse_args`] for the
+    /// syntax of the arguments.
+    pub fn function_from_strs<S: AsRef<str>>(
+        &self,
+        name: &str,
+        args: &[S],
+    ) -> Result<CallBuilder<T, &P, Function, N>> {
+        let (function, args) =
+            self.resolve_function(name, |function| args::parse_args(&function.inputs, args))?;
+        self.function_builder(function, &args)
+    }
+
+    /// Returns a transaction builder for the provided function name, converting the JSON
+    /// arguments to its parameter types.
+    ///
+    /// The arguments are either an array, or an object keyed by the parameter names. Overloaded
+    /// functions are resolved to the one the arguments can be converted for, and an error is
+    /// returned if they can be converted for several. See [`args::args_from_json`].
+    pub fn function_from_json(
+        &self,
+        name: &str,
+        args: &serde_json::Value,
+    ) -> Result<CallBuilder<T, &P, Function, N>> {
+        let (function, args) = self.resolve_json(name, args)?;
+        self.function_builder(function, &args)
+    }
+
+    /// Calls the provided function with JSON arguments, returning its return values as JSON.
+    ///
+    /// See [`function_from_json`](Self::function_from_json) and [`args::values_to_json`].
+    pub async fn call_json(
+        &self,
+        name: &str,
+        args: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let (function, args) = self.resolve_json(name, args)?;
+        let outputs = self.function_builder(function, &args)?.call().await?;
+        Ok(args::values_to_json(&function.outputs, &outputs))
+    }
+
+    fn resolve_json(
+        &self,
+        name: &str,
+        args: &serde_json::Value,
+    ) -> Result<(&Function, Vec<DynSolValue>)> {
+        self.resolve_function(name, |function| args::args_from_json(&function.inputs, args))
+    }
+
+    /// Returns the overload of the function whose arguments are successfully converted by
+    /// `convert`, with the converted arguments.
+    ///
+    /// Returns [`Error::AmbiguousFunction`] if the arguments are converted for several
+    /// overloads.
+    fn resolve_function(
+        &self,
+        name: &str,
+        mut convert: impl FnMut(&Function) -> Result<Vec<DynSolValue>>,
+    ) -> Result<(&Function, Vec<DynSolValue>)> {
+        let functions = self
+            .interface
+            .abi()
+            .function(name)
+            .ok_or_else(|| Error::UnknownFunction(name.to_string()))?;
+        let mut resolved = None;
+        let mut error = None;
+        for function in functions {
+            match convert(function) {
+                Ok(_) if resolved.is_some() => {
+                    return Err(Error::AmbiguousFunction(name.to_string()))
+                }
+                Ok(args) => resolved = Some((function, args)),
+                Err(err) => error = Some(err),
+            }
+        }
+        resolved.ok_or_else(|| error.unwrap_or_else(|| Error::UnknownFunction(name.to_string())))
+    }
+
+    fn function_builder(
+        &self,
+        function: &Function,
+        args: &[DynSolValue],
+    ) -> Result<CallBuilder<T, &P, Function, N>> {
+        CallBuilder::new_dyn(
+            &self.provider,
+            &self.address,
+            function,
+            args,
+            self.interface.abi_arc(),
+        )
+    }
+
+    /// Returns a [`DynEvent`] builder for the provided event name, filtering the logs of the
+    /// contract.
+    ///
+    /// If there are multiple events with the same name due to overloading, consider using
+    /// the [`ContractInstance::dyn_event_from_selector`] method instead, since this will use the
+    /// first match.
+    pub fn dyn_event(&self, name: &str) -> Result<DynEvent<T, &P, N>> {
+        let event = self.interface.get_event_from_name(name)?;
+        Ok(DynEvent::new(&self.provider, Filter::new().address(self.address), event.clone()))
+    }
+
+    /// Returns a [`DynEvent`] builder for the provided event signature (`topic0`), filtering the
+    /// logs of the contract.
+    pub fn dyn_event_from_selector(&self, selector: &B256) -> Result<DynEvent<T, &P, N>> {
+        let event = self.interface.get_event_from_selector(selector)?;
+        Ok(DynEvent::new(&self.provider, Filter::new().address(self.address), event.clone()))
     }
 
     /// Returns an [`Event`] builder with the provided filter.
@@ -125,7 +274,7 @@ mod tests {
     use super::*;
     use alloy_network::TransactionBuilder;
     use alloy_primitives::{hex, U256};
-    use alloy_provider::ProviderBuilder;
+    use alloy_provider::{ProviderBuilder, RootProvider};
     use alloy_rpc_types_eth::TransactionRequest;
 
     #[tokio::test]
@@ -157,4 +306,74 @@ mod tests {
         let result = contract.function("counter", &[]).unwrap().call().await.unwrap();
         assert_eq!(result[0].as_uint().unwrap().0, U256::from(1));
     }
+
+    /// Deploys a contract with a `counter` getter and an `increment` function.
+    async fn deploy_counter<P: Provider<T>, T: Transport + Clone>(
+        provider: &P,
+    ) -> (Address, JsonAbi) {
+        let abi_str = r#"[{"inputs":[],"name":"counter","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"},{"inputs":[],"name":"increment","outputs":[],"stateMutability":"nonpayable","type":"function"}]"#;
+        let abi = serde_json::from_str::<JsonAbi>(abi_str).unwrap();
+        let bytecode = hex::decode("6080806040523460135760b2908160188239f35b5f80fdfe60808060405260043610156011575f80fd5b5f3560e01c90816361bc221a146065575063d09de08a14602f575f80fd5b346061575f3660031901126061575f5460018101809111604d575f55005b634e487b7160e01b5f52601160045260245ffd5b5f80fd5b346061575f3660031901126061576020905f548152f3fea2646970667358221220d802267a5f574e54a87a63d0ff8d733fdb275e6e6c502831d9e14f957bbcd7a264736f6c634300081a0033").unwrap();
+        let deploy_tx = TransactionRequest::default().with_deploy_code(bytecode);
+        let address = provider
+            .send_transaction(deploy_tx)
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap()
+            .contract_address
+            .unwrap();
+        (address, abi)
+    }
+
+    #[tokio::test]
+    async fn string_and_json_args() {
+        let provider = ProviderBuilder::new().on_anvil();
+
+        let (address, abi) = deploy_counter(&provider).await;
+        let contract = ContractInstance::new(address, provider, Interface::new(abi));
+
+        let empty: &[&str] = &[];
+        contract
+            .function_from_strs("increment", empty)
+            .unwrap()
+            .send()
+            .await
+            .unwrap()
+            .watch()
+            .await
+            .unwrap();
+        let result = contract.call_json("counter", &serde_json::json!([])).await.unwrap();
+        assert_eq!(result, serde_json::json!(["1"]));
+    }
+
+    #[test]
+    fn resolves_overloads() {
+        let abi = JsonAbi::parse([
+            "function f(uint256 x)",
+            "function f(uint8 x)",
+            "function f(bool x)",
+            "function f(uint8 x, uint8 y)",
+        ])
+        .unwrap();
+        let provider = RootProvider::<_, Ethereum>::new_http("http://localhost:1".parse().unwrap());
+        let contract = ContractInstance::new(Address::ZERO, provider, Interface::new(abi));
+        let resolve = |args: &[&str]| {
+            contract
+                .function_from_strs("f", args)
+                .map(|call| Selector::from_slice(&call.calldata()[..4]))
+        };
+        let selector = |signature: &str| Function::parse(signature).unwrap().selector();
+
+        assert_eq!(resolve(&["true"]).unwrap(), selector("f(bool)"));
+        assert_eq!(resolve(&["300"]).unwrap(), selector("f(uint256)"));
+        assert_eq!(resolve(&["1", "2"]).unwrap(), selector("f(uint8,uint8)"));
+        assert!(matches!(resolve(&["1"]), Err(Error::AmbiguousFunction(_))));
+        assert!(matches!(
+            contract.function_from_json("f", &serde_json::json!({"x": 1})),
+            Err(Error::AmbiguousFunction(_))
+        ));
+        assert!(resolve(&["x"]).is_err());
+    }
 }
@@ -1,22 +1,28 @@
 use crate::{ContractInstance, Error, Result};
-use alloy_dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt};
-use alloy_json_abi::{Function, JsonAbi};
-use alloy_primitives::{Address, Selector};
-use std::collections::{BTreeMap, HashMap};
+use alloy_dyn_abi::{DecodedEvent, DynSolValue, EventExt, FunctionExt, JsonAbiExt};
+use alloy_json_abi::{Event, Function, JsonAbi};
+use alloy_primitives::{Address, Selector, B256};
+use alloy_rpc_types_eth::Log;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 /// A smart contract interface.
 #[derive(Clone, Debug)]
 pub struct Interface {
-    abi: JsonAbi,
+    abi: Arc<JsonAbi>,
     functions: HashMap<Selector, (String, usize)>,
+    events: HashMap<B256, (String, usize)>,
 }
 
-// TODO: events/errors
+// TODO: errors
 impl Interface {
     /// Creates a new contract interface from the provided ABI.
     pub fn new(abi: JsonAbi) -> Self {
         let functions = create_mapping(&abi.functions, Function::selector);
-        Self { abi, functions }
+        let events = create_mapping(&abi.events, Event::selector);
+        Self { abi: Arc::new(abi), functions, events }
     }
 
     /// Returns the ABI encoded data (including the selector) for the provided function and
@@ -91,14 +97,35 @@ impl Interface {
         self.get_from_selector(selector)?.abi_decode_output(data, validate).map_err(Into::into)
     }
 
+    /// Decodes the log as the event of the ABI matching its event signature (`topic0`).
+    ///
+    /// Anonymous events have no event signature, and are never matched.
+    pub fn decode_log(&self, log: &Log) -> Result<(&Event, DecodedEvent)> {
+        let selector = log
+            .topics()
+            .first()
+            .ok_or(alloy_dyn_abi::Error::TopicLengthMismatch { expected: 1, actual: 0 })?;
+        let event = self
+            .get_event_from_selector(selector)
+            .ok()
+            .filter(|event| !event.anonymous)
+            .ok_or(Error::UnknownEventSelector(*selector))?;
+        Ok((event, event.decode_log(log.as_ref(), false)?))
+    }
+
     /// Returns a reference to the contract's ABI.
-    pub const fn abi(&self) -> &JsonAbi {
+    pub fn abi(&self) -> &JsonAbi {
         &self.abi
     }
 
     /// Consumes the interface, returning the inner ABI.
     pub fn into_abi(self) -> JsonAbi {
-        self.abi
+        Arc::unwrap_or_clone(self.abi)
+    }
+
+    /// Returns a shared reference to the contract's ABI.
+    pub(crate) fn abi_arc(&self) -> Arc<JsonAbi> {
+        self.abi.clone()
     }
 
     pub(crate) fn get_from_name(&self, name: &str) -> Result<&Function> {
@@ -115,6 +142,20 @@ impl Interface {
             .ok_or_else(|| Error::UnknownSelector(*selector))
     }
 
+    pub(crate) fn get_event_from_name(&self, name: &str) -> Result<&Event> {
+        self.abi
+            .event(name)
+            .and_then(|r| r.first())
+            .ok_or_else(|| Error::UnknownEvent(name.to_string()))
+    }
+
+    pub(crate) fn get_event_from_selector(&self, selector: &B256) -> Result<&Event> {
+        self.events
+            .get(selector)
+            .map(|(name, index)| &self.abi.events[name][*index])
+            .ok_or(Error::UnknownEventSelector(*selector))
+    }
+
     /// Create a [`ContractInstance`] from this ABI for a contract at the given address.
     pub const fn connect<T, P, N>(
         self,
@@ -145,3 +186,50 @@ where
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, LogData, U256};
+    use alloy_sol_types::{sol, SolEvent};
+
+    sol! {
+        event Transfer(address indexed from, address indexed to, uint256 value);
+    }
+
+    #[test]
+    fn decodes_logs_by_signature() {
+        let abi = JsonAbi::parse([
+            "event Approval(address indexed owner, address indexed spender, uint256 value)",
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+        ])
+        .unwrap();
+        let interface = Interface::new(abi);
+
+        let transfer = Transfer {
+            from: address!("00000000000000000000000000000000000000aa"),
+            to: address!("00000000000000000000000000000000000000bb"),
+            value: U256::from(1),
+        };
+        let log = Log {
+            inner: alloy_primitives::Log {
+                address: Address::ZERO,
+                data: transfer.encode_log_data(),
+            },
+            ..Default::default()
+        };
+        let (event, decoded) = interface.decode_log(&log).unwrap();
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(decoded.indexed, [transfer.from.into(), transfer.to.into()]);
+        assert_eq!(decoded.body, [transfer.value.into()]);
+
+        let log = Log {
+            inner: alloy_primitives::Log {
+                address: Address::ZERO,
+                data: LogData::new_unchecked(vec![B256::ZERO], Default::default()),
+            },
+            ..Default::default()
+        };
+        assert!(matches!(interface.decode_log(&log), Err(Error::UnknownEventSelector(_))));
+    }
+}
@@ -9,18 +9,50 @@
 #[cfg(test)]
 extern crate self as alloy_contract;
 
+pub mod args;
+
+mod ccip;
+pub use ccip::{CcipError, MAX_CCIP_REDIRECTS};
+
+pub mod deterministic;
+
+pub mod ens;
+
+pub mod linker;
+
+pub mod proxy;
+
+pub mod storage;
+
+pub mod trace;
+
 mod eth_call;
 pub use eth_call::{CallDecoder, EthCall};
 
 mod error;
 pub use error::*;
 
+mod revert;
+pub use revert::{DecodedRevert, Revert};
+
+mod preview;
+pub use preview::{BalanceChange, CallPreview, DecodedStorageChange, StorageChange};
+
 mod event;
 pub use event::{Event, EventPoller};
 
 #[cfg(feature = "pubsub")]
 pub use event::subscription::EventSubscription;
 
+mod event_stream;
+pub use event_stream::{EventStream, EventStreamItem};
+
+mod dyn_event;
+pub use dyn_event::{DynEvent, DynEventPoller};
+
+#[cfg(feature = "pubsub")]
+pub use dyn_event::subscription::DynEventSubscription;
+
 mod interface;
 pub use interface::*;
 
@@ -0,0 +1,433 @@
+//! Linking of contract bytecode against deployed libraries.
+//!
+//! The bytecode of contracts calling external library functions contains placeholders, such as
+//! `__$<hash>$__`, where the library addresses go. Their positions are listed in the link
+//! references of the compiler output.
+
+use crate::{Error, RawCallBuilder};
+use alloy_network::Network;
+use alloy_primitives::{hex, Address, Bytes};
+use alloy_provider::Provider;
+use alloy_transport::Transport;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An error that occurred while linking bytecode.
+#[derive(Debug, thiserror::Error)]
+pub enum LinkError {
+    /// The artifact could not be parsed.
+    #[error("invalid artifact: {0}")]
+    InvalidArtifact(String),
+    /// The linked bytecode is not valid hex.
+    #[error("invalid bytecode: {0}")]
+    InvalidBytecode(#[from] hex::FromHexError),
+    /// A library is referenced, but neither its address nor its bytecode is known.
+    #[error("library {0} is not linked")]
+    Unlinked(String),
+    /// Libraries reference each other in a cycle.
+    #[error("cyclic library references through {0}")]
+    Cycle(String),
+    /// An error occurred deploying a library.
+    #[error(transparent)]
+    Contract(#[from] Error),
+}
+
+/// The link references of a bytecode object, as output by the compiler: the byte offsets of the
+/// placeholders, by library name, by source file.
+type LinkReferences = BTreeMap<String, BTreeMap<String, Vec<Offset>>>;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BytecodeObject {
+    object: String,
+    #[serde(default)]
+    link_references: LinkReferences,
+}
+
+#[derive(Deserialize)]
+struct Offset {
+    start: usize,
+}
+
+/// Contract bytecode whose library placeholders may not be linked yet.
+///
+/// Libraries are identified by their fully qualified name, e.g. `src/Math.sol:Math`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkableBytecode {
+    /// The hex-encoded bytecode, without prefix.
+    object: String,
+    /// The byte offsets of the placeholders of the libraries that are not linked yet.
+    references: BTreeMap<String, Vec<usize>>,
+}
+
+impl LinkableBytecode {
+    /// Parses the creation bytecode and its link references from a contract artifact.
+    ///
+    /// Supported are Foundry artifacts, `solc` standard JSON contract outputs and Hardhat
+    /// artifacts.
+    pub fn from_artifact(json: &str) -> Result<Self, LinkError> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| LinkError::InvalidArtifact(e.to_string()))?;
+        let bytecode = match (&value["bytecode"], &value["evm"]["bytecode"]) {
+            // Foundry.
+            (bytecode @ Value::Object(_), _) => bytecode.clone(),
+            // Hardhat.
+            (Value::String(object), _) => serde_json::json!({
+                "object": object,
+                "linkReferences": value["linkReferences"],
+            }),
+            // solc.
+            (_, bytecode @ Value::Object(_)) => bytecode.clone(),
+            _ => return Err(LinkError::InvalidArtifact("missing bytecode".into())),
+        };
+        let bytecode: BytecodeObject = serde_json::from_value(bytecode)
+            .map_err(|e| LinkError::InvalidArtifact(e.to_string()))?;
+        Ok(Self::new(&bytecode.object, bytecode.link_references))
+    }
+
+    fn new(object: &str, link_references: LinkReferences) -> Self {
+        let object = object.strip_prefix("0x").unwrap_or(object).to_string();
+        let references = link_references
+            .into_iter()
+            .flat_map(|(file, libraries)| {
+                libraries.into_iter().map(move |(name, offsets)| {
+                    (format!("{file}:{name}"), offsets.iter().map(|o| o.start).collect())
+                })
+            })
+            .collect();
+        Self { object, references }
+    }
+
+    /// Returns the fully qualified names of the libraries that are not linked yet.
+    pub fn unlinked(&self) -> impl Iterator<Item = &str> {
+        self.references.keys().map(String::as_str)
+    }
+
+    /// Returns `true` if all libraries are linked.
+    pub fn is_linked(&self) -> bool {
+        self.references.is_empty()
+    }
+
+    /// Links the library with the given fully qualified name to its deployed address.
+    ///
+    /// Libraries that are not referenced are ignored. Returns an error if a placeholder of the
+    /// library lies outside the bytecode.
+    pub fn link(mut self, library: &str, address: Address) -> Result<Self, LinkError> {
+        if let Some(offsets) = self.references.remove(library) {
+            let address = hex::encode(address);
+            for offset in offsets {
+                let range = offset * 2..offset * 2 + address.len();
+                if self.object.get(range.clone()).is_none() {
+                    return Err(LinkError::InvalidArtifact(format!(
+                        "placeholder of library {library} at offset {offset} is outside the bytecode"
+                    )));
+                }
+                self.object.replace_range(range, &address);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Links all given libraries.
+    pub fn link_all<'a>(
+        self,
+        libraries: impl IntoIterator<Item = (&'a str, Address)>,
+    ) -> Result<Self, LinkError> {
+        libraries
+            .into_iter()
+            .try_fold(self, |bytecode, (library, address)| bytecode.link(library, address))
+    }
+
+    /// Returns the linked bytecode.
+    ///
+    /// Returns an error if a library is not linked yet.
+    pub fn into_bytes(self) -> Result<Bytes, LinkError> {
+        if let Some(library) = self.references.into_keys().next() {
+            return Err(LinkError::Unlinked(library));
+        }
+        Ok(hex::decode(self.object)?.into())
+    }
+
+    /// Returns a deployment builder for the linked bytecode, followed by the ABI-encoded
+    /// constructor arguments.
+    ///
+    /// Returns an error if a library is not linked yet.
+    pub fn deploy_builder<T, P, N>(
+        self,
+        provider: P,
+        constructor_args: &[u8],
+    ) -> Result<RawCallBuilder<T, P, N>, LinkError>
+    where
+        T: Transport + Clone,
+        P: Provider<T, N>,
+        N: Network,
+    {
+        let code = [self.into_bytes()?.as_ref(), constructor_args].concat();
+        Ok(RawCallBuilder::new_raw_deploy(provider, code.into()))
+    }
+}
+
+/// Links contracts against libraries, deploying the libraries that are not deployed yet.
+///
+/// # Example
+///
+/// ```no_run
+/// # use alloy_contract::linker::{LinkableBytecode, Linker};
+/// # async fn test<P: alloy_contract::private::Provider>(provider: P, token: &str, math: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut linker = Linker::new()
+///     .artifact("src/Token.sol:Token", LinkableBytecode::from_artifact(token)?)
+///     .artifact("src/Math.sol:Math", LinkableBytecode::from_artifact(math)?);
+///
+/// // Deploys `Math`, then returns the builder deploying `Token` linked against it.
+/// let builder = linker.deploy_builder(&provider, "src/Token.sol:Token", &[]).await?;
+/// let address = builder.deploy().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Linker {
+    artifacts: BTreeMap<String, LinkableBytecode>,
+    libraries: BTreeMap<String, Address>,
+}
+
+impl Linker {
+    /// Creates a new linker, without artifacts or libraries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the bytecode of the contract or library with the given fully qualified name.
+    pub fn artifact(mut self, name: impl Into<String>, bytecode: LinkableBytecode) -> Self {
+        self.artifacts.insert(name.into(), bytecode);
+        self
+    }
+
+    /// Adds the address of the deployed library with the given fully qualified name.
+    pub fn library(mut self, name: impl Into<String>, address: Address) -> Self {
+        self.libraries.insert(name.into(), address);
+        self
+    }
+
+    /// Returns the addresses of the known libraries, including the deployed ones.
+    pub const fn libraries(&self) -> &BTreeMap<String, Address> {
+        &self.libraries
+    }
+
+    /// Links the contract with the given fully qualified name against the known libraries.
+    pub fn link(&self, name: &str) -> Result<LinkableBytecode, LinkError> {
+        let bytecode = self.artifacts.get(name).ok_or_else(|| LinkError::Unlinked(name.into()))?;
+        bytecode
+            .clone()
+            .link_all(self.libraries.iter().map(|(library, address)| (library.as_str(), *address)))
+    }
+
+    /// Deploys the libraries that the contract with the given fully qualified name depends on and
+    /// that are not deployed yet, in dependency order.
+    pub async fn deploy_libraries<T, P, N>(
+        &mut self,
+        provider: &P,
+        name: &str,
+    ) -> Result<(), LinkError>
+    where
+        T: Transport + Clone,
+        P: Provider<T, N>,
+        N: Network,
+    {
+        for library in self.deploy_order(name)? {
+            let address =
+                self.link(&library)?.deploy_builder::<T, _, N>(provider, &[])?.deploy().await?;
+            self.libraries.insert(library, address);
+        }
+        Ok(())
+    }
+
+    /// Deploys the missing libraries of the contract with the given fully qualified name, and
+    /// returns a deployment builder for the linked contract followed by the ABI-encoded
+    /// constructor arguments.
+    pub async fn deploy_builder<T, P, N>(
+        &mut self,
+        provider: P,
+        name: &str,
+        constructor_args: &[u8],
+    ) -> Result<RawCallBuilder<T, P, N>, LinkError>
+    where
+        T: Transport + Clone,
+        P: Provider<T, N>,
+        N: Network,
+    {
+        self.deploy_libraries(&provider, name).await?;
+        self.link(name)?.deploy_builder(provider, constructor_args)
+    }
+
+    /// Returns the libraries to deploy before the contract, dependencies first.
+    fn deploy_order(&self, name: &str) -> Result<Vec<String>, LinkError> {
+        let mut order = Vec::new();
+        self.visit(name, &mut BTreeSet::new(), &mut order)?;
+        // The contract itself is last.
+        order.pop();
+        Ok(order)
+    }
+
+    fn visit(
+        &self,
+        name: &str,
+        visiting: &mut BTreeSet<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), LinkError> {
+        if order.iter().any(|n| n == name) {
+            return Ok(());
+        }
+        if !visiting.insert(name.to_string()) {
+            return Err(LinkError::Cycle(name.into()));
+        }
+        let bytecode = self.artifacts.get(name).ok_or_else(|| LinkError::Unlinked(name.into()))?;
+        for library in bytecode.unlinked() {
+            if !self.libraries.contains_key(library) {
+                self.visit(library, visiting, order)?;
+            }
+        }
+        visiting.remove(name);
+        order.push(name.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+
+    /// Returns the `solc` placeholder of the library.
+    fn placeholder(library: &str) -> String {
+        format!("__${}$__", &hex::encode(keccak256(library))[..34])
+    }
+
+    fn artifact(object: &str, references: &[(&str, &[usize])]) -> String {
+        let mut link_references = serde_json::Map::new();
+        for (library, offsets) in references {
+            let (file, name) = library.split_once(':').unwrap();
+            let offsets: Vec<_> = offsets
+                .iter()
+                .map(|start| serde_json::json!({ "start": start, "length": 20 }))
+                .collect();
+            link_references
+                .entry(file)
+                .or_insert_with(|| serde_json::json!({}))
+                .as_object_mut()
+                .unwrap()
+                .insert(name.into(), offsets.into());
+        }
+        serde_json::json!({ "bytecode": { "object": object, "linkReferences": link_references } })
+            .to_string()
+    }
+
+    #[test]
+    fn links_artifacts() {
+        let math = "src/Math.sol:Math";
+        let object = format!("0x6001{}6002{}", placeholder(math), placeholder(math));
+        let bytecode =
+            LinkableBytecode::from_artifact(&artifact(&object, &[(math, &[2, 24])])).unwrap();
+        assert_eq!(bytecode.unlinked().collect::<Vec<_>>(), [math]);
+        assert!(matches!(bytecode.clone().into_bytes(), Err(LinkError::Unlinked(_))));
+
+        let address = Address::repeat_byte(0xaa);
+        let linked = bytecode.link(math, address).unwrap();
+        assert!(linked.is_linked());
+        assert_eq!(
+            linked.into_bytes().unwrap(),
+            Bytes::from([&[0x60, 0x01], &address[..], &[0x60, 0x02], &address[..]].concat())
+        );
+
+        // A placeholder outside the bytecode is an error, rather than silently left unlinked.
+        let bytecode =
+            LinkableBytecode::from_artifact(&artifact(&object, &[(math, &[2, 40])])).unwrap();
+        let err = bytecode.link(math, address).unwrap_err();
+        assert!(
+            matches!(err, LinkError::InvalidArtifact(msg) if msg.contains(math) && msg.contains("40"))
+        );
+
+        // solc standard JSON output.
+        let solc = serde_json::json!({ "evm": { "bytecode": { "object": "6001", "linkReferences": {} } } });
+        let bytecode = LinkableBytecode::from_artifact(&solc.to_string()).unwrap();
+        assert_eq!(bytecode.into_bytes().unwrap(), Bytes::from_static(&[0x60, 0x01]));
+
+        // Hardhat artifact.
+        let hardhat = serde_json::json!({ "bytecode": "0x6001", "linkReferences": {} });
+        let bytecode = LinkableBytecode::from_artifact(&hardhat.to_string()).unwrap();
+        assert_eq!(bytecode.into_bytes().unwrap(), Bytes::from_static(&[0x60, 0x01]));
+    }
+
+    #[test]
+    fn orders_library_deployments() {
+        let (token, math, log) = ("src/Token.sol:Token", "src/Math.sol:Math", "src/Log.sol:Log");
+        let offsets = [0, 20];
+        let bytecode = |libraries: &[&str]| {
+            let object: String = libraries.iter().map(|library| placeholder(library)).collect();
+            let references: Vec<_> = libraries
+                .iter()
+                .zip(&offsets)
+                .map(|(library, offset)| (*library, std::slice::from_ref(offset)))
+                .collect();
+            LinkableBytecode::from_artifact(&artifact(&object, &references)).unwrap()
+        };
+
+        let linker = Linker::new()
+            .artifact(token, bytecode(&[math, log]))
+            .artifact(math, bytecode(&[log]))
+            .artifact(log, bytecode(&[]));
+        assert_eq!(linker.deploy_order(token).unwrap(), [log, math]);
+
+        let linker = linker.library(log, Address::ZERO);
+        assert_eq!(linker.deploy_order(token).unwrap(), [math]);
+
+        let linker =
+            Linker::new().artifact(math, bytecode(&[log])).artifact(log, bytecode(&[math]));
+        assert!(matches!(linker.deploy_order(math), Err(LinkError::Cycle(_))));
+
+        let linker = Linker::new().artifact(token, bytecode(&[math]));
+        assert!(
+            matches!(linker.deploy_order(token), Err(LinkError::Unlinked(name)) if name == math)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn deploys_libraries() {
+        use alloy_provider::{Provider, ProviderBuilder};
+
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+        let (token, math) = ("src/Token.sol:Token", "src/Math.sol:Math");
+
+        // Init code returning the runtime code that follows it.
+        let init_code = |runtime: &str| {
+            let len = runtime.len() / 2;
+            format!("60{len:02x}600c60003960{len:02x}6000f3{runtime}")
+        };
+        // `PUSH20 <Math>`, `POP`, `STOP`. The placeholder follows the 12-byte init code and the
+        // `PUSH20` opcode.
+        let token_object = init_code(&format!("73{}5000", placeholder(math)));
+        let mut linker = Linker::new()
+            .artifact(
+                token,
+                LinkableBytecode::from_artifact(&artifact(&token_object, &[(math, &[13])]))
+                    .unwrap(),
+            )
+            .artifact(
+                math,
+                LinkableBytecode::from_artifact(&artifact(&init_code("00"), &[])).unwrap(),
+            );
+
+        let address =
+            linker.deploy_builder(&provider, token, &[]).await.unwrap().deploy().await.unwrap();
+        let library = linker.libraries()[math];
+        assert_eq!(provider.get_code_at(library).await.unwrap(), Bytes::from_static(&[0x00]));
+        assert_eq!(
+            provider.get_code_at(address).await.unwrap(),
+            Bytes::from([&[0x73], &library[..], &[0x50, 0x00]].concat())
+        );
+
+        // Deployed libraries are reused.
+        linker.deploy_libraries(&provider, token).await.unwrap();
+        assert_eq!(linker.libraries()[math], library);
+    }
+}
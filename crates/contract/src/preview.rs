@@ -0,0 +1,228 @@
+use crate::{
+    storage::StorageLayout,
+    trace::{DecodedTrace, DecodedTraceLog},
+};
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::{Address, B256, I256, U256};
+use alloy_rpc_types_trace::geth::DiffMode;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A report of what a transaction would change, created with
+/// [`CallBuilder::preview`](crate::CallBuilder::preview).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallPreview {
+    /// The estimated gas limit of the transaction, or `None` if the estimation reverted.
+    pub gas_estimate: Option<u128>,
+    /// The gas used by the traced transaction.
+    pub gas_used: u64,
+    /// The balance changes, by address.
+    pub balance_changes: BTreeMap<Address, BalanceChange>,
+    /// The storage changes, by address.
+    pub storage_changes: BTreeMap<Address, Vec<StorageChange>>,
+    /// The call trace, decoded with the ABI of the contract.
+    pub trace: DecodedTrace,
+}
+
+/// A balance change of a [`CallPreview`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// The balance before the transaction.
+    pub before: U256,
+    /// The balance after the transaction.
+    pub after: U256,
+}
+
+impl BalanceChange {
+    /// Returns the signed change of the balance.
+    pub const fn delta(&self) -> I256 {
+        I256::from_raw(self.after.wrapping_sub(self.before))
+    }
+}
+
+/// A storage slot change of a [`CallPreview`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageChange {
+    /// The slot.
+    pub slot: B256,
+    /// The value before the transaction.
+    pub before: B256,
+    /// The value after the transaction.
+    pub after: B256,
+    /// The changed values stored in the slot, decoded with
+    /// [`CallPreview::decode_storage`].
+    pub decoded: Vec<DecodedStorageChange>,
+}
+
+/// A changed value of a [`StorageChange`], decoded with a [`StorageLayout`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedStorageChange {
+    /// The path of the value, such as `owner` or `config.fee`.
+    pub path: String,
+    /// The value before the transaction.
+    pub before: DynSolValue,
+    /// The value after the transaction.
+    pub after: DynSolValue,
+}
+
+impl CallPreview {
+    pub(crate) fn new(gas_estimate: Option<u128>, diff: DiffMode, trace: DecodedTrace) -> Self {
+        let DiffMode { pre, post } = diff;
+        let addresses = pre.keys().chain(post.keys()).copied().collect::<BTreeSet<_>>();
+
+        let mut balance_changes = BTreeMap::new();
+        let mut storage_changes = BTreeMap::new();
+        for address in addresses {
+            let (pre, post) = (pre.get(&address), post.get(&address));
+
+            // Accounts missing from the post state were deleted, and unchanged balances are
+            // missing from it.
+            let before = pre.and_then(|pre| pre.balance);
+            let after = post.map_or(Some(U256::ZERO), |post| post.balance.or(before));
+            let change = BalanceChange {
+                before: before.unwrap_or_default(),
+                after: after.unwrap_or_default(),
+            };
+            if change.before != change.after {
+                balance_changes.insert(address, change);
+            }
+
+            // Slots missing from the post state were cleared.
+            let mut slots = BTreeMap::<B256, (B256, B256)>::new();
+            for (slot, value) in pre.iter().flat_map(|pre| &pre.storage) {
+                slots.entry(*slot).or_default().0 = *value;
+            }
+            for (slot, value) in post.iter().flat_map(|post| &post.storage) {
+                slots.entry(*slot).or_default().1 = *value;
+            }
+            let changes = slots
+                .into_iter()
+                .filter(|(_, (before, after))| before != after)
+                .map(|(slot, (before, after))| StorageChange {
+                    slot,
+                    before,
+                    after,
+                    decoded: Vec::new(),
+                })
+                .collect::<Vec<_>>();
+            if !changes.is_empty() {
+                storage_changes.insert(address, changes);
+            }
+        }
+
+        Self { gas_estimate, gas_used: trace.gas_used, balance_changes, storage_changes, trace }
+    }
+
+    /// Decodes the storage changes of the contract at the address with its storage layout.
+    ///
+    /// See [`StorageLayout::decode_slot`] for the values that can be decoded.
+    pub fn decode_storage(&mut self, address: Address, layout: &StorageLayout) -> &mut Self {
+        for change in self.storage_changes.get_mut(&address).into_iter().flatten() {
+            let mut after: HashMap<_, _> =
+                layout.decode_slot(change.slot.into(), change.after).into_iter().collect();
+            change.decoded = layout
+                .decode_slot(change.slot.into(), change.before)
+                .into_iter()
+                .filter_map(|(path, before)| {
+                    let after = after.remove(&path)?;
+                    (before != after).then_some(DecodedStorageChange { path, before, after })
+                })
+                .collect();
+        }
+        self
+    }
+
+    /// Returns `true` if the transaction would succeed.
+    pub const fn is_success(&self) -> bool {
+        self.trace.is_success()
+    }
+
+    /// Returns an iterator over the logs the transaction would emit, in call order.
+    pub fn logs(&self) -> impl Iterator<Item = &DecodedTraceLog> {
+        self.trace.iter().flat_map(|trace| &trace.logs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, Bytes};
+    use alloy_rpc_types_trace::geth::AccountState;
+
+    const ALICE: Address = address!("00000000000000000000000000000000000000a1");
+    const TOKEN: Address = address!("00000000000000000000000000000000000000aa");
+
+    fn account(balance: Option<u64>, storage: &[(u8, u8)]) -> AccountState {
+        AccountState {
+            balance: balance.map(U256::from),
+            storage: storage
+                .iter()
+                .map(|(slot, value)| (B256::with_last_byte(*slot), B256::with_last_byte(*value)))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn computes_changes() {
+        let diff = DiffMode {
+            pre: [
+                (ALICE, account(Some(10), &[])),
+                (TOKEN, account(Some(5), &[(0, 0x01), (1, 0x02), (2, 0x03)])),
+            ]
+            .into(),
+            post: [(ALICE, account(Some(7), &[])), (TOKEN, account(None, &[(0, 0x2a), (3, 0x04)]))]
+                .into(),
+        };
+        let trace = DecodedTrace {
+            kind: crate::trace::TraceKind::Call,
+            from: ALICE,
+            to: Some(TOKEN),
+            label: None,
+            value: U256::ZERO,
+            gas_used: 21000,
+            input: Bytes::new(),
+            output: Bytes::new(),
+            error: None,
+            call: None,
+            revert: None,
+            logs: Vec::new(),
+            calls: Vec::new(),
+        };
+        let mut preview = CallPreview::new(Some(30000), diff, trace);
+
+        assert_eq!(preview.gas_used, 21000);
+        assert_eq!(preview.balance_changes.len(), 1);
+        let change = preview.balance_changes[&ALICE];
+        assert_eq!((change.before, change.after), (U256::from(10), U256::from(7)));
+        assert_eq!(change.delta(), I256::try_from(-3).unwrap());
+
+        let changes = &preview.storage_changes[&TOKEN];
+        assert_eq!(
+            changes.iter().map(|c| (c.slot[31], c.before[31], c.after[31])).collect::<Vec<_>>(),
+            [(0, 0x01, 0x2a), (1, 0x02, 0), (2, 0x03, 0), (3, 0, 0x04)]
+        );
+
+        let layout = StorageLayout::from_json(
+            r#"{
+                "storage": [
+                    {"label": "a", "offset": 0, "slot": "0", "type": "t_uint8"},
+                    {"label": "b", "offset": 1, "slot": "0", "type": "t_uint8"}
+                ],
+                "types": {
+                    "t_uint8": {"encoding": "inplace", "label": "uint8", "numberOfBytes": "1"}
+                }
+            }"#,
+        )
+        .unwrap();
+        preview.decode_storage(TOKEN, &layout);
+        let decoded = &preview.storage_changes[&TOKEN][0].decoded;
+        assert_eq!(
+            decoded,
+            &[DecodedStorageChange {
+                path: "a".into(),
+                before: DynSolValue::Uint(U256::from(1), 8),
+                after: DynSolValue::Uint(U256::from(0x2a), 8),
+            }]
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! Detection of proxy contracts and of their implementations.
+//!
+//! See [`ProxyInspector`].
+
+use crate::{Error, Result, SolCallBuilder};
+use alloy_network::{Ethereum, Network};
+use alloy_primitives::{b256, bytes, Address, Bytes, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types_eth::BlockId;
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use std::marker::PhantomData;
+
+/// The [EIP-1967] implementation slot, `keccak256("eip1967.proxy.implementation") - 1`.
+///
+/// [EIP-1967]: https://eips.ethereum.org/EIPS/eip-1967
+pub const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// The [EIP-1967] beacon slot, `keccak256("eip1967.proxy.beacon") - 1`.
+///
+/// [EIP-1967]: https://eips.ethereum.org/EIPS/eip-1967
+pub const EIP1967_BEACON_SLOT: B256 =
+    b256!("a3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+/// The [EIP-1967] admin slot, `keccak256("eip1967.proxy.admin") - 1`.
+///
+/// [EIP-1967]: https://eips.ethereum.org/EIPS/eip-1967
+pub const EIP1967_ADMIN_SLOT: B256 =
+    b256!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
+
+/// The [EIP-1822] (UUPS) implementation slot, `keccak256("PROXIABLE")`.
+///
+/// [EIP-1822]: https://eips.ethereum.org/EIPS/eip-1822
+pub const EIP1822_IMPLEMENTATION_SLOT: B256 =
+    b256!("c5f16f0fcc639fa48a6947836d9850f504798523bf8c9a3a87d5876cf622bcf7");
+
+/// The legacy OpenZeppelin (ZeppelinOS) implementation slot,
+/// `keccak256("org.zeppelinos.proxy.implementation")`.
+pub const OPENZEPPELIN_IMPLEMENTATION_SLOT: B256 =
+    b256!("7050c9e0f4ca769c69bd3a8ef740bc37934f8e2c036e5a723fd8ee048ed3f8c3");
+
+/// The runtime code of an [EIP-1167] minimal proxy before the implementation address.
+///
+/// [EIP-1167]: https://eips.ethereum.org/EIPS/eip-1167
+const EIP1167_PREFIX: Bytes = bytes!("363d3d373d3d3d363d73");
+
+/// The runtime code of an [EIP-1167] minimal proxy after the implementation address.
+///
+/// [EIP-1167]: https://eips.ethereum.org/EIPS/eip-1167
+const EIP1167_SUFFIX: Bytes = bytes!("5af43d82803e903d91602b57fd5bf3");
+
+mod abi {
+    use alloy_sol_types::sol;
+
+    sol! {
+        interface IBeacon {
+            function implementation() external view returns (address);
+        }
+
+        interface ISafeProxy {
+            function masterCopy() external view returns (address);
+        }
+
+        interface IDiamondLoupe {
+            function facetAddresses() external view returns (address[] memory);
+        }
+    }
+}
+
+use abi::{IBeacon, IDiamondLoupe, ISafeProxy};
+
+/// A kind of proxy contract, with its implementation addresses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProxyKind {
+    /// An [EIP-1967](https://eips.ethereum.org/EIPS/eip-1967) proxy, such as OpenZeppelin's
+    /// transparent and UUPS proxies.
+    Eip1967 {
+        /// The implementation address.
+        implementation: Address,
+        /// The admin address, if set.
+        admin: Option<Address>,
+    },
+    /// An [EIP-1967](https://eips.ethereum.org/EIPS/eip-1967) beacon proxy.
+    Eip1967Beacon {
+        /// The beacon address.
+        beacon: Address,
+        /// The implementation address returned by the beacon.
+        implementation: Address,
+    },
+    /// An [EIP-1822](https://eips.ethereum.org/EIPS/eip-1822) UUPS proxy.
+    Eip1822 {
+        /// The implementation address.
+        implementation: Address,
+    },
+    /// A legacy OpenZeppelin (ZeppelinOS) proxy.
+    OpenZeppelin {
+        /// The implementation address.
+        implementation: Address,
+    },
+    /// An [EIP-1167](https://eips.ethereum.org/EIPS/eip-1167) minimal proxy.
+    Minimal {
+        /// The implementation address.
+        implementation: Address,
+    },
+    /// A Safe (formerly Gnosis Safe) proxy.
+    Safe {
+        /// The singleton (master copy) address.
+        singleton: Address,
+    },
+    /// An [EIP-2535](https://eips.ethereum.org/EIPS/eip-2535) diamond.
+    Diamond {
+        /// The facet addresses.
+        facets: Vec<Address>,
+    },
+}
+
+impl ProxyKind {
+    /// Returns the addresses of the contracts the proxy delegates calls to.
+    pub fn implementations(&self) -> Vec<Address> {
+        match self {
+            Self::Eip1967 { implementation, .. }
+            | Self::Eip1967Beacon { implementation, .. }
+            | Self::Eip1822 { implementation }
+            | Self::OpenZeppelin { implementation }
+            | Self::Minimal { implementation } => vec![*implementation],
+            Self::Safe { singleton } => vec![*singleton],
+            Self::Diamond { facets } => facets.clone(),
+        }
+    }
+}
+
+/// Detects proxy contracts and resolves their implementations.
+///
+/// Storage-based proxies are detected by their well-known implementation slots, minimal proxies by
+/// their runtime code, and Safe proxies and diamonds by calling their getters.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn test<P: alloy_contract::private::Provider>(provider: P, address: alloy_primitives::Address) -> Result<(), alloy_contract::Error> {
+/// use alloy_contract::proxy::ProxyInspector;
+///
+/// let inspector = ProxyInspector::new(&provider);
+/// if let Some(proxy) = inspector.detect(address).await? {
+///     println!("{address} is a proxy of {:?}", proxy.implementations());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProxyInspector<P, T, N = Ethereum> {
+    provider: P,
+    _phantom: PhantomData<(T, N)>,
+}
+
+impl<P, T, N> ProxyInspector<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Creates a new proxy inspector.
+    pub const fn new(provider: P) -> Self {
+        Self { provider, _phantom: PhantomData }
+    }
+
+    /// Returns a reference to the provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Detects whether the contract at the address is a proxy, returning its kind and
+    /// implementations.
+    ///
+    /// Returns `None` if the contract is not a known kind of proxy. The state is read at the
+    /// latest block, see [`detect_at`](Self::detect_at).
+    pub async fn detect(&self, address: Address) -> Result<Option<ProxyKind>> {
+        let block = self.provider.get_block_number().await?;
+        self.detect_at(address, block.into()).await
+    }
+
+    /// Detects whether the contract at the address is a proxy at the block, returning its kind
+    /// and implementations.
+    ///
+    /// All the state is read at the block, so that an upgrade in between reads cannot mix the
+    /// state before and after it. Returns `None` if the contract is not a known kind of proxy.
+    pub async fn detect_at(&self, address: Address, block: BlockId) -> Result<Option<ProxyKind>> {
+        let code = self.provider.get_code_at(address).block_id(block).await?;
+        if code.is_empty() {
+            return Ok(None);
+        }
+        if let Some(implementation) = minimal_proxy_implementation(&code) {
+            return Ok(Some(ProxyKind::Minimal { implementation }));
+        }
+
+        let (implementation, beacon, uups, legacy) = futures::try_join!(
+            self.read_address(address, EIP1967_IMPLEMENTATION_SLOT, block),
+            self.read_address(address, EIP1967_BEACON_SLOT, block),
+            self.read_address(address, EIP1822_IMPLEMENTATION_SLOT, block),
+            self.read_address(address, OPENZEPPELIN_IMPLEMENTATION_SLOT, block),
+        )?;
+        if let Some(implementation) = implementation {
+            let admin = self.read_address(address, EIP1967_ADMIN_SLOT, block).await?;
+            return Ok(Some(ProxyKind::Eip1967 { implementation, admin }));
+        }
+        if let Some(beacon) = beacon {
+            let call = IBeacon::implementationCall {};
+            if let Some(implementation) = self.call(beacon, &call, block).await?.map(|r| r._0) {
+                return Ok(Some(ProxyKind::Eip1967Beacon { beacon, implementation }));
+            }
+        }
+        if let Some(implementation) = uups {
+            return Ok(Some(ProxyKind::Eip1822 { implementation }));
+        }
+        if let Some(implementation) = legacy {
+            return Ok(Some(ProxyKind::OpenZeppelin { implementation }));
+        }
+
+        // Safe proxies store the singleton in their first slot, and return it from `masterCopy`.
+        let singleton =
+            self.call(address, &ISafeProxy::masterCopyCall {}, block).await?.map(|r| r._0);
+        if let Some(singleton) = singleton.filter(|singleton| !singleton.is_zero()) {
+            if self.read_address(address, B256::ZERO, block).await? == Some(singleton) {
+                return Ok(Some(ProxyKind::Safe { singleton }));
+            }
+        }
+
+        let facets = self.call(address, &IDiamondLoupe::facetAddressesCall {}, block).await?;
+        if let Some(facets) = facets.map(|r| r._0).filter(|facets| !facets.is_empty()) {
+            return Ok(Some(ProxyKind::Diamond { facets }));
+        }
+
+        Ok(None)
+    }
+
+    /// Reads the address stored in the storage slot, if it is not zero.
+    async fn read_address(
+        &self,
+        address: Address,
+        slot: B256,
+        block: BlockId,
+    ) -> Result<Option<Address>> {
+        let word = self.provider.get_storage_at(address, slot.into()).block_id(block).await?;
+        Ok(Some(Address::from_word(word.into())).filter(|address| !address.is_zero()))
+    }
+
+    /// Calls the contract, returning `None` if the call reverts or its output cannot be decoded.
+    ///
+    /// Other errors, such as transport failures or unrelated node errors, are returned.
+    async fn call<C: SolCall + Unpin>(
+        &self,
+        address: Address,
+        call: &C,
+        block: BlockId,
+    ) -> Result<Option<C::Return>> {
+        match SolCallBuilder::new_sol(&self.provider, &address, call).block(block).call().await {
+            Ok(ret) => Ok(Some(ret)),
+            Err(Error::Revert(_) | Error::AbiError(_)) => Ok(None),
+            // Some nodes omit the data of reverts without a reason.
+            Err(Error::TransportError(err))
+                if err
+                    .as_error_resp()
+                    .is_some_and(|p| p.message.contains("execution reverted")) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Returns the implementation of an [EIP-1167] minimal proxy from its runtime code.
+///
+/// [EIP-1167]: https://eips.ethereum.org/EIPS/eip-1167
+pub fn minimal_proxy_implementation(code: &[u8]) -> Option<Address> {
+    let implementation =
+        code.strip_prefix(&EIP1167_PREFIX[..])?.strip_suffix(&EIP1167_SUFFIX[..])?;
+    (implementation.len() == 20).then(|| Address::from_slice(implementation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{hex, keccak256, U256};
+    use alloy_provider::{ext::AnvilApi, ProviderBuilder};
+    use alloy_rpc_types_eth::TransactionRequest;
+
+    #[test]
+    fn slots() {
+        let eip1967 =
+            |name: &str| B256::from(U256::from_be_bytes(keccak256(name).0) - U256::from(1));
+        assert_eq!(eip1967("eip1967.proxy.implementation"), EIP1967_IMPLEMENTATION_SLOT);
+        assert_eq!(eip1967("eip1967.proxy.beacon"), EIP1967_BEACON_SLOT);
+        assert_eq!(eip1967("eip1967.proxy.admin"), EIP1967_ADMIN_SLOT);
+        assert_eq!(keccak256("PROXIABLE"), EIP1822_IMPLEMENTATION_SLOT);
+        assert_eq!(
+            keccak256("org.zeppelinos.proxy.implementation"),
+            OPENZEPPELIN_IMPLEMENTATION_SLOT
+        );
+    }
+
+    #[test]
+    fn detects_minimal_proxies() {
+        let implementation = Address::repeat_byte(0xbe);
+        let code = [&EIP1167_PREFIX[..], &implementation[..], &EIP1167_SUFFIX[..]].concat();
+        assert_eq!(minimal_proxy_implementation(&code), Some(implementation));
+
+        assert_eq!(minimal_proxy_implementation(&code[..code.len() - 1]), None);
+        assert_eq!(minimal_proxy_implementation(&EIP1167_PREFIX), None);
+    }
+
+    /// Returns the runtime code of a contract delegating all calls to the address stored in the
+    /// storage slot.
+    fn proxy_code(slot: B256) -> Bytes {
+        [&hex!("365f5f375f5f365f7f")[..], &slot[..], &hex!("545af43d5f5f3e6036573d5ffd5b3d5ff3")]
+            .concat()
+            .into()
+    }
+
+    /// Returns the runtime code of a contract returning the address from every call.
+    fn returning_code(address: Address) -> Bytes {
+        [&hex!("73")[..], &address[..], &hex!("5f5260205ff3")].concat().into()
+    }
+
+    #[tokio::test]
+    async fn detects_eip1967_proxies() {
+        let provider = ProviderBuilder::new().on_anvil();
+        let proxy = Address::repeat_byte(0x01);
+        let implementation = Address::repeat_byte(0x02);
+        let admin = Address::repeat_byte(0x03);
+
+        provider.anvil_set_code(proxy, proxy_code(EIP1967_IMPLEMENTATION_SLOT)).await.unwrap();
+        provider.anvil_set_code(implementation, returning_code(admin)).await.unwrap();
+        let inspector = ProxyInspector::new(&provider);
+        assert_eq!(inspector.detect(proxy).await.unwrap(), None);
+
+        let set_slot = |slot: B256, address: Address| {
+            provider.anvil_set_storage_at(proxy, slot.into(), address.into_word())
+        };
+        set_slot(EIP1967_IMPLEMENTATION_SLOT, implementation).await.unwrap();
+        assert_eq!(
+            inspector.detect(proxy).await.unwrap(),
+            Some(ProxyKind::Eip1967 { implementation, admin: None })
+        );
+
+        set_slot(EIP1967_ADMIN_SLOT, admin).await.unwrap();
+        assert_eq!(
+            inspector.detect(proxy).await.unwrap(),
+            Some(ProxyKind::Eip1967 { implementation, admin: Some(admin) })
+        );
+
+        // Calls are delegated to the implementation.
+        let output = provider.call(&TransactionRequest::default().to(proxy)).await.unwrap();
+        assert_eq!(output[..], admin.into_word()[..]);
+    }
+
+    #[tokio::test]
+    async fn detects_beacon_proxies() {
+        let provider = ProviderBuilder::new().on_anvil();
+        let proxy = Address::repeat_byte(0x01);
+        let beacon = Address::repeat_byte(0x02);
+        let implementation = Address::repeat_byte(0x03);
+
+        provider.anvil_set_code(proxy, proxy_code(EIP1967_IMPLEMENTATION_SLOT)).await.unwrap();
+        provider
+            .anvil_set_storage_at(proxy, EIP1967_BEACON_SLOT.into(), beacon.into_word())
+            .await
+            .unwrap();
+        provider.anvil_set_code(beacon, returning_code(implementation)).await.unwrap();
+
+        let inspector = ProxyInspector::new(&provider);
+        let kind = inspector.detect(proxy).await.unwrap();
+        assert_eq!(kind, Some(ProxyKind::Eip1967Beacon { beacon, implementation }));
+        assert_eq!(kind.unwrap().implementations(), [implementation]);
+    }
+}
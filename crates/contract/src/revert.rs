@@ -0,0 +1,177 @@
+use alloy_dyn_abi::{DynSolValue, ErrorExt};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Bytes, Selector};
+use alloy_sol_types::{GenericContractError, Panic, PanicKind, SolInterface};
+use std::fmt;
+
+/// The revert data of a reverted contract call.
+///
+/// The data is decoded as `Error(string)` or `Panic(uint256)` when created, and as one of the
+/// custom errors of the contract's ABI when the call was made through a [`ContractInstance`].
+///
+/// [`ContractInstance`]: crate::ContractInstance
+#[derive(Clone, Debug, PartialEq)]
+pub struct Revert {
+    data: Bytes,
+    decoded: Option<DecodedRevert>,
+}
+
+/// A decoded [`Revert`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedRevert {
+    /// A revert reason, from `revert(reason)` or `require(condition, reason)`.
+    Reason(String),
+    /// A panic, from a failed assertion or an internal check such as arithmetic overflow.
+    Panic(Panic),
+    /// A custom error of the contract's ABI.
+    Custom {
+        /// The error definition.
+        error: alloy_json_abi::Error,
+        /// The decoded error arguments.
+        args: Vec<DynSolValue>,
+    },
+}
+
+impl Revert {
+    /// Creates a new revert from the raw revert data, decoding `Error(string)` and
+    /// `Panic(uint256)`.
+    pub fn new(data: Bytes) -> Self {
+        let decoded = match GenericContractError::abi_decode(&data, true) {
+            Ok(GenericContractError::Revert(revert)) => Some(DecodedRevert::Reason(revert.reason)),
+            Ok(GenericContractError::Panic(panic)) => Some(DecodedRevert::Panic(panic)),
+            Err(_) => None,
+        };
+        Self { data, decoded }
+    }
+
+    /// Decodes the revert data as one of the custom errors of the ABI, if it was not decoded yet.
+    pub fn decode_with_abi(mut self, abi: &JsonAbi) -> Self {
+        if self.decoded.is_none() {
+            self.decoded = abi.errors().find_map(|error| {
+                let decoded = error.decode_error(&self.data).ok()?;
+                Some(DecodedRevert::Custom { error: error.clone(), args: decoded.body })
+            });
+        }
+        self
+    }
+
+    /// Returns the raw revert data.
+    pub const fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Returns the selector of the revert data, if any.
+    pub fn selector(&self) -> Option<Selector> {
+        self.data.get(..4).map(Selector::from_slice)
+    }
+
+    /// Returns the decoded revert, if it could be decoded.
+    pub const fn decoded(&self) -> Option<&DecodedRevert> {
+        self.decoded.as_ref()
+    }
+
+    /// Returns the revert reason, if the call reverted with `Error(string)`.
+    pub fn reason(&self) -> Option<&str> {
+        match &self.decoded {
+            Some(DecodedRevert::Reason(reason)) => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Returns the panic kind, if the call panicked with a known panic code.
+    pub fn panic_kind(&self) -> Option<PanicKind> {
+        match &self.decoded {
+            Some(DecodedRevert::Panic(panic)) => panic.kind(),
+            _ => None,
+        }
+    }
+
+    /// Decodes the revert data as one of the errors of the interface, e.g. the `Errors` enum
+    /// generated by [`sol!`](alloy_sol_types::sol) for a contract.
+    pub fn decode<E: SolInterface>(&self) -> Option<E> {
+        E::abi_decode(&self.data, true).ok()
+    }
+}
+
+impl fmt::Display for Revert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("execution reverted")?;
+        match &self.decoded {
+            Some(DecodedRevert::Reason(reason)) => write!(f, ": {reason}"),
+            Some(DecodedRevert::Panic(panic)) => write!(f, ": {panic}"),
+            Some(DecodedRevert::Custom { error, args }) => {
+                write!(f, ": {}(", error.name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{arg:?}")?;
+                }
+                f.write_str(")")
+            }
+            None if self.data.is_empty() => Ok(()),
+            None => write!(f, ": {}", self.data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+    use alloy_sol_types::{sol, SolError};
+
+    sol! {
+        interface IToken {
+            error InsufficientBalance(address account, uint256 balance);
+        }
+    }
+
+    #[test]
+    fn decodes_builtin_reverts() {
+        let revert = Revert::new(alloy_sol_types::Revert::from("not owner").abi_encode().into());
+        assert_eq!(revert.reason(), Some("not owner"));
+        assert_eq!(revert.to_string(), "execution reverted: not owner");
+
+        let panic = Panic::from(PanicKind::UnderOverflow);
+        let revert = Revert::new(panic.abi_encode().into());
+        assert_eq!(revert.panic_kind(), Some(PanicKind::UnderOverflow));
+        assert_eq!(revert.decoded(), Some(&DecodedRevert::Panic(panic)));
+
+        let revert = Revert::new(Bytes::new());
+        assert_eq!(revert.decoded(), None);
+        assert_eq!(revert.to_string(), "execution reverted");
+    }
+
+    #[test]
+    fn decodes_custom_errors() {
+        let error = IToken::InsufficientBalance { account: Address::ZERO, balance: U256::from(1) };
+        let revert = Revert::new(error.abi_encode().into());
+        assert_eq!(revert.decoded(), None);
+
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[
+                {"type":"error","name":"Unauthorized","inputs":[]},
+                {"type":"error","name":"InsufficientBalance","inputs":[
+                    {"name":"account","type":"address"},
+                    {"name":"balance","type":"uint256"}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+        let revert = revert.decode_with_abi(&abi);
+        let Some(DecodedRevert::Custom { error, args }) = revert.decoded() else {
+            panic!("not decoded: {revert:?}");
+        };
+        assert_eq!(error.name, "InsufficientBalance");
+        assert_eq!(args, &[Address::ZERO.into(), U256::from(1).into()]);
+
+        assert!(matches!(
+            revert.decode::<IToken::ITokenErrors>(),
+            Some(IToken::ITokenErrors::InsufficientBalance(IToken::InsufficientBalance {
+                account: Address::ZERO,
+                balance
+            })) if balance == U256::from(1)
+        ));
+    }
+}
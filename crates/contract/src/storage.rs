@@ -0,0 +1,916 @@
+//! Typed reads of contract storage, following the storage layout output by `solc`.
+//!
+//! See [`StorageReader`].
+
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_network::{Ethereum, Network};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_client::BatchRequest;
+use alloy_rpc_types_eth::BlockId;
+use alloy_transport::{Transport, TransportError};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Deserializer};
+use std::{
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+/// The maximum number of storage slots fetched in a single JSON-RPC batch.
+const BATCH_SIZE: usize = 100;
+
+/// The default maximum length of the dynamic arrays read.
+const DEFAULT_MAX_ARRAY_LEN: usize = 1024;
+
+/// The default maximum length of the `bytes` and `string` values read, in bytes.
+const DEFAULT_MAX_BYTES_LEN: usize = 32 * 1024;
+
+/// An error that occurred while reading storage.
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    /// The storage layout could not be parsed.
+    #[error("invalid storage layout: {0}")]
+    InvalidLayout(String),
+    /// The path could not be parsed.
+    #[error("invalid path: {0}")]
+    InvalidPath(String),
+    /// No state variable has the name.
+    #[error("unknown state variable {0}")]
+    UnknownVariable(String),
+    /// The type has no member with the name.
+    #[error("{ty} has no member {member}")]
+    UnknownMember {
+        /// The type label.
+        ty: String,
+        /// The member name.
+        member: String,
+    },
+    /// The type is not a mapping or an array.
+    #[error("{0} cannot be indexed")]
+    NotIndexable(String),
+    /// The index is out of bounds of a static array.
+    #[error("index {index} out of bounds of {ty}")]
+    OutOfBounds {
+        /// The type label.
+        ty: String,
+        /// The index.
+        index: usize,
+    },
+    /// The mapping key or array index could not be parsed.
+    #[error("invalid key {key} for {ty}: {reason}")]
+    InvalidKey {
+        /// The type label of the key.
+        ty: String,
+        /// The key.
+        key: String,
+        /// The reason the key is invalid.
+        reason: String,
+    },
+    /// The type cannot be read as a whole, e.g. a mapping.
+    #[error("{0} cannot be read")]
+    Unreadable(String),
+    /// The length of a dynamic value exceeds the maximum length read.
+    #[error("{ty} of length {len} exceeds the maximum length {max}")]
+    TooLong {
+        /// The type label.
+        ty: String,
+        /// The length of the value.
+        len: usize,
+        /// The maximum length.
+        max: usize,
+    },
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+}
+
+/// The storage layout of a contract, as output by `solc` with the `storageLayout` output
+/// selection.
+#[derive(Clone, Debug, Deserialize)]
+pub struct StorageLayout {
+    storage: Vec<StorageEntry>,
+    #[serde(default)]
+    types: BTreeMap<String, StorageType>,
+}
+
+/// A state variable or struct member.
+#[derive(Clone, Debug, Deserialize)]
+struct StorageEntry {
+    label: String,
+    #[serde(deserialize_with = "from_str")]
+    slot: U256,
+    offset: usize,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+/// A type of the storage layout.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageType {
+    encoding: Encoding,
+    label: String,
+    #[serde(deserialize_with = "from_str")]
+    number_of_bytes: usize,
+    key: Option<String>,
+    value: Option<String>,
+    base: Option<String>,
+    members: Option<Vec<StorageEntry>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Encoding {
+    Inplace,
+    Mapping,
+    DynamicArray,
+    Bytes,
+}
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+}
+
+/// The location of a value in storage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageLocation {
+    /// The first slot of the value.
+    pub slot: U256,
+    /// The byte offset of the value in the slot, from the right, if it is packed.
+    pub offset: usize,
+    /// The type of the value in the storage layout.
+    pub ty: String,
+}
+
+/// A segment of a path.
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Member(&'a str),
+    Index(&'a str),
+}
+
+/// Parses a path such as `balances[0xabc].amount` into its segments.
+fn parse_path(path: &str) -> Result<Vec<Segment<'_>>, StorageError> {
+    let invalid = || StorageError::InvalidPath(path.to_string());
+    let mut segments = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let end = index.find(']').ok_or_else(invalid)?;
+            let key = index[..end].trim();
+            let key = key.strip_prefix('"').and_then(|key| key.strip_suffix('"')).unwrap_or(key);
+            segments.push(Segment::Index(key));
+            rest = &index[end + 1..];
+        } else {
+            let member = rest.strip_prefix('.').unwrap_or(rest);
+            if segments.is_empty() == (member.len() != rest.len()) {
+                // Members are separated by dots, except the first one.
+                return Err(invalid());
+            }
+            let end = member.find(['.', '[']).unwrap_or(member.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(Segment::Member(&member[..end]));
+            rest = &member[end..];
+        }
+    }
+    if !matches!(segments.first(), Some(Segment::Member(_))) {
+        return Err(invalid());
+    }
+    Ok(segments)
+}
+
+impl StorageLayout {
+    /// Parses the storage layout from its JSON representation.
+    pub fn from_json(json: &str) -> Result<Self, StorageError> {
+        serde_json::from_str(json).map_err(|e| StorageError::InvalidLayout(e.to_string()))
+    }
+
+    /// Computes the location of the value at the path.
+    ///
+    /// Paths start with the name of a state variable, followed by struct members such as
+    /// `.amount`, mapping keys such as `[0xabc]` or `["name"]`, and array indices such as `[3]`.
+    /// Indices of dynamic arrays are not checked against their length.
+    pub fn locate(&self, path: &str) -> Result<StorageLocation, StorageError> {
+        let mut segments = parse_path(path)?.into_iter();
+        let Some(Segment::Member(name)) = segments.next() else { unreachable!() };
+        let entry = self
+            .storage
+            .iter()
+            .find(|entry| entry.label == name)
+            .ok_or_else(|| StorageError::UnknownVariable(name.to_string()))?;
+
+        let mut location =
+            StorageLocation { slot: entry.slot, offset: entry.offset, ty: entry.ty.clone() };
+        for segment in segments {
+            let ty = self.ty(&location.ty)?;
+            location = match segment {
+                Segment::Member(member) => {
+                    let entry = ty
+                        .members
+                        .iter()
+                        .flatten()
+                        .find(|entry| entry.label == member)
+                        .ok_or_else(|| StorageError::UnknownMember {
+                            ty: ty.label.clone(),
+                            member: member.to_string(),
+                        })?;
+                    StorageLocation {
+                        slot: location.slot + entry.slot,
+                        offset: entry.offset,
+                        ty: entry.ty.clone(),
+                    }
+                }
+                Segment::Index(key) => match (ty.encoding, &ty.key, &ty.value, &ty.base) {
+                    (Encoding::Mapping, Some(key_ty), Some(value), _) => {
+                        let key = mapping_key(&self.ty(key_ty)?.label, key)?;
+                        let slot =
+                            keccak256([&key[..], &location.slot.to_be_bytes::<32>()].concat());
+                        StorageLocation { slot: slot.into(), offset: 0, ty: value.clone() }
+                    }
+                    (Encoding::DynamicArray, _, _, Some(base)) => {
+                        let index = parse_index(&ty.label, key)?;
+                        let start = keccak256(location.slot.to_be_bytes::<32>()).into();
+                        self.element(start, base, index)?
+                    }
+                    (Encoding::Inplace, _, _, Some(base)) => {
+                        let index = parse_index(&ty.label, key)?;
+                        if index >= self.static_len(ty, base)? {
+                            return Err(StorageError::OutOfBounds { ty: ty.label.clone(), index });
+                        }
+                        self.element(location.slot, base, index)?
+                    }
+                    _ => return Err(StorageError::NotIndexable(ty.label.clone())),
+                },
+            };
+        }
+        Ok(location)
+    }
+
+    /// Decodes the values stored in the slot, returning the path of each value.
+    ///
+    /// Only values at statically known slots are decoded: state variables, and the members and
+    /// elements of structs and static arrays. The lengths of dynamic arrays are decoded at paths
+    /// such as `arr.length`. Values of mappings and dynamic arrays, and `bytes` and `string` values
+    /// are not decoded.
+    pub fn decode_slot(&self, slot: U256, word: B256) -> Vec<(String, DynSolValue)> {
+        let mut values = Vec::new();
+        for entry in &self.storage {
+            self.decode_slot_inner(
+                &entry.ty,
+                entry.slot,
+                entry.offset,
+                &entry.label,
+                slot,
+                word,
+                &mut values,
+            );
+        }
+        values
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn decode_slot_inner(
+        &self,
+        ty_id: &str,
+        start: U256,
+        offset: usize,
+        path: &str,
+        slot: U256,
+        word: B256,
+        values: &mut Vec<(String, DynSolValue)>,
+    ) {
+        let Ok(ty) = self.ty(ty_id) else { return };
+        let slots = U256::from(ty.number_of_bytes.div_ceil(32).max(1));
+        if slot < start || slot >= start + slots {
+            return;
+        }
+        match (ty.encoding, &ty.members, &ty.base) {
+            (Encoding::Inplace, Some(members), _) => {
+                for member in members {
+                    let path = format!("{path}.{}", member.label);
+                    let start = start + member.slot;
+                    self.decode_slot_inner(
+                        &member.ty,
+                        start,
+                        member.offset,
+                        &path,
+                        slot,
+                        word,
+                        values,
+                    );
+                }
+            }
+            (Encoding::Inplace, None, Some(base)) => {
+                let (Ok(len), Ok(element)) = (self.static_len(ty, base), self.ty(base)) else {
+                    return;
+                };
+                let size = element.number_of_bytes;
+                let slot_index: usize = (slot - start).to();
+                let indices = if size <= 16 {
+                    let per_slot = 32 / size;
+                    slot_index * per_slot..(slot_index * per_slot + per_slot).min(len)
+                } else {
+                    let index = slot_index / size.div_ceil(32);
+                    index..(index + 1).min(len)
+                };
+                for index in indices {
+                    let Ok(element) = self.element(start, base, index) else { return };
+                    let path = format!("{path}[{index}]");
+                    self.decode_slot_inner(
+                        base,
+                        element.slot,
+                        element.offset,
+                        &path,
+                        slot,
+                        word,
+                        values,
+                    );
+                }
+            }
+            (Encoding::Inplace, None, None) => {
+                let value = value_type(&ty.label)
+                    .and_then(|value_ty| decode_word(&value_ty, word, offset, ty.number_of_bytes));
+                if let Some(value) = value {
+                    values.push((path.to_string(), value));
+                }
+            }
+            (Encoding::DynamicArray, ..) => {
+                values.push((format!("{path}.length"), U256::from_be_bytes(word.0).into()));
+            }
+            _ => {}
+        }
+    }
+
+    fn ty(&self, id: &str) -> Result<&StorageType, StorageError> {
+        self.types.get(id).ok_or_else(|| StorageError::InvalidLayout(format!("missing type {id}")))
+    }
+
+    /// Returns the location of the element of an array whose elements start at `start`.
+    ///
+    /// Elements of at most 16 bytes are packed in slots.
+    fn element(
+        &self,
+        start: U256,
+        base: &str,
+        index: usize,
+    ) -> Result<StorageLocation, StorageError> {
+        let size = self.ty(base)?.number_of_bytes;
+        let (slot, offset) = if size <= 16 {
+            let per_slot = 32 / size;
+            (start + U256::from(index / per_slot), index % per_slot * size)
+        } else {
+            (start + U256::from(index * size.div_ceil(32)), 0)
+        };
+        Ok(StorageLocation { slot, offset, ty: base.to_string() })
+    }
+
+    /// Returns the length of a static array.
+    fn static_len(&self, ty: &StorageType, base: &str) -> Result<usize, StorageError> {
+        let size = self.ty(base)?.number_of_bytes;
+        Ok(if size <= 16 {
+            // The length of packed arrays is only known from their label.
+            ty.label
+                .rsplit_once('[')
+                .and_then(|(_, len)| len.strip_suffix(']')?.parse().ok())
+                .unwrap_or(ty.number_of_bytes / 32 * (32 / size))
+        } else {
+            ty.number_of_bytes / size.div_ceil(32).max(1) / 32
+        })
+    }
+}
+
+/// Returns the value type of the type label, if it is one.
+fn value_type(label: &str) -> Option<DynSolType> {
+    if label.starts_with("contract ") || label == "address payable" {
+        Some(DynSolType::Address)
+    } else if label.starts_with("enum ") {
+        Some(DynSolType::Uint(8))
+    } else {
+        DynSolType::parse(label).ok()
+    }
+}
+
+/// Returns the bytes hashed with the mapping slot for the key.
+fn mapping_key(label: &str, key: &str) -> Result<Vec<u8>, StorageError> {
+    let invalid = |reason: String| StorageError::InvalidKey {
+        ty: label.to_string(),
+        key: key.to_string(),
+        reason,
+    };
+    let ty = value_type(label).ok_or_else(|| invalid("unsupported key type".into()))?;
+    let value = ty.coerce_str(key).map_err(|e| invalid(e.to_string()))?;
+    match (value.as_word(), value.as_packed_seq()) {
+        (Some(word), _) => Ok(word.to_vec()),
+        (None, Some(bytes)) => Ok(bytes.to_vec()),
+        (None, None) => Err(invalid("unsupported key type".into())),
+    }
+}
+
+fn parse_index(label: &str, index: &str) -> Result<usize, StorageError> {
+    index.parse().map_err(|e: std::num::ParseIntError| StorageError::InvalidKey {
+        ty: label.to_string(),
+        key: index.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Returns an error if the length of the dynamic value exceeds the maximum length.
+fn check_len(ty: &StorageType, len: usize, max: usize) -> Result<(), StorageError> {
+    if len > max {
+        return Err(StorageError::TooLong { ty: ty.label.clone(), len, max });
+    }
+    Ok(())
+}
+
+/// Decodes a value type from its slot.
+fn decode_word(ty: &DynSolType, word: B256, offset: usize, size: usize) -> Option<DynSolValue> {
+    let end = 32usize.checked_sub(offset)?;
+    let bytes = word.get(end.checked_sub(size)?..end)?;
+    let mut padded = B256::ZERO;
+    match ty {
+        // Fixed bytes are left-aligned.
+        DynSolType::FixedBytes(_) => padded[..size].copy_from_slice(bytes),
+        DynSolType::Int(_) if bytes[0] & 0x80 != 0 => {
+            padded = B256::repeat_byte(0xff);
+            padded[32 - size..].copy_from_slice(bytes);
+        }
+        _ => padded[32 - size..].copy_from_slice(bytes),
+    }
+    ty.abi_decode(&padded[..]).ok()
+}
+
+/// Reads typed values from the storage of a contract, following its [`StorageLayout`].
+///
+/// The slots of a value are fetched in JSON-RPC batches, and packed values are decoded into
+/// [`DynSolValue`]s. Structs are read as tuples, and arrays as arrays. Mappings cannot be read as
+/// a whole, only their values.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn test<P: alloy_contract::private::Provider>(provider: P, address: alloy_primitives::Address, json: &str) -> Result<(), alloy_contract::storage::StorageError> {
+/// use alloy_contract::storage::{StorageLayout, StorageReader};
+///
+/// let layout = StorageLayout::from_json(json)?;
+/// let reader = StorageReader::new(&provider, address, layout);
+/// let amount = reader.read("balances[0x0000000000000000000000000000000000000abc].amount").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StorageReader<P, T, N = Ethereum> {
+    provider: P,
+    address: Address,
+    layout: StorageLayout,
+    block: BlockId,
+    max_array_len: usize,
+    max_bytes_len: usize,
+    _phantom: PhantomData<(T, N)>,
+}
+
+impl<P, T, N> StorageReader<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Creates a new reader of the storage of the contract at the address.
+    pub fn new(provider: P, address: Address, layout: StorageLayout) -> Self {
+        Self {
+            provider,
+            address,
+            layout,
+            block: BlockId::default(),
+            max_array_len: DEFAULT_MAX_ARRAY_LEN,
+            max_bytes_len: DEFAULT_MAX_BYTES_LEN,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the block to read the storage at.
+    pub const fn block(mut self, block: BlockId) -> Self {
+        self.block = block;
+        self
+    }
+
+    /// Sets the maximum length of the dynamic arrays read. Longer arrays are not fetched, and
+    /// return [`StorageError::TooLong`].
+    ///
+    /// Defaults to 1024.
+    pub const fn max_array_len(mut self, max_array_len: usize) -> Self {
+        self.max_array_len = max_array_len;
+        self
+    }
+
+    /// Sets the maximum length in bytes of the `bytes` and `string` values read. Longer values
+    /// are not fetched, and return [`StorageError::TooLong`].
+    ///
+    /// Defaults to 32 KiB.
+    pub const fn max_bytes_len(mut self, max_bytes_len: usize) -> Self {
+        self.max_bytes_len = max_bytes_len;
+        self
+    }
+
+    /// Returns the storage layout.
+    pub const fn layout(&self) -> &StorageLayout {
+        &self.layout
+    }
+
+    /// Reads the value at the path.
+    ///
+    /// See [`StorageLayout::locate`] for the syntax of paths.
+    pub async fn read(&self, path: &str) -> Result<DynSolValue, StorageError> {
+        let location = self.layout.locate(path)?;
+        let mut words = HashMap::new();
+        self.read_at(&mut words, location.slot, location.offset, &location.ty).await
+    }
+
+    /// Reads the value of the type at the location, caching the fetched slots in `words`.
+    fn read_at<'a>(
+        &'a self,
+        words: &'a mut HashMap<U256, B256>,
+        slot: U256,
+        offset: usize,
+        ty_id: &'a str,
+    ) -> BoxFuture<'a, Result<DynSolValue, StorageError>>
+    where
+        P: Sync,
+    {
+        Box::pin(async move {
+            let ty = self.layout.ty(ty_id)?;
+            match (ty.encoding, &ty.members, &ty.base) {
+                (Encoding::Mapping, ..) => Err(StorageError::Unreadable(ty.label.clone())),
+                (Encoding::Bytes, ..) => self.read_bytes(words, slot, ty).await,
+                (Encoding::DynamicArray, _, Some(base)) => {
+                    self.fetch(words, slot, 1).await?;
+                    let len: usize = U256::from_be_bytes(words[&slot].0)
+                        .try_into()
+                        .map_err(|_| StorageError::Unreadable(ty.label.clone()))?;
+                    check_len(ty, len, self.max_array_len)?;
+                    let start = keccak256(slot.to_be_bytes::<32>()).into();
+                    let values = self.read_elements(words, start, base, len).await?;
+                    Ok(DynSolValue::Array(values))
+                }
+                (Encoding::Inplace, Some(members), _) => {
+                    self.fetch(words, slot, ty.number_of_bytes.div_ceil(32)).await?;
+                    let mut values = Vec::with_capacity(members.len());
+                    for member in members {
+                        values.push(
+                            self.read_at(words, slot + member.slot, member.offset, &member.ty)
+                                .await?,
+                        );
+                    }
+                    Ok(DynSolValue::Tuple(values))
+                }
+                (Encoding::Inplace, _, Some(base)) => {
+                    let len = self.layout.static_len(ty, base)?;
+                    let values = self.read_elements(words, slot, base, len).await?;
+                    Ok(DynSolValue::FixedArray(values))
+                }
+                (Encoding::Inplace, None, None) => {
+                    let value_ty = value_type(&ty.label)
+                        .ok_or_else(|| StorageError::Unreadable(ty.label.clone()))?;
+                    self.fetch(words, slot, 1).await?;
+                    decode_word(&value_ty, words[&slot], offset, ty.number_of_bytes)
+                        .ok_or_else(|| StorageError::Unreadable(ty.label.clone()))
+                }
+                _ => Err(StorageError::InvalidLayout(format!("invalid type {ty_id}"))),
+            }
+        })
+    }
+
+    /// Reads the elements of an array whose elements start at `start`.
+    async fn read_elements(
+        &self,
+        words: &mut HashMap<U256, B256>,
+        start: U256,
+        base: &str,
+        len: usize,
+    ) -> Result<Vec<DynSolValue>, StorageError>
+    where
+        P: Sync,
+    {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let last = self.layout.element(start, base, len - 1)?;
+        let size = self.layout.ty(base)?.number_of_bytes;
+        let count = last.slot - start + U256::from(size.div_ceil(32));
+        self.fetch(words, start, count.to()).await?;
+
+        let mut values = Vec::with_capacity(len);
+        for index in 0..len {
+            let element = self.layout.element(start, base, index)?;
+            values.push(self.read_at(words, element.slot, element.offset, base).await?);
+        }
+        Ok(values)
+    }
+
+    /// Reads a `bytes` or `string` value.
+    ///
+    /// Values shorter than 32 bytes are stored in the slot with twice their length in the lowest
+    /// byte. Longer values store twice their length plus one in the slot, and their data from the
+    /// slot at the hash of the slot.
+    async fn read_bytes(
+        &self,
+        words: &mut HashMap<U256, B256>,
+        slot: U256,
+        ty: &StorageType,
+    ) -> Result<DynSolValue, StorageError> {
+        self.fetch(words, slot, 1).await?;
+        let word = words[&slot];
+        let data = if word[31] & 1 == 0 {
+            word.get(..(word[31] / 2) as usize)
+                .ok_or_else(|| StorageError::Unreadable(ty.label.clone()))?
+                .to_vec()
+        } else {
+            let len: usize = (U256::from_be_bytes(word.0) / U256::from(2))
+                .try_into()
+                .map_err(|_| StorageError::Unreadable(ty.label.clone()))?;
+            check_len(ty, len, self.max_bytes_len)?;
+            let start: U256 = keccak256(slot.to_be_bytes::<32>()).into();
+            self.fetch(words, start, len.div_ceil(32)).await?;
+            let mut data: Vec<u8> =
+                (0..len.div_ceil(32)).flat_map(|i| words[&(start + U256::from(i))].0).collect();
+            data.truncate(len);
+            data
+        };
+        Ok(if ty.label == "string" {
+            DynSolValue::String(String::from_utf8_lossy(&data).into_owned())
+        } else {
+            DynSolValue::Bytes(data)
+        })
+    }
+
+    /// Fetches the `count` slots from `start` that are not fetched yet, in JSON-RPC batches.
+    async fn fetch(
+        &self,
+        words: &mut HashMap<U256, B256>,
+        start: U256,
+        count: usize,
+    ) -> Result<(), StorageError> {
+        let slots: Vec<_> = (0..count)
+            .map(|i| start + U256::from(i))
+            .filter(|slot| !words.contains_key(slot))
+            .collect();
+        for chunk in slots.chunks(BATCH_SIZE) {
+            let mut batch = BatchRequest::new(self.provider.client());
+            let waiters = chunk
+                .iter()
+                .map(|slot| {
+                    batch
+                        .add_call::<_, U256>("eth_getStorageAt", &(self.address, *slot, self.block))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            batch.send().await?;
+            for (slot, waiter) in chunk.iter().zip(waiters) {
+                words.insert(*slot, B256::from(waiter.await?));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256, hex};
+
+    /// The storage layout of:
+    ///
+    /// ```solidity
+    /// contract C {
+    ///     struct Account { uint128 amount; bool frozen; address owner; }
+    ///     uint8 a;
+    ///     int16 b;
+    ///     mapping(address => Account) balances;
+    ///     uint64[] arr;
+    ///     Account[2] accounts;
+    ///     mapping(string => mapping(uint256 => bytes4)) nested;
+    ///     string name;
+    /// }
+    /// ```
+    const LAYOUT: &str = r#"{
+        "storage": [
+            {"astId": 1, "contract": "C.sol:C", "label": "a", "offset": 0, "slot": "0", "type": "t_uint8"},
+            {"astId": 2, "contract": "C.sol:C", "label": "b", "offset": 1, "slot": "0", "type": "t_int16"},
+            {"astId": 3, "contract": "C.sol:C", "label": "balances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_struct(Account)10_storage)"},
+            {"astId": 4, "contract": "C.sol:C", "label": "arr", "offset": 0, "slot": "2", "type": "t_array(t_uint64)dyn_storage"},
+            {"astId": 5, "contract": "C.sol:C", "label": "accounts", "offset": 0, "slot": "3", "type": "t_array(t_struct(Account)10_storage)2_storage"},
+            {"astId": 6, "contract": "C.sol:C", "label": "nested", "offset": 0, "slot": "7", "type": "t_mapping(t_string_memory_ptr,t_mapping(t_uint256,t_bytes4))"},
+            {"astId": 7, "contract": "C.sol:C", "label": "name", "offset": 0, "slot": "8", "type": "t_string_storage"}
+        ],
+        "types": {
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+            "t_bytes4": {"encoding": "inplace", "label": "bytes4", "numberOfBytes": "4"},
+            "t_int16": {"encoding": "inplace", "label": "int16", "numberOfBytes": "2"},
+            "t_uint8": {"encoding": "inplace", "label": "uint8", "numberOfBytes": "1"},
+            "t_uint64": {"encoding": "inplace", "label": "uint64", "numberOfBytes": "8"},
+            "t_uint128": {"encoding": "inplace", "label": "uint128", "numberOfBytes": "16"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"},
+            "t_string_memory_ptr": {"encoding": "bytes", "label": "string", "numberOfBytes": "32"},
+            "t_string_storage": {"encoding": "bytes", "label": "string", "numberOfBytes": "32"},
+            "t_array(t_uint64)dyn_storage": {"encoding": "dynamic_array", "label": "uint64[]", "numberOfBytes": "32", "base": "t_uint64"},
+            "t_array(t_struct(Account)10_storage)2_storage": {"encoding": "inplace", "label": "struct C.Account[2]", "numberOfBytes": "128", "base": "t_struct(Account)10_storage"},
+            "t_mapping(t_address,t_struct(Account)10_storage)": {"encoding": "mapping", "label": "mapping(address => struct C.Account)", "numberOfBytes": "32", "key": "t_address", "value": "t_struct(Account)10_storage"},
+            "t_mapping(t_string_memory_ptr,t_mapping(t_uint256,t_bytes4))": {"encoding": "mapping", "label": "mapping(string => mapping(uint256 => bytes4))", "numberOfBytes": "32", "key": "t_string_memory_ptr", "value": "t_mapping(t_uint256,t_bytes4)"},
+            "t_mapping(t_uint256,t_bytes4)": {"encoding": "mapping", "label": "mapping(uint256 => bytes4)", "numberOfBytes": "32", "key": "t_uint256", "value": "t_bytes4"},
+            "t_struct(Account)10_storage": {"encoding": "inplace", "label": "struct C.Account", "numberOfBytes": "64", "members": [
+                {"astId": 8, "contract": "C.sol:C", "label": "amount", "offset": 0, "slot": "0", "type": "t_uint128"},
+                {"astId": 9, "contract": "C.sol:C", "label": "frozen", "offset": 16, "slot": "0", "type": "t_bool"},
+                {"astId": 10, "contract": "C.sol:C", "label": "owner", "offset": 0, "slot": "1", "type": "t_address"}
+            ]}
+        }
+    }"#;
+
+    fn slot(n: u64) -> U256 {
+        U256::from(n)
+    }
+
+    #[test]
+    fn parses_paths() {
+        assert_eq!(
+            parse_path(r#"a.b[0x01]["x y"][3].c"#).unwrap(),
+            [
+                Segment::Member("a"),
+                Segment::Member("b"),
+                Segment::Index("0x01"),
+                Segment::Index("x y"),
+                Segment::Index("3"),
+                Segment::Member("c"),
+            ]
+        );
+        for path in ["", ".a", "[1]", "a..b", "a[1", "a[1]b"] {
+            assert!(parse_path(path).is_err(), "{path}");
+        }
+    }
+
+    #[test]
+    fn locates_values() {
+        let layout = StorageLayout::from_json(LAYOUT).unwrap();
+
+        let b = layout.locate("b").unwrap();
+        assert_eq!((b.slot, b.offset), (slot(0), 1));
+
+        let owner = address!("0000000000000000000000000000000000000abc");
+        let account: U256 = keccak256([owner.into_word().0, slot(1).to_be_bytes()].concat()).into();
+        let amount = layout.locate("balances[0x0000000000000000000000000000000000000abc].amount");
+        assert_eq!(
+            amount.unwrap(),
+            StorageLocation { slot: account, offset: 0, ty: "t_uint128".into() }
+        );
+        let frozen = layout.locate("balances[0x0000000000000000000000000000000000000abc].frozen");
+        assert_eq!((frozen.as_ref().unwrap().slot, frozen.unwrap().offset), (account, 16));
+        let owner = layout.locate("balances[0x0000000000000000000000000000000000000abc].owner");
+        assert_eq!(owner.unwrap().slot, account + slot(1));
+
+        // Four `uint64` per slot.
+        let start: U256 = keccak256(slot(2).to_be_bytes::<32>()).into();
+        let element = layout.locate("arr[5]").unwrap();
+        assert_eq!((element.slot, element.offset), (start + slot(1), 8));
+
+        // Two slots per `Account`.
+        assert_eq!(layout.locate("accounts[1].owner").unwrap().slot, slot(6));
+        assert!(matches!(
+            layout.locate("accounts[2]"),
+            Err(StorageError::OutOfBounds { index: 2, .. })
+        ));
+
+        let outer: U256 = keccak256([&b"key"[..], &slot(7).to_be_bytes::<32>()].concat()).into();
+        let inner: U256 =
+            keccak256([slot(3).to_be_bytes::<32>(), outer.to_be_bytes()].concat()).into();
+        let nested = layout.locate(r#"nested["key"][3]"#).unwrap();
+        assert_eq!(nested, StorageLocation { slot: inner, offset: 0, ty: "t_bytes4".into() });
+
+        assert!(matches!(layout.locate("c"), Err(StorageError::UnknownVariable(_))));
+        assert!(matches!(layout.locate("a[0]"), Err(StorageError::NotIndexable(_))));
+        assert!(matches!(layout.locate("balances[1].x"), Err(StorageError::InvalidKey { .. })));
+        assert!(matches!(layout.locate("accounts[0].x"), Err(StorageError::UnknownMember { .. })));
+    }
+
+    #[test]
+    fn decodes_slots() {
+        let layout = StorageLayout::from_json(LAYOUT).unwrap();
+
+        // `uint8 a = 42` followed by `int16 b = -100`.
+        let word = B256::left_padding_from(&hex!("ff9c2a"));
+        assert_eq!(
+            layout.decode_slot(slot(0), word),
+            [
+                ("a".to_string(), DynSolValue::Uint(U256::from(42), 8)),
+                (
+                    "b".to_string(),
+                    DynSolValue::Int(alloy_primitives::I256::try_from(-100).unwrap(), 16)
+                ),
+            ]
+        );
+
+        let word = B256::with_last_byte(3);
+        assert_eq!(
+            layout.decode_slot(slot(2), word),
+            [("arr.length".to_string(), U256::from(3).into())]
+        );
+        let owner = address!("0000000000000000000000000000000000000abc");
+        assert_eq!(
+            layout.decode_slot(slot(6), owner.into_word()),
+            [("accounts[1].owner".to_string(), owner.into())]
+        );
+        assert!(layout.decode_slot(slot(7), word).is_empty());
+        assert!(layout.decode_slot(slot(9), word).is_empty());
+    }
+
+    #[test]
+    fn decodes_packed_values() {
+        // `uint8 a = 42` followed by `int16 b = -100`.
+        let word = B256::left_padding_from(&hex!("ff9c2a"));
+        assert_eq!(
+            decode_word(&DynSolType::Uint(8), word, 0, 1),
+            Some(DynSolValue::Uint(U256::from(42), 8))
+        );
+        assert_eq!(
+            decode_word(&DynSolType::Int(16), word, 1, 2),
+            Some(DynSolValue::Int(alloy_primitives::I256::try_from(-100).unwrap(), 16))
+        );
+
+        let word = b256!("00000000000000000000000000000000000000000000000000000000deadbeef");
+        assert_eq!(
+            decode_word(&DynSolType::FixedBytes(4), word, 0, 4),
+            Some(DynSolValue::FixedBytes(
+                b256!("deadbeef00000000000000000000000000000000000000000000000000000000"),
+                4
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_values() {
+        use alloy_provider::{ext::AnvilApi, ProviderBuilder};
+
+        let provider = ProviderBuilder::new().on_anvil();
+        let address = address!("00000000000000000000000000000000000000cc");
+        let owner = address!("0000000000000000000000000000000000000abc");
+        let name = "a string longer than thirty-two bytes.";
+
+        let arr: U256 = keccak256(slot(2).to_be_bytes::<32>()).into();
+        let name_data: U256 = keccak256(slot(8).to_be_bytes::<32>()).into();
+        let storage = [
+            // `uint8 a = 42` followed by `int16 b = -100`.
+            (slot(0), B256::left_padding_from(&hex!("ff9c2a"))),
+            // `arr = [1, 2, 3, 4, 5]`, four per slot.
+            (slot(2), B256::with_last_byte(5)),
+            (arr, b256!("0000000000000004000000000000000300000000000000020000000000000001")),
+            (arr + slot(1), B256::with_last_byte(5)),
+            // `accounts[1] = Account(7, true, owner)`.
+            (slot(5), b256!("0000000000000000000000000000000100000000000000000000000000000007")),
+            (slot(6), owner.into_word()),
+            // A long `name`, stored from the hash of its slot.
+            (slot(8), B256::with_last_byte(name.len() as u8 * 2 + 1)),
+            (name_data, B256::right_padding_from(&name.as_bytes()[..32])),
+            (name_data + slot(1), B256::right_padding_from(&name.as_bytes()[32..])),
+        ];
+        for (slot, value) in storage {
+            provider.anvil_set_storage_at(address, slot, value).await.unwrap();
+        }
+
+        let layout = StorageLayout::from_json(LAYOUT).unwrap();
+        let reader = StorageReader::new(&provider, address, layout);
+        assert_eq!(reader.read("a").await.unwrap(), DynSolValue::Uint(U256::from(42), 8));
+        assert_eq!(
+            reader.read("arr").await.unwrap(),
+            DynSolValue::Array((1..=5).map(|i| DynSolValue::Uint(U256::from(i), 64)).collect())
+        );
+        let account = |amount: u64, frozen: bool, owner: Address| {
+            DynSolValue::Tuple(vec![
+                DynSolValue::Uint(U256::from(amount), 128),
+                frozen.into(),
+                owner.into(),
+            ])
+        };
+        assert_eq!(
+            reader.read("accounts").await.unwrap(),
+            DynSolValue::FixedArray(vec![
+                account(0, false, Address::ZERO),
+                account(7, true, owner)
+            ])
+        );
+        assert_eq!(reader.read("name").await.unwrap(), DynSolValue::String(name.into()));
+        assert!(matches!(reader.read("balances").await, Err(StorageError::Unreadable(_))));
+
+        let reader = reader.max_array_len(4).max_bytes_len(32);
+        assert!(matches!(reader.read("arr").await, Err(StorageError::TooLong { len: 5, .. })));
+        assert!(matches!(reader.read("name").await, Err(StorageError::TooLong { max: 32, .. })));
+
+        // A short value cannot be longer than 31 bytes.
+        provider.anvil_set_storage_at(address, slot(8), B256::with_last_byte(80)).await.unwrap();
+        assert!(matches!(reader.read("name").await, Err(StorageError::Unreadable(_))));
+    }
+}
@@ -0,0 +1,705 @@
+//! ABI-aware decoding of call traces.
+//!
+//! See [`TraceDecoder`].
+
+use crate::{DecodedRevert, Interface, Revert};
+use alloy_dyn_abi::{DecodedEvent, DynSolValue, EventExt, FunctionExt, JsonAbiExt};
+use alloy_json_abi::{Event, Function, JsonAbi};
+use alloy_primitives::{Address, Bytes, LogData, Selector, U256};
+use alloy_rpc_types_trace::{
+    geth::{CallFrame, CallLogFrame},
+    parity::{Action, CallType, TraceOutput, TransactionTrace},
+};
+use std::{collections::HashMap, fmt};
+
+/// Decodes call traces with the ABIs of the called contracts, or with a database of signatures.
+///
+/// Every frame of the trace is annotated with its decoded function call, return value or revert,
+/// and logs. The decoded trace renders as an indented call tree.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn test(frame: alloy_rpc_types_trace::geth::CallFrame, token: alloy_primitives::Address, abi: alloy_json_abi::JsonAbi) -> Result<(), alloy_json_abi::parser::Error> {
+/// use alloy_contract::trace::TraceDecoder;
+///
+/// let decoder = TraceDecoder::new()
+///     .with_abi(token, abi)
+///     .with_label(token, "Token")
+///     .with_signatures(["function balanceOf(address) returns (uint256)"])?;
+/// println!("{}", decoder.decode_call_frame(&frame));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TraceDecoder {
+    contracts: HashMap<Address, Interface>,
+    labels: HashMap<Address, String>,
+    signatures: Interface,
+}
+
+impl Default for TraceDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceDecoder {
+    /// Creates a new decoder without any ABI.
+    pub fn new() -> Self {
+        Self {
+            contracts: HashMap::new(),
+            labels: HashMap::new(),
+            signatures: Interface::new(JsonAbi::new()),
+        }
+    }
+
+    /// Adds the ABI of the contract at the address.
+    pub fn with_abi(mut self, address: Address, abi: JsonAbi) -> Self {
+        self.contracts.insert(address, Interface::new(abi));
+        self
+    }
+
+    /// Sets the name the contract at the address is rendered with.
+    pub fn with_label(mut self, address: Address, label: impl Into<String>) -> Self {
+        self.labels.insert(address, label.into());
+        self
+    }
+
+    /// Adds the functions, events and errors of the ABI to the signature database, which is
+    /// used to decode calls and logs of contracts without an ABI.
+    pub fn with_signature_abi(mut self, abi: JsonAbi) -> Self {
+        let items = self.signatures.abi().clone().into_items().chain(abi.into_items());
+        self.signatures = Interface::new(items.collect());
+        self
+    }
+
+    /// Adds human-readable signatures, such as `function transfer(address,uint256)` or
+    /// `event Transfer(address indexed,address indexed,uint256)`, to the signature database.
+    ///
+    /// See [`with_signature_abi`](Self::with_signature_abi).
+    pub fn with_signatures<'a>(
+        self,
+        signatures: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, alloy_json_abi::parser::Error> {
+        Ok(self.with_signature_abi(JsonAbi::parse(signatures)?))
+    }
+
+    /// Decodes a trace of the `callTracer` of `debug_traceTransaction` or `debug_traceCall`.
+    ///
+    /// Logs are only decoded if the tracer was configured with
+    /// [`with_log`](alloy_rpc_types_trace::geth::CallConfig::with_log).
+    pub fn decode_call_frame(&self, frame: &CallFrame) -> DecodedTrace {
+        let kind = match frame.typ.to_ascii_uppercase().as_str() {
+            "CREATE" => TraceKind::Create,
+            "CREATE2" => TraceKind::Create2,
+            "DELEGATECALL" => TraceKind::DelegateCall,
+            "STATICCALL" => TraceKind::StaticCall,
+            "CALLCODE" => TraceKind::CallCode,
+            "SELFDESTRUCT" => TraceKind::Selfdestruct,
+            _ => TraceKind::Call,
+        };
+        let mut trace = self.decode_frame(
+            kind,
+            frame.from,
+            frame.to,
+            frame.value.unwrap_or_default(),
+            frame.gas_used.saturating_to(),
+            &frame.input,
+            frame.output.as_ref(),
+            frame.error.clone(),
+        );
+        trace.logs = frame.logs.iter().map(|log| self.decode_log_frame(frame.to, log)).collect();
+        trace.calls = frame.calls.iter().map(|call| self.decode_call_frame(call)).collect();
+        trace
+    }
+
+    /// Decodes the parity style traces of a transaction, as returned by `trace_transaction`.
+    ///
+    /// The traces are nested by their trace addresses. Parity style traces have no logs, and no
+    /// revert data. Returns `None` if there is no top-level trace.
+    pub fn decode_parity_traces(&self, traces: &[TransactionTrace]) -> Option<DecodedTrace> {
+        let mut root: Option<DecodedTrace> = None;
+        for trace in traces {
+            let decoded = self.decode_parity_trace(trace);
+            let Some((_, parents)) = trace.trace_address.split_last() else {
+                root.get_or_insert(decoded);
+                continue;
+            };
+            let mut parent = root.as_mut();
+            for &index in parents {
+                parent = parent.and_then(|parent| parent.calls.get_mut(index));
+            }
+            if let Some(parent) = parent {
+                parent.calls.push(decoded);
+            }
+        }
+        root
+    }
+
+    fn decode_parity_trace(&self, trace: &TransactionTrace) -> DecodedTrace {
+        let output = trace.result.as_ref().map(TraceOutput::output);
+        let gas_used = trace.result.as_ref().map_or(0, TraceOutput::gas_used);
+        let error = trace.error.clone();
+        match &trace.action {
+            Action::Call(call) => {
+                let kind = match call.call_type {
+                    CallType::DelegateCall => TraceKind::DelegateCall,
+                    CallType::StaticCall => TraceKind::StaticCall,
+                    CallType::CallCode => TraceKind::CallCode,
+                    _ => TraceKind::Call,
+                };
+                self.decode_frame(
+                    kind,
+                    call.from,
+                    Some(call.to),
+                    call.value,
+                    gas_used,
+                    &call.input,
+                    output,
+                    error,
+                )
+            }
+            Action::Create(create) => {
+                let to = match &trace.result {
+                    Some(TraceOutput::Create(output)) => Some(output.address),
+                    _ => None,
+                };
+                self.decode_frame(
+                    TraceKind::Create,
+                    create.from,
+                    to,
+                    create.value,
+                    gas_used,
+                    &create.init,
+                    output,
+                    error,
+                )
+            }
+            Action::Selfdestruct(selfdestruct) => self.decode_frame(
+                TraceKind::Selfdestruct,
+                selfdestruct.address,
+                Some(selfdestruct.refund_address),
+                selfdestruct.balance,
+                gas_used,
+                &Bytes::new(),
+                output,
+                error,
+            ),
+            Action::Reward(reward) => self.decode_frame(
+                TraceKind::Call,
+                Address::ZERO,
+                Some(reward.author),
+                reward.value,
+                gas_used,
+                &Bytes::new(),
+                output,
+                error,
+            ),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn decode_frame(
+        &self,
+        kind: TraceKind,
+        from: Address,
+        to: Option<Address>,
+        value: U256,
+        gas_used: u64,
+        input: &Bytes,
+        output: Option<&Bytes>,
+        error: Option<String>,
+    ) -> DecodedTrace {
+        let output = output.cloned().unwrap_or_default();
+        let contract = to.and_then(|to| self.contracts.get(&to));
+
+        let function = (!kind.is_create())
+            .then(|| input.get(..4).map(Selector::from_slice))
+            .flatten()
+            .and_then(|selector| {
+                contract
+                    .and_then(|contract| contract.get_from_selector(&selector).ok())
+                    .or_else(|| self.signatures.get_from_selector(&selector).ok())
+            });
+        let call = function.and_then(|function| {
+            let inputs = function.abi_decode_input(&input[4..], false).ok()?;
+            let outputs = if error.is_none() {
+                function.abi_decode_output(&output, false).ok()
+            } else {
+                None
+            };
+            Some(DecodedCall { function: function.clone(), inputs, outputs })
+        });
+
+        let revert = (error.is_some() && !output.is_empty()).then(|| {
+            let revert = Revert::new(output.clone());
+            let revert = match contract {
+                Some(contract) => revert.decode_with_abi(contract.abi()),
+                None => revert,
+            };
+            revert.decode_with_abi(self.signatures.abi())
+        });
+
+        DecodedTrace {
+            kind,
+            from,
+            to,
+            label: to.and_then(|to| self.labels.get(&to).cloned()),
+            value,
+            gas_used,
+            input: input.clone(),
+            output,
+            error,
+            call,
+            revert,
+            logs: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+
+    fn decode_log_frame(&self, to: Option<Address>, log: &CallLogFrame) -> DecodedTraceLog {
+        let address = log.address.or(to).unwrap_or_default();
+        let data = LogData::new_unchecked(
+            log.topics.clone().unwrap_or_default(),
+            log.data.clone().unwrap_or_default(),
+        );
+        let event = self.decode_event(address, &data);
+        DecodedTraceLog { address, data, position: log.position, event }
+    }
+
+    fn decode_event(&self, address: Address, data: &LogData) -> Option<(Event, DecodedEvent)> {
+        let selector = data.topics().first()?;
+        [self.contracts.get(&address), Some(&self.signatures)].into_iter().flatten().find_map(
+            |interface| {
+                let event = interface.get_event_from_selector(selector).ok()?;
+                if event.anonymous {
+                    return None;
+                }
+                let decoded = event.decode_log(data, false).ok()?;
+                Some((event.clone(), decoded))
+            },
+        )
+    }
+}
+
+/// The kind of a [`DecodedTrace`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceKind {
+    /// A `CALL`.
+    Call,
+    /// A `STATICCALL`.
+    StaticCall,
+    /// A `DELEGATECALL`.
+    DelegateCall,
+    /// A `CALLCODE`.
+    CallCode,
+    /// A `CREATE`.
+    Create,
+    /// A `CREATE2`.
+    Create2,
+    /// A `SELFDESTRUCT`.
+    Selfdestruct,
+}
+
+impl TraceKind {
+    /// Returns `true` if the trace created a contract.
+    pub const fn is_create(&self) -> bool {
+        matches!(self, Self::Create | Self::Create2)
+    }
+}
+
+/// A decoded function call of a [`DecodedTrace`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall {
+    /// The function.
+    pub function: Function,
+    /// The decoded arguments.
+    pub inputs: Vec<DynSolValue>,
+    /// The decoded return values, if the call succeeded.
+    pub outputs: Option<Vec<DynSolValue>>,
+}
+
+/// A decoded log of a [`DecodedTrace`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedTraceLog {
+    /// The address of the contract that emitted the log.
+    pub address: Address,
+    /// The topics and data of the log.
+    pub data: LogData,
+    /// The number of calls of the frame made before the log was emitted, if known.
+    pub position: Option<u64>,
+    /// The event and its decoded arguments, if the event is known.
+    pub event: Option<(Event, DecodedEvent)>,
+}
+
+/// A frame of a call trace, decoded by a [`TraceDecoder`].
+///
+/// Renders as an indented call tree with [`Display`](fmt::Display).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedTrace {
+    /// The kind of the frame.
+    pub kind: TraceKind,
+    /// The caller.
+    pub from: Address,
+    /// The called or created contract, if any.
+    pub to: Option<Address>,
+    /// The label of the called or created contract, if any.
+    pub label: Option<String>,
+    /// The transferred value.
+    pub value: U256,
+    /// The gas used by the frame.
+    pub gas_used: u64,
+    /// The calldata, or the init code of a created contract.
+    pub input: Bytes,
+    /// The return or revert data, or the runtime code of a created contract.
+    pub output: Bytes,
+    /// The error, if the frame failed.
+    pub error: Option<String>,
+    /// The decoded function call, if the function is known.
+    pub call: Option<DecodedCall>,
+    /// The revert, if the frame reverted with revert data.
+    pub revert: Option<Revert>,
+    /// The logs emitted by the frame.
+    pub logs: Vec<DecodedTraceLog>,
+    /// The calls made by the frame.
+    pub calls: Vec<Self>,
+}
+
+impl DecodedTrace {
+    /// Returns `true` if the frame succeeded.
+    pub const fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Returns an iterator over the frame and all its nested calls, depth-first.
+    pub fn iter(&self) -> impl Iterator<Item = &Self> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let trace = stack.pop()?;
+            stack.extend(trace.calls.iter().rev());
+            Some(trace)
+        })
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, prefix: &str) -> fmt::Result {
+        self.fmt_header(f)?;
+        writeln!(f)?;
+
+        // Logs are interleaved with the calls made before and after them.
+        let mut logs = self.logs.iter().peekable();
+        let mut items = Vec::with_capacity(self.calls.len() + self.logs.len() + 1);
+        for (i, call) in self.calls.iter().enumerate() {
+            while let Some(log) = logs.next_if(|log| log.position.is_some_and(|p| p <= i as u64)) {
+                items.push(Item::Log(log));
+            }
+            items.push(Item::Call(call));
+        }
+        items.extend(logs.map(Item::Log));
+        items.push(Item::Result);
+
+        for (i, item) in items.iter().enumerate() {
+            let (branch, indent) =
+                if i + 1 == items.len() { ("└─ ", "    ") } else { ("├─ ", "│   ") };
+            write!(f, "{prefix}{branch}")?;
+            match item {
+                Item::Call(call) => call.render(f, &format!("{prefix}{indent}"))?,
+                Item::Log(log) => writeln!(f, "{log}")?,
+                Item::Result => {
+                    self.fmt_result(f)?;
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fmt_header(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] ", self.gas_used)?;
+        let target = |f: &mut fmt::Formatter<'_>| match (&self.label, self.to) {
+            (Some(label), _) => f.write_str(label),
+            (None, Some(to)) => write!(f, "{to}"),
+            (None, None) => f.write_str("<unknown>"),
+        };
+        if self.kind.is_create() {
+            f.write_str("→ new ")?;
+            match (&self.label, self.to) {
+                (Some(label), Some(to)) => write!(f, "{label}@{to}")?,
+                _ => target(f)?,
+            }
+            return self.fmt_value(f);
+        }
+        if self.kind == TraceKind::Selfdestruct {
+            write!(f, "{}::selfdestruct(", self.from)?;
+            target(f)?;
+            return f.write_str(")");
+        }
+
+        target(f)?;
+        f.write_str("::")?;
+        match &self.call {
+            Some(call) => {
+                f.write_str(&call.function.name)?;
+                self.fmt_value(f)?;
+                f.write_str("(")?;
+                fmt_values(f, &call.inputs)?;
+                f.write_str(")")?;
+            }
+            None if self.input.is_empty() => {
+                f.write_str("fallback")?;
+                self.fmt_value(f)?;
+                f.write_str("()")?;
+            }
+            None => {
+                f.write_str("fallback")?;
+                self.fmt_value(f)?;
+                write!(f, "({})", self.input)?;
+            }
+        }
+        match self.kind {
+            TraceKind::StaticCall => f.write_str(" [staticcall]"),
+            TraceKind::DelegateCall => f.write_str(" [delegatecall]"),
+            TraceKind::CallCode => f.write_str(" [callcode]"),
+            _ => Ok(()),
+        }
+    }
+
+    fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.value.is_zero() {
+            return Ok(());
+        }
+        write!(f, "{{value: {}}}", self.value)
+    }
+
+    fn fmt_result(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("← ")?;
+        if let Some(revert) = &self.revert {
+            f.write_str("[Revert] ")?;
+            return match revert.decoded() {
+                Some(DecodedRevert::Reason(reason)) => f.write_str(reason),
+                Some(DecodedRevert::Panic(panic)) => write!(f, "{panic}"),
+                Some(DecodedRevert::Custom { error, args }) => {
+                    write!(f, "{}(", error.name)?;
+                    fmt_values(f, args)?;
+                    f.write_str(")")
+                }
+                None => write!(f, "{}", revert.data()),
+            };
+        }
+        if let Some(error) = &self.error {
+            return write!(f, "[{error}]");
+        }
+        if self.kind.is_create() {
+            return write!(f, "[Return] {} bytes of code", self.output.len());
+        }
+        match self.call.as_ref().and_then(|call| call.outputs.as_ref()) {
+            Some(outputs) if !outputs.is_empty() => {
+                f.write_str("[Return] ")?;
+                fmt_values(f, outputs)
+            }
+            _ if self.output.is_empty() => f.write_str("[Stop]"),
+            Some(_) => f.write_str("[Return]"),
+            None => write!(f, "[Return] {}", self.output),
+        }
+    }
+}
+
+impl fmt::Display for DecodedTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, "")
+    }
+}
+
+impl fmt::Display for DecodedTraceLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((event, decoded)) = &self.event else {
+            f.write_str("emit ")?;
+            for (i, topic) in self.data.topics().iter().enumerate() {
+                write!(f, "topic {i}: {topic}, ")?;
+            }
+            return write!(f, "data: {}", self.data.data);
+        };
+
+        write!(f, "emit {}(", event.name)?;
+        let (mut indexed, mut body) = (decoded.indexed.iter(), decoded.body.iter());
+        for (i, input) in event.inputs.iter().enumerate() {
+            let value = if input.indexed { indexed.next() } else { body.next() };
+            let Some(value) = value else { break };
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if !input.name.is_empty() {
+                write!(f, "{}: ", input.name)?;
+            }
+            write!(f, "{}", DisplayValue(value))?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// An item of the call tree of a [`DecodedTrace`].
+enum Item<'a> {
+    Call(&'a DecodedTrace),
+    Log(&'a DecodedTraceLog),
+    Result,
+}
+
+fn fmt_values(f: &mut fmt::Formatter<'_>, values: &[DynSolValue]) -> fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", DisplayValue(value))?;
+    }
+    Ok(())
+}
+
+/// Formats a value like Solidity literals.
+struct DisplayValue<'a>(&'a DynSolValue);
+
+impl fmt::Display for DisplayValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            DynSolValue::Bool(value) => write!(f, "{value}"),
+            DynSolValue::Int(value, _) => write!(f, "{value}"),
+            DynSolValue::Uint(value, _) => write!(f, "{value}"),
+            DynSolValue::FixedBytes(word, size) => {
+                write!(f, "{}", Bytes::copy_from_slice(&word[..*size]))
+            }
+            DynSolValue::Address(address) => write!(f, "{address}"),
+            DynSolValue::Function(function) => write!(f, "{function}"),
+            DynSolValue::Bytes(bytes) => write!(f, "{}", Bytes::copy_from_slice(bytes)),
+            DynSolValue::String(string) => write!(f, "{string:?}"),
+            DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+                f.write_str("[")?;
+                fmt_values(f, values)?;
+                f.write_str("]")
+            }
+            DynSolValue::Tuple(values) => {
+                f.write_str("(")?;
+                fmt_values(f, values)?;
+                f.write_str(")")
+            }
+            #[allow(unreachable_patterns)]
+            value => write!(f, "{value:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+    use alloy_rpc_types_trace::parity::CallAction;
+    use alloy_sol_types::{sol, SolCall, SolError, SolEvent};
+
+    sol! {
+        function transfer(address to, uint256 amount) returns (bool);
+        function balanceOf(address owner) returns (uint256);
+        event Transfer(address indexed from, address indexed to, uint256 value);
+        error InsufficientBalance(uint256 balance);
+    }
+
+    const TOKEN: Address = address!("00000000000000000000000000000000000000aa");
+    const ALICE: Address = address!("00000000000000000000000000000000000000a1");
+    const BOB: Address = address!("00000000000000000000000000000000000000b0");
+
+    fn decoder() -> TraceDecoder {
+        let abi = JsonAbi::parse([
+            "function transfer(address to, uint256 amount) returns (bool)",
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+        ])
+        .unwrap();
+        TraceDecoder::new()
+            .with_abi(TOKEN, abi)
+            .with_label(TOKEN, "Token")
+            .with_signatures([
+                "function balanceOf(address owner) returns (uint256)",
+                "error InsufficientBalance(uint256 balance)",
+            ])
+            .unwrap()
+    }
+
+    #[test]
+    fn decodes_call_frames() {
+        let transfer = Transfer { from: ALICE, to: BOB, value: U256::from(100) };
+        let frame = CallFrame {
+            from: ALICE,
+            to: Some(TOKEN),
+            gas_used: U256::from(30000),
+            input: transferCall { to: BOB, amount: U256::from(100) }.abi_encode().into(),
+            output: Some(transferCall::abi_encode_returns(&(true,)).into()),
+            typ: "CALL".into(),
+            calls: vec![CallFrame {
+                from: TOKEN,
+                to: Some(BOB),
+                gas_used: U256::from(2000),
+                input: balanceOfCall { owner: ALICE }.abi_encode().into(),
+                output: Some(InsufficientBalance { balance: U256::from(1) }.abi_encode().into()),
+                error: Some("execution reverted".into()),
+                typ: "STATICCALL".into(),
+                ..Default::default()
+            }],
+            logs: vec![CallLogFrame {
+                address: Some(TOKEN),
+                topics: Some(transfer.encode_topics().into_iter().map(|t| t.0).collect()),
+                data: Some(transfer.encode_data().into()),
+                position: Some(1),
+            }],
+            ..Default::default()
+        };
+
+        let trace = decoder().decode_call_frame(&frame);
+        let call = trace.call.as_ref().unwrap();
+        assert_eq!(call.function.name, "transfer");
+        assert_eq!(call.inputs, [BOB.into(), U256::from(100).into()]);
+        assert_eq!(call.outputs, Some(vec![true.into()]));
+        assert!(trace.logs[0].event.is_some());
+
+        let nested = &trace.calls[0];
+        assert!(!nested.is_success());
+        assert_eq!(nested.call.as_ref().unwrap().function.name, "balanceOf");
+        assert!(matches!(
+            nested.revert.as_ref().and_then(Revert::decoded),
+            Some(DecodedRevert::Custom { error, .. }) if error.name == "InsufficientBalance"
+        ));
+        assert_eq!(trace.iter().count(), 2);
+
+        assert_eq!(
+            trace.to_string(),
+            format!(
+                "[30000] Token::transfer({BOB}, 100)\n\
+                 ├─ [2000] {BOB}::balanceOf({ALICE}) [staticcall]\n\
+                 │   └─ ← [Revert] InsufficientBalance(1)\n\
+                 ├─ emit Transfer(from: {ALICE}, to: {BOB}, value: 100)\n\
+                 └─ ← [Return] true\n"
+            )
+        );
+    }
+
+    #[test]
+    fn nests_parity_traces() {
+        let trace = |trace_address: Vec<usize>, to: Address| TransactionTrace {
+            action: Action::Call(CallAction { to, ..Default::default() }),
+            trace_address,
+            ..Default::default()
+        };
+        let traces = [
+            trace(vec![], TOKEN),
+            trace(vec![0], ALICE),
+            trace(vec![0, 0], BOB),
+            trace(vec![1], BOB),
+        ];
+
+        let root = decoder().decode_parity_traces(&traces).unwrap();
+        assert_eq!(root.label.as_deref(), Some("Token"));
+        assert_eq!(root.calls.len(), 2);
+        assert_eq!(root.calls[0].to, Some(ALICE));
+        assert_eq!(root.calls[0].calls[0].to, Some(BOB));
+        assert_eq!(root.calls[1].to, Some(BOB));
+        assert_eq!(
+            root.iter().map(|trace| trace.to.unwrap()).collect::<Vec<_>>(),
+            [TOKEN, ALICE, BOB, BOB]
+        );
+    }
+}
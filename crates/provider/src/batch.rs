@@ -0,0 +1,262 @@
+//! Sending many transactions in a single JSON-RPC batch.
+
+use crate::{
+    fillers::{FillProvider, FillerControlFlow, TxFiller},
+    PendingTransaction, PendingTransactionBuilder, PendingTransactionError, Provider, RootProvider,
+    SendableTx,
+};
+use alloy_eips::{eip2718::Encodable2718, BlockId};
+use alloy_json_rpc::RpcError;
+use alloy_network::{Ethereum, Network, ReceiptResponse, TransactionBuilder};
+use alloy_primitives::{Address, Bytes, TxHash};
+use alloy_rpc_client::{BatchRequest, Waiter};
+use alloy_transport::{Transport, TransportResult};
+use futures::{stream::FuturesUnordered, StreamExt};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+impl<F, P, T, N> FillProvider<F, P, T, N>
+where
+    F: TxFiller<N>,
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Fills and signs the requests, then broadcasts them in a single JSON-RPC batch.
+    ///
+    /// Requests without a nonce are assigned sequential nonces per sender, starting at the
+    /// sender's pending transaction count. Every request must therefore have a sender, or be
+    /// given one by a filler, e.g. the [`WalletFiller`]. As the nonces are not assigned by the
+    /// configured nonce filler, a [`CachedNonceManager`] should not be used for the same senders
+    /// concurrently.
+    ///
+    /// The transactions are broadcast directly, bypassing the layers below the fillers. If any
+    /// request fails to be filled, nothing is broadcast.
+    ///
+    /// All broadcast transactions are registered with the provider's heartbeat, and the returned
+    /// [`PendingBatch`] resolves their receipts.
+    ///
+    /// [`WalletFiller`]: crate::fillers::WalletFiller
+    /// [`CachedNonceManager`]: crate::fillers::CachedNonceManager
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use alloy_network::EthereumWallet;
+    /// # use alloy_rpc_types_eth::TransactionRequest;
+    /// # use alloy_provider::ProviderBuilder;
+    /// # async fn test(url: url::Url, wallet: EthereumWallet, requests: Vec<TransactionRequest>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let provider = ProviderBuilder::new().with_recommended_fillers().wallet(wallet).on_http(url);
+    ///
+    /// let batch = provider.send_transactions_batch(requests).await?;
+    /// for receipt in batch.get_receipts().await {
+    ///     println!("{:?}", receipt?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_transactions_batch<I>(
+        &self,
+        requests: I,
+    ) -> TransportResult<PendingBatch<'_, T, N>>
+    where
+        I: IntoIterator<Item = N::TransactionRequest>,
+    {
+        // Assign sequential nonces, remembering the sender and nonce of each transaction so that
+        // the heartbeat can detect replacements.
+        let mut next_nonces = HashMap::<Address, u64>::new();
+        let mut txs = Vec::new();
+        let mut sender_nonces = Vec::new();
+        for request in requests {
+            let mut tx = SendableTx::Builder(request);
+            self.filler.fill_sync(&mut tx);
+            if let Some(builder) = tx.as_mut_builder() {
+                if builder.nonce().is_none() {
+                    let from = builder.from().ok_or_else(|| {
+                        RpcError::local_usage_str("batch sending requires `from` or `nonce`")
+                    })?;
+                    let next = match next_nonces.entry(from) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            entry.insert(self.get_transaction_count(from).pending().await?)
+                        }
+                    };
+                    builder.set_nonce(*next);
+                    *next += 1;
+                }
+            }
+            sender_nonces.push(tx.as_builder().and_then(|tx| tx.from().zip(tx.nonce())));
+            txs.push(tx);
+        }
+
+        let txs =
+            futures::future::try_join_all(txs.into_iter().map(|tx| self.fill_inner(tx))).await?;
+        for tx in &txs {
+            if let Some(builder) = tx.as_builder() {
+                if let FillerControlFlow::Missing(missing) = self.filler.status(builder) {
+                    let message = format!("missing properties: {:?}", missing);
+                    return Err(RpcError::local_usage_str(&message));
+                }
+            }
+        }
+
+        let mut batch = BatchRequest::new(self.client());
+        let mut waiters: Vec<Waiter<TxHash>> = Vec::with_capacity(txs.len());
+        for tx in &txs {
+            let waiter = match tx {
+                SendableTx::Envelope(envelope) => batch
+                    .add_call("eth_sendRawTransaction", &(Bytes::from(envelope.encoded_2718()),))?,
+                SendableTx::Builder(request) => {
+                    batch.add_call("eth_sendTransaction", &(request,))?
+                }
+            };
+            waiters.push(waiter);
+        }
+        batch.send().await?;
+
+        let mut pending = Vec::with_capacity(waiters.len());
+        for (waiter, sender_nonce) in waiters.into_iter().zip(sender_nonces) {
+            let tx = match waiter.await {
+                Ok(tx_hash) => {
+                    let mut builder = PendingTransactionBuilder::new(self.root(), tx_hash);
+                    if let Some((sender, nonce)) = sender_nonce {
+                        builder = builder.with_sender_nonce(sender, nonce);
+                    }
+                    builder.register().await
+                }
+                Err(err) => Err(err.into()),
+            };
+            pending.push(tx);
+        }
+        Ok(PendingBatch { provider: self.root(), txs: pending })
+    }
+}
+
+/// A batch of transactions sent with [`FillProvider::send_transactions_batch`].
+///
+/// The transactions are in the order of the requests. Receipts are fetched with a single
+/// `eth_getBlockReceipts` call per block that included any of the transactions, instead of
+/// polling for each transaction.
+#[derive(Debug)]
+pub struct PendingBatch<'a, T, N: Network = Ethereum> {
+    provider: &'a RootProvider<T, N>,
+    txs: Vec<Result<PendingTransaction, PendingTransactionError>>,
+}
+
+impl<'a, T, N> PendingBatch<'a, T, N>
+where
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Returns the number of transactions in the batch, including the ones that failed to be
+    /// broadcast.
+    pub fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    /// Returns `true` if the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    /// Returns the hash of each transaction, or `None` if it failed to be broadcast.
+    pub fn tx_hashes(&self) -> impl Iterator<Item = Option<TxHash>> + '_ {
+        self.txs.iter().map(|tx| tx.as_ref().ok().map(|pending| *pending.tx_hash()))
+    }
+
+    /// Waits for every transaction to be confirmed, returning their receipts in the order of the
+    /// requests.
+    ///
+    /// Transactions that failed to be broadcast or confirmed yield their error instead.
+    pub async fn get_receipts(self) -> Vec<Result<N::ReceiptResponse, PendingTransactionError>> {
+        let mut results = Vec::with_capacity(self.txs.len());
+        let mut hashes = HashSet::new();
+        let mut included = FuturesUnordered::new();
+        for (index, tx) in self.txs.into_iter().enumerate() {
+            match tx {
+                Ok(pending) => {
+                    let tx_hash = *pending.tx_hash();
+                    hashes.insert(tx_hash);
+                    included.push(async move { (index, tx_hash, pending.included_at().await) });
+                    results.push(None);
+                }
+                Err(err) => results.push(Some(Err(err))),
+            }
+        }
+
+        let mut receipts = HashMap::new();
+        let mut fetched_blocks = HashSet::new();
+        while let Some((index, tx_hash, block)) = included.next().await {
+            let receipt = match block {
+                Ok(block) => {
+                    if let Some(block) = block.filter(|block| fetched_blocks.insert(*block)) {
+                        Self::fetch_block_receipts(self.provider, block, &hashes, &mut receipts)
+                            .await;
+                    }
+                    match receipts.remove(&tx_hash) {
+                        Some(receipt) => Ok(receipt),
+                        // The block is unknown, or was reorged before fetching its receipts.
+                        None => self
+                            .provider
+                            .get_transaction_receipt(tx_hash)
+                            .await
+                            .map_err(Into::into)
+                            .and_then(|receipt| receipt.ok_or_else(|| RpcError::NullResp.into())),
+                    }
+                }
+                Err(err) => Err(err),
+            };
+            results[index] = Some(receipt);
+        }
+
+        results.into_iter().map(|result| result.expect("every transaction is resolved")).collect()
+    }
+
+    /// Fetches the receipts of the given block, keeping the ones of the batch's transactions.
+    async fn fetch_block_receipts(
+        provider: &RootProvider<T, N>,
+        block: u64,
+        hashes: &HashSet<TxHash>,
+        receipts: &mut HashMap<TxHash, N::ReceiptResponse>,
+    ) {
+        match provider.get_block_receipts(BlockId::number(block)).await {
+            Ok(block_receipts) => receipts.extend(
+                block_receipts
+                    .into_iter()
+                    .flatten()
+                    .filter(|receipt| hashes.contains(&receipt.transaction_hash()))
+                    .map(|receipt| (receipt.transaction_hash(), receipt)),
+            ),
+            Err(err) => debug!(block, %err, "failed to fetch block receipts"),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[cfg(test)]
+mod tests {
+    use crate::{Provider, ProviderBuilder, WalletProvider};
+    use alloy_network::{ReceiptResponse, TransactionBuilder};
+    use alloy_primitives::{address, U256};
+    use alloy_rpc_types_eth::TransactionRequest;
+
+    #[tokio::test]
+    async fn sends_batch_with_sequential_nonces() {
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+
+        let tx = TransactionRequest::default()
+            .with_to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .with_value(U256::from(100));
+        let batch = provider.send_transactions_batch(vec![tx; 3]).await.unwrap();
+        assert_eq!(batch.len(), 3);
+        let hashes = batch.tx_hashes().collect::<Option<Vec<_>>>().unwrap();
+
+        let receipts = batch.get_receipts().await;
+        for (receipt, hash) in receipts.iter().zip(&hashes) {
+            let receipt = receipt.as_ref().unwrap();
+            assert!(receipt.status());
+            assert_eq!(receipt.transaction_hash(), *hash);
+        }
+        assert_eq!(provider.get_transaction_count(from).await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,469 @@
+//! A synchronous facade over a [`Provider`].
+
+use crate::{
+    utils::{Eip1559Estimation, EstimatorFunction},
+    PendingTransactionBuilder, PendingTransactionError, Provider,
+};
+use alloy_json_rpc::{RpcParam, RpcReturn};
+use alloy_network::{Ethereum, Network};
+use alloy_network_primitives::BlockTransactionsKind;
+use alloy_primitives::{
+    Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue, TxHash, B256, U256,
+};
+use alloy_rpc_types_eth::{
+    AccessListResult, BlockId, BlockNumberOrTag, EIP1186AccountProofResponse, FeeHistory, Filter,
+    Log, SyncStatus,
+};
+use alloy_transport::{BoxTransport, Transport, TransportErrorKind, TransportResult};
+use futures::{Stream, StreamExt};
+use std::{borrow::Cow, future::Future, io, marker::PhantomData, pin::Pin, sync::Arc};
+use tokio::runtime::{Handle, Runtime};
+
+/// Generates blocking versions of provider methods that take no block argument.
+macro_rules! blocking_methods {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Blocking version of [`Provider::", stringify!($name), "`].")]
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.block_on(async { self.provider.$name($($arg),*).await })
+            }
+        )*
+    };
+}
+
+/// A synchronous facade over a [`Provider`], for use outside of an asynchronous context.
+///
+/// The provider's futures are driven to completion on a tokio runtime, which is either owned by
+/// the `BlockingProvider`, or borrowed through a [`Handle`]. A borrowed handle must belong to a
+/// multi-threaded runtime, as only those drive their I/O when blocked on from a handle.
+///
+/// All methods block the current thread, and panic if called from within an asynchronous
+/// context.
+///
+/// Methods that take a block argument, e.g. [`BlockingProvider::get_balance`], map to the
+/// corresponding [`RpcWithBlock`] or [`EthCall`] configured with that block. Extension APIs, e.g.
+/// [`AnvilApi`], and any other asynchronous API of the provider are called through
+/// [`BlockingProvider::run`].
+///
+/// [`RpcWithBlock`]: crate::RpcWithBlock
+/// [`EthCall`]: crate::EthCall
+/// [`AnvilApi`]: crate::ext::AnvilApi
+///
+/// # Example
+///
+/// ```no_run
+/// # use alloy_provider::{BlockingProvider, ProviderBuilder};
+/// # fn test(url: url::Url) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = BlockingProvider::new(ProviderBuilder::new().on_http(url))?;
+///
+/// let block_number = provider.get_block_number()?;
+/// for block_hash in provider.watch_blocks()?.take(5) {
+///     println!("new block: {block_hash}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BlockingProvider<P, T = BoxTransport, N = Ethereum> {
+    provider: P,
+    handle: Handle,
+    /// The runtime owned by this provider, if any. Kept alive for as long as the provider.
+    _runtime: Option<Arc<Runtime>>,
+    _pd: PhantomData<fn() -> (T, N)>,
+}
+
+impl<P: Clone, T, N> Clone for BlockingProvider<P, T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            provider: self.provider.clone(),
+            handle: self.handle.clone(),
+            _runtime: self._runtime.clone(),
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<P, T, N> BlockingProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Creates a new `BlockingProvider` with its own runtime.
+    pub fn new(provider: P) -> io::Result<Self> {
+        Ok(Self::with_runtime(provider, Self::build_runtime()?))
+    }
+
+    /// Creates a new `BlockingProvider` owning the given runtime.
+    pub fn with_runtime(provider: P, runtime: Runtime) -> Self {
+        Self {
+            provider,
+            handle: runtime.handle().clone(),
+            _runtime: Some(Arc::new(runtime)),
+            _pd: PhantomData,
+        }
+    }
+
+    /// Creates a new `BlockingProvider` running on the runtime of the given handle.
+    pub const fn with_handle(provider: P, handle: Handle) -> Self {
+        Self { provider, handle, _runtime: None, _pd: PhantomData }
+    }
+
+    /// Creates a new `BlockingProvider` with its own runtime, on which the provider is connected.
+    ///
+    /// This is required for transports that spawn tasks when connecting, e.g. WebSocket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use alloy_provider::{BlockingProvider, ProviderBuilder};
+    /// # fn test() -> Result<(), Box<dyn std::error::Error>> {
+    /// let provider =
+    ///     BlockingProvider::connect(ProviderBuilder::new().on_builtin("ws://localhost:8546"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect<F>(connect: F) -> TransportResult<Self>
+    where
+        F: Future<Output = TransportResult<P>>,
+    {
+        let runtime = Self::build_runtime().map_err(TransportErrorKind::custom)?;
+        let provider = runtime.block_on(connect)?;
+        Ok(Self::with_runtime(provider, runtime))
+    }
+
+    fn build_runtime() -> io::Result<Runtime> {
+        tokio::runtime::Builder::new_multi_thread().worker_threads(1).enable_all().build()
+    }
+
+    /// Returns a reference to the inner provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Returns the inner provider.
+    pub fn into_inner(self) -> P {
+        self.provider
+    }
+
+    /// Returns the handle of the runtime the provider runs on.
+    pub const fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// Runs the future to completion on the provider's runtime.
+    pub fn block_on<F: Future>(&self, fut: F) -> F::Output {
+        self.handle.block_on(fut)
+    }
+
+    /// Runs the asynchronous call returned by `f` to completion.
+    ///
+    /// This gives blocking access to any asynchronous API of the provider, such as the extension
+    /// APIs.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use alloy_provider::{BlockingProvider, Provider};
+    /// # fn test<P: Provider>(provider: BlockingProvider<P>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let block = provider.run(|provider| provider.get_block_number())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run<'a, F, Fut>(&'a self, f: F) -> Fut::Output
+    where
+        F: FnOnce(&'a P) -> Fut,
+        Fut: std::future::IntoFuture,
+    {
+        self.block_on(f(&self.provider).into_future())
+    }
+
+    /// Returns a blocking iterator over the items of the stream, e.g. of a [`PollChannel`] or of
+    /// a [`Subscription`].
+    ///
+    /// [`PollChannel`]: alloy_rpc_client::PollChannel
+    /// [`Subscription`]: alloy_pubsub::Subscription
+    pub fn iter<S: Stream>(&self, stream: S) -> BlockingIter<'_, S> {
+        BlockingIter { handle: &self.handle, stream: Box::pin(stream) }
+    }
+
+    blocking_methods! {
+        get_accounts() -> TransportResult<Vec<Address>>;
+        get_blob_base_fee() -> TransportResult<u128>;
+        get_block_number() -> TransportResult<BlockNumber>;
+        get_chain_id() -> TransportResult<u64>;
+        estimate_eip1559_fees(estimator: Option<EstimatorFunction>) -> TransportResult<Eip1559Estimation>;
+        get_fee_history(block_count: u64, last_block: BlockNumberOrTag, reward_percentiles: &[f64]) -> TransportResult<FeeHistory>;
+        get_gas_price() -> TransportResult<u128>;
+        get_max_priority_fee_per_gas() -> TransportResult<u128>;
+        get_block(block: BlockId, kind: BlockTransactionsKind) -> TransportResult<Option<N::BlockResponse>>;
+        get_block_by_hash(hash: BlockHash, kind: BlockTransactionsKind) -> TransportResult<Option<N::BlockResponse>>;
+        get_block_by_number(number: BlockNumberOrTag, hydrate: bool) -> TransportResult<Option<N::BlockResponse>>;
+        get_block_receipts(block: BlockId) -> TransportResult<Option<Vec<N::ReceiptResponse>>>;
+        get_logs(filter: &Filter) -> TransportResult<Vec<Log>>;
+        get_transaction_by_hash(hash: TxHash) -> TransportResult<Option<N::TransactionResponse>>;
+        get_raw_transaction_by_hash(hash: TxHash) -> TransportResult<Option<Bytes>>;
+        get_transaction_receipt(hash: TxHash) -> TransportResult<Option<N::ReceiptResponse>>;
+        get_uncle(tag: BlockId, idx: u64) -> TransportResult<Option<N::BlockResponse>>;
+        get_uncle_count(tag: BlockId) -> TransportResult<u64>;
+        new_block_filter() -> TransportResult<U256>;
+        new_filter(filter: &Filter) -> TransportResult<U256>;
+        new_pending_transactions_filter(full: bool) -> TransportResult<U256>;
+        syncing() -> TransportResult<SyncStatus>;
+        get_client_version() -> TransportResult<String>;
+        get_sha3(data: &[u8]) -> TransportResult<B256>;
+        get_net_version() -> TransportResult<u64>;
+        raw_request_dyn(method: Cow<'static, str>, params: &serde_json::value::RawValue) -> TransportResult<Box<serde_json::value::RawValue>>;
+    }
+
+    /// Blocking version of [`Provider::get_account`], at the given block.
+    pub fn get_account(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> TransportResult<alloy_consensus::Account> {
+        self.block_on(async { self.provider.get_account(address).block_id(block).await })
+    }
+
+    /// Blocking version of [`Provider::get_balance`], at the given block.
+    pub fn get_balance(&self, address: Address, block: BlockId) -> TransportResult<U256> {
+        self.block_on(async { self.provider.get_balance(address).block_id(block).await })
+    }
+
+    /// Blocking version of [`Provider::get_code_at`], at the given block.
+    pub fn get_code_at(&self, address: Address, block: BlockId) -> TransportResult<Bytes> {
+        self.block_on(async { self.provider.get_code_at(address).block_id(block).await })
+    }
+
+    /// Blocking version of [`Provider::get_proof`], at the given block.
+    pub fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<StorageKey>,
+        block: BlockId,
+    ) -> TransportResult<EIP1186AccountProofResponse> {
+        self.block_on(async { self.provider.get_proof(address, keys).block_id(block).await })
+    }
+
+    /// Blocking version of [`Provider::get_storage_at`], at the given block.
+    pub fn get_storage_at(
+        &self,
+        address: Address,
+        key: U256,
+        block: BlockId,
+    ) -> TransportResult<StorageValue> {
+        self.block_on(async { self.provider.get_storage_at(address, key).block_id(block).await })
+    }
+
+    /// Blocking version of [`Provider::get_transaction_count`], at the given block.
+    pub fn get_transaction_count(&self, address: Address, block: BlockId) -> TransportResult<u64> {
+        self.block_on(async { self.provider.get_transaction_count(address).block_id(block).await })
+    }
+
+    /// Blocking version of [`Provider::call`], at the given block.
+    pub fn call(&self, tx: &N::TransactionRequest, block: BlockId) -> TransportResult<Bytes> {
+        self.block_on(async { self.provider.call(tx).block(block).await })
+    }
+
+    /// Blocking version of [`Provider::estimate_gas`], at the given block.
+    pub fn estimate_gas(
+        &self,
+        tx: &N::TransactionRequest,
+        block: BlockId,
+    ) -> TransportResult<u128> {
+        self.block_on(async { self.provider.estimate_gas(tx).block(block).await })
+    }
+
+    /// Blocking version of [`Provider::create_access_list`], at the given block.
+    pub fn create_access_list(
+        &self,
+        tx: &N::TransactionRequest,
+        block: BlockId,
+    ) -> TransportResult<AccessListResult> {
+        self.block_on(async { self.provider.create_access_list(tx).block_id(block).await })
+    }
+
+    /// Blocking version of [`Provider::raw_request`].
+    pub fn raw_request<Params, R>(
+        &self,
+        method: Cow<'static, str>,
+        params: Params,
+    ) -> TransportResult<R>
+    where
+        Params: RpcParam,
+        R: RpcReturn,
+    {
+        self.block_on(self.provider.raw_request(method, params))
+    }
+
+    /// Blocking version of [`Provider::send_transaction`], returning the transaction hash once
+    /// the transaction is broadcast.
+    ///
+    /// See [`BlockingProvider::send_transaction_and_wait`] to also wait for its receipt.
+    pub fn send_transaction(&self, tx: N::TransactionRequest) -> TransportResult<TxHash> {
+        self.block_on(async { Ok(*self.provider.send_transaction(tx).await?.tx_hash()) })
+    }
+
+    /// Blocking version of [`Provider::send_raw_transaction`], returning the transaction hash once
+    /// the transaction is broadcast.
+    pub fn send_raw_transaction(&self, encoded_tx: &[u8]) -> TransportResult<TxHash> {
+        self.block_on(async {
+            Ok(*self.provider.send_raw_transaction(encoded_tx).await?.tx_hash())
+        })
+    }
+
+    /// Broadcasts the transaction, then waits for its receipt.
+    pub fn send_transaction_and_wait(
+        &self,
+        tx: N::TransactionRequest,
+    ) -> Result<N::ReceiptResponse, PendingTransactionError> {
+        self.block_on(async { self.provider.send_transaction(tx).await?.get_receipt().await })
+    }
+
+    /// Waits for the transaction to be confirmed the given number of times, then returns its
+    /// receipt.
+    pub fn wait_for_receipt(
+        &self,
+        tx_hash: TxHash,
+        required_confirmations: u64,
+    ) -> Result<N::ReceiptResponse, PendingTransactionError> {
+        self.block_on(
+            PendingTransactionBuilder::new(self.provider.root(), tx_hash)
+                .with_required_confirmations(required_confirmations)
+                .get_receipt(),
+        )
+    }
+
+    /// Blocking version of [`Provider::watch_blocks`], returning an iterator over the hashes of
+    /// new blocks.
+    pub fn watch_blocks(&self) -> TransportResult<BlockingIter<'_, impl Stream<Item = B256>>> {
+        let poller = self.block_on(self.provider.watch_blocks())?;
+        let _guard = self.handle.enter();
+        Ok(self.iter(poller.into_stream().flat_map(futures::stream::iter)))
+    }
+
+    /// Blocking version of [`Provider::watch_pending_transactions`], returning an iterator over
+    /// the hashes of new pending transactions.
+    pub fn watch_pending_transactions(
+        &self,
+    ) -> TransportResult<BlockingIter<'_, impl Stream<Item = B256>>> {
+        let poller = self.block_on(self.provider.watch_pending_transactions())?;
+        let _guard = self.handle.enter();
+        Ok(self.iter(poller.into_stream().flat_map(futures::stream::iter)))
+    }
+
+    /// Blocking version of [`Provider::watch_logs`], returning an iterator over the new logs
+    /// matching the filter.
+    pub fn watch_logs(
+        &self,
+        filter: &Filter,
+    ) -> TransportResult<BlockingIter<'_, impl Stream<Item = Log>>> {
+        let poller = self.block_on(self.provider.watch_logs(filter))?;
+        let _guard = self.handle.enter();
+        Ok(self.iter(poller.into_stream().flat_map(futures::stream::iter)))
+    }
+
+    /// Blocking version of [`Provider::subscribe_blocks`], returning an iterator over the new
+    /// blocks.
+    #[cfg(feature = "pubsub")]
+    pub fn subscribe_blocks(
+        &self,
+    ) -> TransportResult<BlockingIter<'_, impl Stream<Item = N::BlockResponse>>> {
+        let sub = self.block_on(self.provider.subscribe_blocks())?;
+        Ok(self.iter(sub.into_stream()))
+    }
+
+    /// Blocking version of [`Provider::subscribe_pending_transactions`], returning an iterator
+    /// over the hashes of new pending transactions.
+    #[cfg(feature = "pubsub")]
+    pub fn subscribe_pending_transactions(
+        &self,
+    ) -> TransportResult<BlockingIter<'_, impl Stream<Item = B256>>> {
+        let sub = self.block_on(self.provider.subscribe_pending_transactions())?;
+        Ok(self.iter(sub.into_stream()))
+    }
+
+    /// Blocking version of [`Provider::subscribe_full_pending_transactions`], returning an
+    /// iterator over new pending transactions.
+    #[cfg(feature = "pubsub")]
+    pub fn subscribe_full_pending_transactions(
+        &self,
+    ) -> TransportResult<BlockingIter<'_, impl Stream<Item = N::TransactionResponse>>> {
+        let sub = self.block_on(self.provider.subscribe_full_pending_transactions())?;
+        Ok(self.iter(sub.into_stream()))
+    }
+
+    /// Blocking version of [`Provider::subscribe_logs`], returning an iterator over the new logs
+    /// matching the filter.
+    #[cfg(feature = "pubsub")]
+    pub fn subscribe_logs(
+        &self,
+        filter: &Filter,
+    ) -> TransportResult<BlockingIter<'_, impl Stream<Item = Log>>> {
+        let sub = self.block_on(self.provider.subscribe_logs(filter))?;
+        Ok(self.iter(sub.into_stream()))
+    }
+}
+
+/// A blocking iterator over the items of a stream, created by a [`BlockingProvider`].
+///
+/// Each call to [`Iterator::next`] blocks the current thread until the stream yields an item.
+#[must_use = "iterators do nothing unless consumed"]
+pub struct BlockingIter<'a, S> {
+    handle: &'a Handle,
+    stream: Pin<Box<S>>,
+}
+
+impl<S> std::fmt::Debug for BlockingIter<'_, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingIter").finish_non_exhaustive()
+    }
+}
+
+impl<S: Stream> Iterator for BlockingIter<'_, S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.handle.block_on(self.stream.next())
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProviderBuilder, RootProvider};
+
+    #[test]
+    fn iterates_stream() {
+        let root = RootProvider::<_, Ethereum>::new_http("http://localhost:1".parse().unwrap());
+        let provider = BlockingProvider::new(root).unwrap();
+        let items = provider.iter(futures::stream::iter([1, 2, 3])).collect::<Vec<_>>();
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(provider.run(|_| async { 42 }), 42);
+    }
+
+    #[test]
+    fn sends_and_waits() {
+        use crate::WalletProvider;
+        use alloy_network::{ReceiptResponse, TransactionBuilder};
+        use alloy_primitives::address;
+        use alloy_rpc_types_eth::TransactionRequest;
+
+        let provider = BlockingProvider::new(
+            ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet(),
+        )
+        .unwrap();
+        let from = provider.provider().default_signer_address();
+
+        let tx = TransactionRequest::default()
+            .with_to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .with_value(U256::from(100));
+        let receipt = provider.send_transaction_and_wait(tx.clone()).unwrap();
+        assert!(receipt.status());
+
+        let tx_hash = provider.send_transaction(tx).unwrap();
+        assert_eq!(provider.wait_for_receipt(tx_hash, 1).unwrap().transaction_hash(), tx_hash);
+        assert_eq!(provider.get_transaction_count(from, BlockId::latest()).unwrap(), 2);
+    }
+}
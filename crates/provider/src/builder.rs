@@ -1,7 +1,8 @@
 use crate::{
     fillers::{
-        CachedNonceManager, ChainIdFiller, FillerControlFlow, GasFiller, JoinFill, NonceFiller,
-        NonceManager, RecommendedFiller, SimpleNonceManager, TxFiller, WalletFiller,
+        AccessListFiller, CachedNonceManager, ChainIdFiller, FeeEstimator, FillerControlFlow,
+        GasFiller, JoinFill, NonceFiller, NonceManager, RecommendedFiller, SimpleNonceManager,
+        TxFiller, WalletFiller,
     },
     provider::SendableTx,
     Provider, RootProvider,
@@ -131,14 +132,26 @@ impl<L, N> ProviderBuilder<L, Identity, N> {
     /// Add preconfigured set of layers handling gas estimation, nonce
     /// management, and chain-id fetching.
     pub fn with_recommended_fillers(self) -> ProviderBuilder<L, RecommendedFiller, N> {
-        self.filler(GasFiller).filler(NonceFiller::default()).filler(ChainIdFiller::default())
+        self.filler(GasFiller::default())
+            .filler(NonceFiller::default())
+            .filler(ChainIdFiller::default())
     }
 
     /// Add gas estimation to the stack being built.
     ///
     /// See [`GasFiller`]
     pub fn with_gas_estimation(self) -> ProviderBuilder<L, JoinFill<Identity, GasFiller>, N> {
-        self.filler(GasFiller)
+        self.filler(GasFiller::default())
+    }
+
+    /// Add gas estimation with a custom [`FeeEstimator`] to the stack being built.
+    ///
+    /// See [`GasFiller`]
+    pub fn with_fee_estimator<E: FeeEstimator>(
+        self,
+        estimator: E,
+    ) -> ProviderBuilder<L, JoinFill<Identity, GasFiller<E>>, N> {
+        self.filler(GasFiller::new(estimator))
     }
 
     /// Add nonce management to the stack being built.
@@ -219,6 +232,14 @@ impl<L, F, N> ProviderBuilder<L, F, N> {
         }
     }
 
+    /// Add an access list filler to the stack being built. This should be added after gas
+    /// estimation, e.g. after [`ProviderBuilder::with_recommended_fillers`].
+    ///
+    /// See [`AccessListFiller`].
+    pub fn with_access_list(self) -> ProviderBuilder<L, JoinFill<F, AccessListFiller>, N> {
+        self.filler(AccessListFiller)
+    }
+
     /// Add a wallet layer to the stack being built.
     ///
     /// See [`WalletFiller`].
@@ -0,0 +1,265 @@
+//! Concurrent dispatch of transactions across a pool of senders.
+
+use crate::{
+    fillers::ReservingNonceManager, utils::is_rejection, PendingTransactionError, Provider,
+    WalletProvider,
+};
+use alloy_network::{Ethereum, Network, TransactionBuilder};
+use alloy_primitives::{Address, TxHash};
+use alloy_transport::Transport;
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use std::marker::PhantomData;
+
+/// The default maximum number of unconfirmed transactions per sender.
+const DEFAULT_MAX_IN_FLIGHT: usize = 8;
+
+/// Returns the index of the least loaded sender that can take another transaction.
+fn least_loaded(loads: &[usize], max_in_flight: usize) -> Option<usize> {
+    loads
+        .iter()
+        .enumerate()
+        .filter(|(_, &load)| load < max_in_flight)
+        .min_by_key(|(_, &load)| load)
+        .map(|(i, _)| i)
+}
+
+/// An error that occurred while creating a [`Dispatcher`].
+#[derive(Debug, thiserror::Error)]
+pub enum DispatcherError {
+    /// The dispatcher was given no senders.
+    #[error("dispatcher requires at least one sender")]
+    NoSenders,
+}
+
+/// The outcome of a transaction dispatched by a [`Dispatcher`].
+#[derive(Debug)]
+pub struct DispatchOutcome<N: Network = Ethereum> {
+    /// The index of the request in the dispatched queue.
+    pub index: usize,
+    /// The sender the request was assigned to.
+    pub sender: Address,
+    /// The hash of the transaction, if it was broadcast.
+    pub tx_hash: Option<TxHash>,
+    /// The receipt of the transaction, or the error that prevented its confirmation.
+    pub result: Result<N::ReceiptResponse, PendingTransactionError>,
+}
+
+/// Dispatches transactions concurrently across a pool of senders.
+///
+/// Each request is assigned to the least loaded sender, i.e. the one with the fewest unconfirmed
+/// transactions, and sent once that sender is below the maximum number of transactions in flight.
+/// Nonces are assigned per sender by a [`ReservingNonceManager`], so that the nonces of
+/// transactions that fail to be sent are reused.
+///
+/// The provider must be able to sign for every sender, e.g. with an [`EthereumWallet`] that has a
+/// signer registered for each of them.
+///
+/// [`EthereumWallet`]: alloy_network::EthereumWallet
+///
+/// # Example
+///
+/// ```no_run
+/// # use alloy_network::EthereumWallet;
+/// # use alloy_rpc_types_eth::TransactionRequest;
+/// # use alloy_provider::{Dispatcher, ProviderBuilder};
+/// # use futures::StreamExt;
+/// # async fn test(url: url::Url, wallet: EthereumWallet, requests: Vec<TransactionRequest>) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = ProviderBuilder::new().with_recommended_fillers().wallet(wallet).on_http(url);
+/// let dispatcher = Dispatcher::from_wallet(provider)?.with_max_in_flight(4);
+///
+/// let mut outcomes = std::pin::pin!(dispatcher.dispatch(requests));
+/// while let Some(outcome) = outcomes.next().await {
+///     println!("request {} sent by {}: {:?}", outcome.index, outcome.sender, outcome.result);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dispatcher<P, T, N = Ethereum> {
+    provider: P,
+    senders: Vec<Address>,
+    nonce_manager: ReservingNonceManager,
+    max_in_flight: usize,
+    _pd: PhantomData<fn() -> (T, N)>,
+}
+
+impl<P, T, N> Dispatcher<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Creates a new `Dispatcher` sending from the given senders.
+    ///
+    /// Returns [`DispatcherError::NoSenders`] if `senders` is empty.
+    pub fn new(
+        provider: P,
+        senders: impl IntoIterator<Item = Address>,
+    ) -> Result<Self, DispatcherError> {
+        let senders = senders.into_iter().collect::<Vec<_>>();
+        if senders.is_empty() {
+            return Err(DispatcherError::NoSenders);
+        }
+        Ok(Self {
+            provider,
+            senders,
+            nonce_manager: ReservingNonceManager::default(),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            _pd: PhantomData,
+        })
+    }
+
+    /// Creates a new `Dispatcher` sending from every signer of the provider's wallet.
+    ///
+    /// Returns [`DispatcherError::NoSenders`] if the wallet has no signers.
+    pub fn from_wallet(provider: P) -> Result<Self, DispatcherError>
+    where
+        P: WalletProvider<N>,
+    {
+        let senders = provider.signer_addresses().collect::<Vec<_>>();
+        Self::new(provider, senders)
+    }
+
+    /// Sets the maximum number of unconfirmed transactions per sender.
+    ///
+    /// A value of zero is treated as one, as no transaction could be sent otherwise.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Sets the nonce manager, e.g. to share it with other senders.
+    pub fn with_nonce_manager(mut self, nonce_manager: ReservingNonceManager) -> Self {
+        self.nonce_manager = nonce_manager;
+        self
+    }
+
+    /// Returns a reference to the provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Returns the senders of the pool.
+    pub fn senders(&self) -> &[Address] {
+        &self.senders
+    }
+
+    /// Sends the request from the given sender and waits for its receipt.
+    async fn send(
+        &self,
+        index: usize,
+        sender: Address,
+        mut tx: N::TransactionRequest,
+    ) -> DispatchOutcome<N> {
+        let outcome = |tx_hash, result| DispatchOutcome { index, sender, tx_hash, result };
+
+        let nonce = match self.nonce_manager.reserve(&self.provider, sender).await {
+            Ok(nonce) => nonce,
+            Err(err) => return outcome(None, Err(err.into())),
+        };
+        tx.set_from(sender);
+        tx.set_nonce(nonce);
+
+        let pending = match self.provider.send_transaction(tx).await {
+            Ok(pending) => pending,
+            Err(err) => {
+                // The transaction may have been broadcast unless it was rejected.
+                if is_rejection(&err) {
+                    self.nonce_manager.release(sender, nonce).await;
+                } else {
+                    self.nonce_manager.confirm(sender, nonce).await;
+                }
+                return outcome(None, Err(err.into()));
+            }
+        };
+        self.nonce_manager.confirm(sender, nonce).await;
+
+        let tx_hash = *pending.tx_hash();
+        outcome(Some(tx_hash), pending.get_receipt().await)
+    }
+
+    /// Dispatches the requests across the senders, yielding the outcome of each request once its
+    /// transaction is confirmed or has failed.
+    ///
+    /// The `from` and `nonce` fields of the requests are overwritten. Outcomes are yielded in
+    /// completion order, use [`DispatchOutcome::index`] to match them with their requests.
+    pub fn dispatch<'a, I>(&'a self, requests: I) -> impl Stream<Item = DispatchOutcome<N>> + 'a
+    where
+        I: IntoIterator<Item = N::TransactionRequest>,
+        I::IntoIter: 'a,
+    {
+        let mut requests = requests.into_iter().enumerate();
+        async_stream::stream! {
+            let mut loads = vec![0; self.senders.len()];
+            let mut in_flight = FuturesUnordered::new();
+            loop {
+                while let Some(slot) = least_loaded(&loads, self.max_in_flight) {
+                    let Some((index, tx)) = requests.next() else { break };
+                    loads[slot] += 1;
+                    let sender = self.senders[slot];
+                    in_flight.push(async move { (slot, self.send(index, sender, tx).await) });
+                }
+
+                let Some((slot, outcome)) = in_flight.next().await else { break };
+                loads[slot] -= 1;
+                yield outcome;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_least_loaded_sender() {
+        assert_eq!(least_loaded(&[0, 0, 0], 2), Some(0));
+        assert_eq!(least_loaded(&[1, 0, 1], 2), Some(1));
+        assert_eq!(least_loaded(&[2, 1, 1], 2), Some(1));
+        assert_eq!(least_loaded(&[2, 2, 2], 2), None);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn rejects_empty_sender_set() {
+        let provider =
+            crate::ProviderBuilder::new().on_http("http://localhost:8545".parse().unwrap());
+        let err = Dispatcher::new(provider.clone(), []).unwrap_err();
+        assert!(matches!(err, DispatcherError::NoSenders));
+
+        let dispatcher = Dispatcher::new(provider, [Address::ZERO]).unwrap().with_max_in_flight(0);
+        assert_eq!(dispatcher.max_in_flight, 1);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn dispatches_across_senders() {
+        use crate::ProviderBuilder;
+        use alloy_network::EthereumWallet;
+        use alloy_node_bindings::Anvil;
+        use alloy_primitives::{address, U256};
+        use alloy_rpc_types_eth::TransactionRequest;
+        use alloy_signer_local::PrivateKeySigner;
+
+        let anvil = Anvil::new().spawn();
+        let mut wallet = EthereumWallet::from(PrivateKeySigner::from(anvil.keys()[0].clone()));
+        wallet.register_signer(PrivateKeySigner::from(anvil.keys()[1].clone()));
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(wallet)
+            .on_http(anvil.endpoint_url());
+
+        let dispatcher = Dispatcher::from_wallet(provider).unwrap().with_max_in_flight(2);
+        let tx = TransactionRequest::default()
+            .to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .value(U256::from(100));
+        let outcomes = dispatcher.dispatch(vec![tx; 6]).collect::<Vec<_>>().await;
+
+        assert_eq!(outcomes.len(), 6);
+        for sender in dispatcher.senders() {
+            assert!(outcomes.iter().any(|outcome| outcome.sender == *sender));
+        }
+        assert!(outcomes.iter().all(|outcome| outcome.result.as_ref().unwrap().status()));
+    }
+}
@@ -0,0 +1,207 @@
+use std::future::IntoFuture;
+
+use crate::{
+    fillers::{FillerControlFlow, TxFiller},
+    provider::SendableTx,
+    Provider,
+};
+use alloy_eips::eip2930::{AccessList, AccessListResult};
+use alloy_network::{Network, TransactionBuilder};
+use alloy_transport::{Transport, TransportResult};
+
+/// The outcome of an [`AccessListFiller`] preparation.
+#[doc(hidden)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessListFillable {
+    /// The access list lowers the gas usage and should be attached.
+    Attach { access_list: AccessList, gas_limit: u128 },
+    /// The access list does not lower the gas usage. Only the gas limit is filled.
+    Skip { gas_limit: u128 },
+}
+
+impl AccessListFillable {
+    /// Returns the access list generated by `eth_createAccessList`, if it succeeded and is not
+    /// empty.
+    fn access_list(result: AccessListResult) -> Option<AccessList> {
+        result
+            .ensure_ok()
+            .ok()
+            .map(|with_list| with_list.access_list)
+            .filter(|list| !list.is_empty())
+    }
+
+    /// Compares the gas estimates of the transaction with and without the access list.
+    fn new(estimate: u128, access_list: AccessList, estimate_with_list: u128) -> Self {
+        if estimate_with_list < estimate {
+            Self::Attach { access_list, gas_limit: estimate_with_list }
+        } else {
+            Self::Skip { gas_limit: estimate }
+        }
+    }
+}
+
+/// A [`TxFiller`] that attaches the access list generated by `eth_createAccessList`, if it lowers
+/// the gas used by the transaction.
+///
+/// The filler fetches the access list via [`Provider::create_access_list`], and compares the
+/// [`Provider::estimate_gas`] of the transaction with the access list attached against the one
+/// without it. The access list is only attached if it is cheaper, in which case the gas limit is
+/// set to the estimate with the access list. Otherwise, only the gas limit is filled.
+///
+/// The `gasUsed` returned by `eth_createAccessList` is not used as the gas limit, as it doesn't
+/// account for the gas withheld from calls or for refunds, which the estimate does.
+///
+/// ## Note:
+///
+/// - Transactions that already have an `access_list` or a `gas_limit` are left untouched.
+/// - If the transaction request does not have a sender set, this layer will not fill the access
+///   list.
+/// - When combined with the [`GasFiller`], this filler must be added after it, so that its gas
+///   limit takes precedence.
+///
+/// [`GasFiller`]: crate::fillers::GasFiller
+///
+/// # Example
+///
+/// ```
+/// # use alloy_network::{NetworkWallet, EthereumWallet, Ethereum};
+/// # use alloy_rpc_types_eth::TransactionRequest;
+/// # use alloy_provider::{ProviderBuilder, RootProvider, Provider};
+/// # async fn test<W: NetworkWallet<Ethereum> + Clone>(url: url::Url, wallet: W) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = ProviderBuilder::new()
+///     .with_recommended_fillers()
+///     .with_access_list()
+///     .wallet(wallet)
+///     .on_http(url);
+///
+/// provider.send_transaction(TransactionRequest::default()).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AccessListFiller;
+
+impl<N: Network> TxFiller<N> for AccessListFiller {
+    type Fillable = AccessListFillable;
+
+    fn status(&self, tx: &<N as Network>::TransactionRequest) -> FillerControlFlow {
+        if tx.access_list().is_some() || tx.gas_limit().is_some() {
+            return FillerControlFlow::Finished;
+        }
+        if tx.from().is_none() {
+            return FillerControlFlow::missing("AccessListFiller", vec!["from"]);
+        }
+        FillerControlFlow::Ready
+    }
+
+    fn fill_sync(&self, _tx: &mut SendableTx<N>) {}
+
+    async fn prepare<P, T>(
+        &self,
+        provider: &P,
+        tx: &<N as Network>::TransactionRequest,
+    ) -> TransportResult<Self::Fillable>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+    {
+        let (estimate, result) = futures::try_join!(
+            provider.estimate_gas(tx).into_future(),
+            provider.create_access_list(tx).into_future()
+        )?;
+        let Some(access_list) = AccessListFillable::access_list(result) else {
+            return Ok(AccessListFillable::Skip { gas_limit: estimate });
+        };
+
+        let mut with_list = tx.clone();
+        with_list.set_access_list(access_list.clone());
+        let estimate_with_list = provider.estimate_gas(&with_list).await?;
+        Ok(AccessListFillable::new(estimate, access_list, estimate_with_list))
+    }
+
+    async fn fill(
+        &self,
+        fillable: Self::Fillable,
+        mut tx: SendableTx<N>,
+    ) -> TransportResult<SendableTx<N>> {
+        if let Some(builder) = tx.as_mut_builder() {
+            match fillable {
+                AccessListFillable::Attach { access_list, gas_limit } => {
+                    builder.set_access_list(access_list);
+                    builder.set_gas_limit(gas_limit);
+                }
+                AccessListFillable::Skip { gas_limit } => builder.set_gas_limit(gas_limit),
+            }
+        }
+        Ok(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip2930::AccessListItem;
+    use alloy_primitives::{Address, U256};
+
+    fn access_list() -> AccessList {
+        AccessList(vec![AccessListItem {
+            address: Address::ZERO,
+            storage_keys: vec![Default::default()],
+        }])
+    }
+
+    fn result(access_list: AccessList, error: Option<&str>) -> AccessListResult {
+        AccessListResult { access_list, gas_used: U256::from(20_000), error: error.map(Into::into) }
+    }
+
+    #[test]
+    fn attaches_cheaper_access_list() {
+        let fillable = AccessListFillable::new(30_000, access_list(), 29_000);
+        assert_eq!(
+            fillable,
+            AccessListFillable::Attach { access_list: access_list(), gas_limit: 29_000 }
+        );
+    }
+
+    #[test]
+    fn skips_access_list() {
+        let expensive = AccessListFillable::new(30_000, access_list(), 30_100);
+        assert_eq!(expensive, AccessListFillable::Skip { gas_limit: 30_000 });
+
+        let failed = result(access_list(), Some("execution reverted"));
+        assert_eq!(AccessListFillable::access_list(failed), None);
+
+        let empty = result(AccessList::default(), None);
+        assert_eq!(AccessListFillable::access_list(empty), None);
+
+        assert_eq!(
+            AccessListFillable::access_list(result(access_list(), None)),
+            Some(access_list())
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn fills_gas_limit_for_transfer() {
+        use crate::{ProviderBuilder, WalletProvider};
+        use alloy_primitives::address;
+        use alloy_rpc_types_eth::TransactionRequest;
+
+        let provider =
+            ProviderBuilder::new().with_gas_estimation().with_access_list().on_anvil_with_wallet();
+
+        let tx = TransactionRequest {
+            from: Some(provider.default_signer_address()),
+            value: Some(U256::from(100)),
+            to: Some(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045").into()),
+            ..Default::default()
+        };
+
+        let filled = provider.fill(tx).await.unwrap();
+        let filled = filled.as_builder().unwrap();
+
+        // a plain transfer does not benefit from an access list
+        assert_eq!(filled.access_list, None);
+        assert_eq!(filled.gas, Some(21_000));
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{
+    utils::{self, Eip1559Estimation},
+    Provider,
+};
+use alloy_json_rpc::RpcError;
+use alloy_network::Network;
+use alloy_network_primitives::{BlockResponse, HeaderResponse};
+use alloy_rpc_types_eth::BlockNumberOrTag;
+use alloy_transport::{Transport, TransportResult};
+use async_trait::async_trait;
+
+/// A trait that determines how the [`GasFiller`] estimates fees.
+///
+/// Legacy gas prices are always fetched with [`Provider::get_gas_price`]. Estimators that cannot
+/// estimate EIP-1559 fees on the current network should return
+/// [`RpcError::UnsupportedFeature`], in which case the [`GasFiller`] falls back to legacy
+/// transactions.
+///
+/// [`GasFiller`]: crate::fillers::GasFiller
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait FeeEstimator: Clone + Send + Sync + std::fmt::Debug {
+    /// Estimates the `max_fee_per_gas` and `max_priority_fee_per_gas` fields.
+    async fn estimate_eip1559_fees<P, T, N>(
+        &self,
+        provider: &P,
+    ) -> TransportResult<Eip1559Estimation>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network;
+
+    /// Estimates the `max_fee_per_blob_gas` field.
+    ///
+    /// Defaults to the current blob base fee, as returned by [`Provider::get_blob_base_fee`].
+    async fn estimate_blob_fee<P, T, N>(&self, provider: &P) -> TransportResult<u128>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        provider.get_blob_base_fee().await
+    }
+}
+
+/// The default [`FeeEstimator`].
+///
+/// EIP-1559 fees are estimated with [`Provider::estimate_eip1559_fees`] using the
+/// [default estimator](utils::eip1559_default_estimator), and the blob fee is the next block's blob
+/// fee, as computed from the latest block header.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultFeeEstimator;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl FeeEstimator for DefaultFeeEstimator {
+    async fn estimate_eip1559_fees<P, T, N>(
+        &self,
+        provider: &P,
+    ) -> TransportResult<Eip1559Estimation>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        provider.estimate_eip1559_fees(None).await
+    }
+
+    async fn estimate_blob_fee<P, T, N>(&self, provider: &P) -> TransportResult<u128>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        latest_header_fee(provider, |header| header.next_block_blob_fee(), "eip4844").await
+    }
+}
+
+/// A [`FeeEstimator`] based on the rewards paid in recent blocks.
+///
+/// The priority fee is the median of the non-zero rewards at the configured percentiles over the
+/// last `block_count` blocks, and the max fee adds the latest base fee scaled by the base fee
+/// multiplier. The blob fee is the current blob base fee scaled by the same multiplier.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeHistoryEstimator {
+    block_count: u64,
+    reward_percentiles: Vec<f64>,
+    base_fee_multiplier: f64,
+}
+
+impl Default for FeeHistoryEstimator {
+    fn default() -> Self {
+        Self::new(
+            utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+            [utils::EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
+        )
+    }
+}
+
+impl FeeHistoryEstimator {
+    /// Creates a new estimator over the given number of blocks and reward percentiles.
+    pub fn new(block_count: u64, reward_percentiles: impl Into<Vec<f64>>) -> Self {
+        Self {
+            block_count,
+            reward_percentiles: reward_percentiles.into(),
+            base_fee_multiplier: utils::EIP1559_BASE_FEE_MULTIPLIER as f64,
+        }
+    }
+
+    /// Sets the multiplier applied to the base fee to compute the max fee.
+    pub const fn with_base_fee_multiplier(mut self, base_fee_multiplier: f64) -> Self {
+        self.base_fee_multiplier = base_fee_multiplier;
+        self
+    }
+
+    /// Returns the number of blocks the rewards are fetched for.
+    pub const fn block_count(&self) -> u64 {
+        self.block_count
+    }
+
+    /// Returns the reward percentiles.
+    pub fn reward_percentiles(&self) -> &[f64] {
+        &self.reward_percentiles
+    }
+
+    /// Returns the multiplier applied to the base fee.
+    pub const fn base_fee_multiplier(&self) -> f64 {
+        self.base_fee_multiplier
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl FeeEstimator for FeeHistoryEstimator {
+    async fn estimate_eip1559_fees<P, T, N>(
+        &self,
+        provider: &P,
+    ) -> TransportResult<Eip1559Estimation>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        let fee_history = provider
+            .get_fee_history(self.block_count, BlockNumberOrTag::Latest, &self.reward_percentiles)
+            .await?;
+
+        let base_fee_per_gas = match fee_history.latest_block_base_fee() {
+            Some(base_fee) if base_fee != 0 => base_fee,
+            _ => latest_header_fee(provider, |header| header.base_fee_per_gas(), "eip1559").await?,
+        };
+
+        let max_priority_fee_per_gas =
+            median_reward(fee_history.reward.as_deref().unwrap_or_default());
+        Ok(Eip1559Estimation {
+            max_fee_per_gas: scale(base_fee_per_gas, self.base_fee_multiplier)
+                + max_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+
+    async fn estimate_blob_fee<P, T, N>(&self, provider: &P) -> TransportResult<u128>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        Ok(scale(provider.get_blob_base_fee().await?, self.base_fee_multiplier))
+    }
+}
+
+/// A [`FeeEstimator`] that scales the latest base fee by a fixed multiplier.
+///
+/// The priority fee is the node's suggestion, as returned by
+/// [`Provider::get_max_priority_fee_per_gas`]. The blob fee is the current blob base fee scaled by
+/// the same multiplier.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BaseFeeMultiplierEstimator {
+    multiplier: f64,
+}
+
+impl BaseFeeMultiplierEstimator {
+    /// Creates a new estimator with the given base fee multiplier.
+    pub const fn new(multiplier: f64) -> Self {
+        Self { multiplier }
+    }
+
+    /// Returns the base fee multiplier.
+    pub const fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl FeeEstimator for BaseFeeMultiplierEstimator {
+    async fn estimate_eip1559_fees<P, T, N>(
+        &self,
+        provider: &P,
+    ) -> TransportResult<Eip1559Estimation>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        let base_fee_fut =
+            latest_header_fee(provider, |header| header.base_fee_per_gas(), "eip1559");
+        let (base_fee_per_gas, max_priority_fee_per_gas) =
+            futures::try_join!(base_fee_fut, provider.get_max_priority_fee_per_gas())?;
+
+        Ok(Eip1559Estimation {
+            max_fee_per_gas: scale(base_fee_per_gas, self.multiplier) + max_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+
+    async fn estimate_blob_fee<P, T, N>(&self, provider: &P) -> TransportResult<u128>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        Ok(scale(provider.get_blob_base_fee().await?, self.multiplier))
+    }
+}
+
+/// A [`FeeEstimator`] based on the pending block.
+///
+/// The max fee is the base fee of the pending block plus the node's suggested priority fee, as
+/// returned by [`Provider::get_max_priority_fee_per_gas`]. This targets inclusion in the next block
+/// with no headroom for base fee increases.
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct PendingBlockEstimator;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl FeeEstimator for PendingBlockEstimator {
+    async fn estimate_eip1559_fees<P, T, N>(
+        &self,
+        provider: &P,
+    ) -> TransportResult<Eip1559Estimation>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        let base_fee_fut = async {
+            provider
+                .get_block_by_number(BlockNumberOrTag::Pending, false)
+                .await?
+                .ok_or(RpcError::NullResp)?
+                .header()
+                .base_fee_per_gas()
+                .ok_or(RpcError::UnsupportedFeature("eip1559"))
+        };
+        let (base_fee_per_gas, max_priority_fee_per_gas) =
+            futures::try_join!(base_fee_fut, provider.get_max_priority_fee_per_gas())?;
+
+        Ok(Eip1559Estimation {
+            max_fee_per_gas: base_fee_per_gas + max_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+}
+
+/// How quickly a transaction should be included, see [`UrgencyEstimator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Urgency {
+    /// Cheap, but may take a while to be included.
+    Slow,
+    /// The default trade-off between cost and inclusion time.
+    #[default]
+    Normal,
+    /// Expensive, but likely to be included quickly.
+    Fast,
+}
+
+impl Urgency {
+    /// Returns the reward percentile used to estimate the priority fee.
+    pub const fn reward_percentile(&self) -> f64 {
+        match self {
+            Self::Slow => 10.0,
+            Self::Normal => 20.0,
+            Self::Fast => 30.0,
+        }
+    }
+
+    /// Returns the multiplier applied to the base fee to compute the max fee.
+    pub const fn base_fee_multiplier(&self) -> f64 {
+        match self {
+            Self::Slow => 1.1,
+            Self::Normal => 1.2,
+            Self::Fast => 1.25,
+        }
+    }
+}
+
+/// A [`FeeEstimator`] with preset aggressiveness tiers.
+///
+/// This is a [`FeeHistoryEstimator`] configured for the given [`Urgency`]. Based on the work by
+/// [MetaMask](https://github.com/MetaMask/core/blob/main/packages/gas-fee-controller/src/fetchGasEstimatesViaEthFeeHistory/calculateGasFeeEstimatesForPriorityLevels.ts#L56).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UrgencyEstimator {
+    urgency: Urgency,
+}
+
+impl UrgencyEstimator {
+    /// Creates a new estimator for the given urgency.
+    pub const fn new(urgency: Urgency) -> Self {
+        Self { urgency }
+    }
+
+    /// Returns the urgency.
+    pub const fn urgency(&self) -> Urgency {
+        self.urgency
+    }
+
+    fn fee_history(&self) -> FeeHistoryEstimator {
+        FeeHistoryEstimator::new(
+            utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+            [self.urgency.reward_percentile()],
+        )
+        .with_base_fee_multiplier(self.urgency.base_fee_multiplier())
+    }
+}
+
+impl From<Urgency> for UrgencyEstimator {
+    fn from(urgency: Urgency) -> Self {
+        Self::new(urgency)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl FeeEstimator for UrgencyEstimator {
+    async fn estimate_eip1559_fees<P, T, N>(
+        &self,
+        provider: &P,
+    ) -> TransportResult<Eip1559Estimation>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        self.fee_history().estimate_eip1559_fees(provider).await
+    }
+
+    async fn estimate_blob_fee<P, T, N>(&self, provider: &P) -> TransportResult<u128>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        self.fee_history().estimate_blob_fee(provider).await
+    }
+}
+
+/// Fetches a fee from the latest block header, failing with [`RpcError::UnsupportedFeature`] if it
+/// is not set.
+async fn latest_header_fee<P, T, N>(
+    provider: &P,
+    fee: impl FnOnce(&N::HeaderResponse) -> Option<u128>,
+    feature: &'static str,
+) -> TransportResult<u128>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Latest, false)
+        .await?
+        .ok_or(RpcError::NullResp)?;
+    fee(block.header()).ok_or(RpcError::UnsupportedFeature(feature))
+}
+
+/// Returns the median of the non-zero rewards at all percentiles, and at least the minimum
+/// priority fee.
+fn median_reward(rewards: &[Vec<u128>]) -> u128 {
+    let mut rewards = rewards.iter().flatten().copied().filter(|r| *r > 0).collect::<Vec<_>>();
+    if rewards.is_empty() {
+        return utils::EIP1559_MIN_PRIORITY_FEE;
+    }
+    rewards.sort_unstable();
+
+    let n = rewards.len();
+    let median =
+        if n % 2 == 0 { (rewards[n / 2 - 1] + rewards[n / 2]) / 2 } else { rewards[n / 2] };
+    median.max(utils::EIP1559_MIN_PRIORITY_FEE)
+}
+
+/// Scales `value` by `multiplier`.
+fn scale(value: u128, multiplier: f64) -> u128 {
+    (value as f64 * multiplier) as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_rewards() {
+        let rewards = vec![vec![300_u128], vec![0], vec![100], vec![200]];
+        assert_eq!(median_reward(&rewards), 200);
+        let rewards = vec![vec![100_u128, 400], vec![200, 500]];
+        assert_eq!(median_reward(&rewards), 300);
+        assert_eq!(median_reward(&[]), utils::EIP1559_MIN_PRIORITY_FEE);
+    }
+
+    #[test]
+    fn urgency_tiers() {
+        let [slow, normal, fast] = [Urgency::Slow, Urgency::Normal, Urgency::Fast]
+            .map(|u| UrgencyEstimator::new(u).fee_history());
+        assert!(slow.reward_percentiles() < normal.reward_percentiles());
+        assert!(normal.reward_percentiles() < fast.reward_percentiles());
+        assert!(slow.base_fee_multiplier() < normal.base_fee_multiplier());
+        assert!(normal.base_fee_multiplier() < fast.base_fee_multiplier());
+        assert_eq!(UrgencyEstimator::default().urgency(), Urgency::Normal);
+    }
+
+    #[test]
+    fn scales_fees() {
+        assert_eq!(scale(1_000_000_000, 1.25), 1_250_000_000);
+        assert_eq!(scale(100, 2.0), 200);
+    }
+}
@@ -1,15 +1,13 @@
 use std::future::IntoFuture;
 
 use crate::{
-    fillers::{FillerControlFlow, TxFiller},
+    fillers::{DefaultFeeEstimator, FeeEstimator, FillerControlFlow, TxFiller},
     provider::SendableTx,
     utils::Eip1559Estimation,
     Provider,
 };
 use alloy_json_rpc::RpcError;
 use alloy_network::{Network, TransactionBuilder};
-use alloy_network_primitives::{BlockResponse, HeaderResponse};
-use alloy_rpc_types_eth::BlockNumberOrTag;
 use alloy_transport::{Transport, TransportResult};
 use futures::FutureExt;
 
@@ -29,8 +27,8 @@ pub enum GasFillable {
 /// max_priority_fee_per_gas and max_fee_per_blob_gas.
 ///
 /// The layer fetches the estimations for these via the
-/// [`Provider::get_gas_price`], [`Provider::estimate_gas`] and
-/// [`Provider::estimate_eip1559_fees`] methods.
+/// [`Provider::get_gas_price`] and [`Provider::estimate_gas`] methods, and
+/// the configured [`FeeEstimator`], which defaults to [`DefaultFeeEstimator`].
 ///
 /// ## Note:
 ///
@@ -63,9 +61,21 @@ pub enum GasFillable {
 /// # }
 /// ```
 #[derive(Clone, Copy, Debug, Default)]
-pub struct GasFiller;
+pub struct GasFiller<E: FeeEstimator = DefaultFeeEstimator> {
+    estimator: E,
+}
+
+impl<E: FeeEstimator> GasFiller<E> {
+    /// Creates a new `GasFiller` with the given [`FeeEstimator`].
+    pub const fn new(estimator: E) -> Self {
+        Self { estimator }
+    }
+
+    /// Returns a reference to the [`FeeEstimator`].
+    pub const fn estimator(&self) -> &E {
+        &self.estimator
+    }
 
-impl GasFiller {
     async fn prepare_legacy<P, T, N>(
         &self,
         provider: &P,
@@ -112,7 +122,7 @@ impl GasFiller {
             async move { Ok(Eip1559Estimation { max_fee_per_gas, max_priority_fee_per_gas }) }
                 .left_future()
         } else {
-            provider.estimate_eip1559_fees(None).right_future()
+            self.estimator.estimate_eip1559_fees(provider).right_future()
         };
 
         let (gas_limit, estimate) = futures::try_join!(gas_limit_fut, eip1559_fees_fut)?;
@@ -141,22 +151,11 @@ impl GasFiller {
             async move { Ok(Eip1559Estimation { max_fee_per_gas, max_priority_fee_per_gas }) }
                 .left_future()
         } else {
-            provider.estimate_eip1559_fees(None).right_future()
+            self.estimator.estimate_eip1559_fees(provider).right_future()
         };
 
         let max_fee_per_blob_gas_fut = tx.max_fee_per_blob_gas().map_or_else(
-            || {
-                async {
-                    provider
-                        .get_block_by_number(BlockNumberOrTag::Latest, false)
-                        .await?
-                        .ok_or(RpcError::NullResp)?
-                        .header()
-                        .next_block_blob_fee()
-                        .ok_or(RpcError::UnsupportedFeature("eip4844"))
-                }
-                .right_future()
-            },
+            || self.estimator.estimate_blob_fee(provider).right_future(),
             |max_fee_per_blob_gas| async move { Ok(max_fee_per_blob_gas) }.left_future(),
         );
 
@@ -167,7 +166,7 @@ impl GasFiller {
     }
 }
 
-impl<N: Network> TxFiller<N> for GasFiller {
+impl<N: Network, E: FeeEstimator> TxFiller<N> for GasFiller<E> {
     type Fillable = GasFillable;
 
     fn status(&self, tx: &<N as Network>::TransactionRequest) -> FillerControlFlow {
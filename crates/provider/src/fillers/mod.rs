@@ -6,6 +6,9 @@
 //!
 //! [`Provider`]: crate::Provider
 
+mod access_list;
+pub use access_list::{AccessListFillable, AccessListFiller};
+
 mod chain_id;
 pub use chain_id::ChainIdFiller;
 
@@ -13,7 +16,15 @@ mod wallet;
 pub use wallet::WalletFiller;
 
 mod nonce;
-pub use nonce::{CachedNonceManager, NonceFiller, NonceManager, SimpleNonceManager};
+pub use nonce::{
+    CachedNonceManager, NonceFiller, NonceManager, ReservingNonceManager, SimpleNonceManager,
+};
+
+mod fee_estimator;
+pub use fee_estimator::{
+    BaseFeeMultiplierEstimator, DefaultFeeEstimator, FeeEstimator, FeeHistoryEstimator,
+    PendingBlockEstimator, Urgency, UrgencyEstimator,
+};
 
 mod gas;
 pub use gas::{GasFillable, GasFiller};
@@ -250,7 +261,7 @@ where
         self.filler.join_with(other).layer(self.inner)
     }
 
-    async fn fill_inner(&self, mut tx: SendableTx<N>) -> TransportResult<SendableTx<N>> {
+    pub(crate) async fn fill_inner(&self, mut tx: SendableTx<N>) -> TransportResult<SendableTx<N>> {
         let mut count = 0;
 
         while self.filler.continue_filling(&tx) {
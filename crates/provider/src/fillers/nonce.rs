@@ -9,7 +9,11 @@ use alloy_transport::{Transport, TransportResult};
 use async_trait::async_trait;
 use dashmap::DashMap;
 use futures::lock::Mutex;
-use std::sync::Arc;
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// A trait that determines the behavior of filling nonces.
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -88,6 +92,232 @@ impl NonceManager for CachedNonceManager {
     }
 }
 
+/// The default interval between two reconciliations of a [`ReservingNonceManager`].
+const DEFAULT_RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The nonces of a single account tracked by a [`ReservingNonceManager`].
+#[derive(Clone, Debug)]
+struct NonceState {
+    /// The next nonce that has never been handed out.
+    next: u64,
+    /// Nonces handed out whose transaction has not been broadcast yet.
+    reserved: BTreeSet<u64>,
+    /// Nonces handed out and then released, to be handed out again before `next`.
+    released: BTreeSet<u64>,
+    /// When the state was last reconciled with the node.
+    reconciled_at: Instant,
+    /// Whether the state was reset, and must be resynced with the node before the next nonce is
+    /// handed out.
+    stale: bool,
+}
+
+impl NonceState {
+    fn new(pending: u64) -> Self {
+        Self {
+            next: pending,
+            reserved: BTreeSet::new(),
+            released: BTreeSet::new(),
+            reconciled_at: Instant::now(),
+            stale: false,
+        }
+    }
+
+    fn reserve(&mut self) -> u64 {
+        let nonce = self.released.pop_first().unwrap_or_else(|| {
+            self.next += 1;
+            self.next - 1
+        });
+        self.reserved.insert(nonce);
+        nonce
+    }
+
+    fn release(&mut self, nonce: u64) {
+        if !self.reserved.remove(&nonce) {
+            return;
+        }
+        self.released.insert(nonce);
+        // Shrink `next` back down over the trailing released nonces.
+        while self.next > 0 && self.released.remove(&(self.next - 1)) {
+            self.next -= 1;
+        }
+    }
+
+    fn confirm(&mut self, nonce: u64) {
+        self.reserved.remove(&nonce);
+    }
+
+    /// Reconciles the state with the `pending` transaction count of the account.
+    fn reconcile(&mut self, pending: u64) {
+        self.reconciled_at = Instant::now();
+        self.released.retain(|&nonce| nonce >= pending);
+        if pending > self.next {
+            // Transactions were sent from this account by someone else.
+            self.next = pending;
+        } else if pending < self.next && self.reserved.is_empty() {
+            // Some broadcast transactions are missing from the node, which leaves a gap that
+            // would block every later transaction.
+            debug!(pending, next = self.next, "nonce gap detected, resyncing");
+            self.next = pending;
+            self.released.clear();
+        }
+    }
+
+    /// Resyncs the state with the `pending` transaction count of the account after a reset.
+    ///
+    /// Pending reservations are kept, so that their nonces are not handed out twice.
+    fn resync(&mut self, pending: u64) {
+        self.reconciled_at = Instant::now();
+        self.stale = false;
+        self.next = pending.max(self.reserved.last().map_or(0, |nonce| nonce + 1));
+        let next = self.next;
+        self.released.retain(|&nonce| nonce >= pending && nonce < next);
+    }
+}
+
+/// This [`NonceManager`] implementation tracks the nonces it hands out as reservations, so that
+/// nonces of transactions that failed to be broadcast can be released and handed out again.
+///
+/// Unlike [`CachedNonceManager`], it periodically reconciles its state against the `pending`
+/// transaction count of the account, and resyncs when it detects a gap, e.g. because a
+/// transaction was dropped by the node.
+///
+/// Nonces are reserved with [`ReservingNonceManager::reserve`], and the reservations released and
+/// confirmed with [`ReservingNonceManager::release`] and [`ReservingNonceManager::confirm`]. The
+/// [`NonceRecoveryLayer`] does so automatically, and retries transactions rejected by the node
+/// with a nonce error.
+///
+/// When used as the [`NonceManager`] of a [`NonceFiller`], nonces are handed out without being
+/// reserved, as the filler cannot tell whether the transaction was broadcast.
+///
+/// Clones of this manager share their state.
+///
+/// [`NonceRecoveryLayer`]: crate::layers::NonceRecoveryLayer
+#[derive(Clone, Debug)]
+pub struct ReservingNonceManager {
+    nonces: Arc<DashMap<Address, Arc<Mutex<Option<NonceState>>>>>,
+    reconcile_interval: Duration,
+}
+
+impl Default for ReservingNonceManager {
+    fn default() -> Self {
+        Self { nonces: Default::default(), reconcile_interval: DEFAULT_RECONCILE_INTERVAL }
+    }
+}
+
+impl ReservingNonceManager {
+    /// Sets the interval after which the state of an account is reconciled against the node.
+    ///
+    /// Reconciliation happens on the next nonce request after the interval has elapsed, and only
+    /// while no reservation of the account is pending.
+    pub const fn with_reconcile_interval(mut self, reconcile_interval: Duration) -> Self {
+        self.reconcile_interval = reconcile_interval;
+        self
+    }
+
+    fn state(&self, address: Address) -> Arc<Mutex<Option<NonceState>>> {
+        Arc::clone(self.nonces.entry(address).or_default().value())
+    }
+
+    /// Reserves the next nonce of the account, until it is released or confirmed.
+    pub async fn reserve<P, T, N>(&self, provider: &P, address: Address) -> TransportResult<u64>
+    where
+        P: Provider<T, N>,
+        N: Network,
+        T: Transport + Clone,
+    {
+        let state = self.state(address);
+        let mut state = state.lock().await;
+        match state.as_mut() {
+            Some(state) if state.stale => {
+                state.resync(provider.get_transaction_count(address).pending().await?);
+            }
+            Some(state)
+                if state.reserved.is_empty()
+                    && state.reconciled_at.elapsed() >= self.reconcile_interval =>
+            {
+                state.reconcile(provider.get_transaction_count(address).pending().await?);
+            }
+            Some(_) => {}
+            None => {
+                let pending = provider.get_transaction_count(address).pending().await?;
+                *state = Some(NonceState::new(pending));
+            }
+        }
+        Ok(state.as_mut().expect("initialized above").reserve())
+    }
+
+    /// Releases a reserved nonce, because the node rejected its transaction. The nonce is handed
+    /// out again before any new one.
+    ///
+    /// Nonces must not be released if the transaction may have been broadcast, e.g. after a
+    /// transport error, as it would be handed out twice.
+    pub async fn release(&self, address: Address, nonce: u64) {
+        if let Some(state) = self.state(address).lock().await.as_mut() {
+            state.release(nonce);
+        }
+    }
+
+    /// Confirms that the transaction with a reserved nonce was broadcast.
+    pub async fn confirm(&self, address: Address, nonce: u64) {
+        if let Some(state) = self.state(address).lock().await.as_mut() {
+            state.confirm(nonce);
+        }
+    }
+
+    /// Resyncs the account with the node before the next nonce is handed out.
+    ///
+    /// Pending reservations are kept, and nonces are never handed out below them.
+    pub async fn reset(&self, address: Address) {
+        if let Some(state) = self.state(address).lock().await.as_mut() {
+            state.stale = true;
+        }
+    }
+
+    /// Seeds the next nonce of the account, e.g. from a journal of in-flight transactions.
+    ///
+    /// Does nothing if the manager would already hand out a higher nonce.
+    pub async fn seed(&self, address: Address, next: u64) {
+        let state = self.state(address);
+        let mut state = state.lock().await;
+        match state.as_mut() {
+            Some(state) => state.next = state.next.max(next),
+            None => *state = Some(NonceState::new(next)),
+        }
+    }
+
+    /// Reconciles the state of the account against its `pending` transaction count.
+    pub async fn reconcile<P, T, N>(&self, provider: &P, address: Address) -> TransportResult<()>
+    where
+        P: Provider<T, N>,
+        N: Network,
+        T: Transport + Clone,
+    {
+        let state = self.state(address);
+        let mut state = state.lock().await;
+        let pending = provider.get_transaction_count(address).pending().await?;
+        match state.as_mut() {
+            Some(state) => state.reconcile(pending),
+            None => *state = Some(NonceState::new(pending)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl NonceManager for ReservingNonceManager {
+    async fn get_next_nonce<P, T, N>(&self, provider: &P, address: Address) -> TransportResult<u64>
+    where
+        P: Provider<T, N>,
+        N: Network,
+        T: Transport + Clone,
+    {
+        let nonce = self.reserve(provider, address).await?;
+        self.confirm(address, nonce).await;
+        Ok(nonce)
+    }
+}
+
 /// A [`TxFiller`] that fills nonces on transactions. The behavior of filling nonces is determined
 /// by the [`NonceManager`].
 ///
@@ -173,6 +403,66 @@ mod tests {
     use alloy_primitives::{address, U256};
     use alloy_rpc_types_eth::TransactionRequest;
 
+    #[test]
+    fn releases_nonces() {
+        let mut state = NonceState::new(5);
+        assert_eq!((state.reserve(), state.reserve(), state.reserve()), (5, 6, 7));
+
+        // Releasing a nonce in the middle hands it out again first.
+        state.release(6);
+        assert_eq!(state.reserve(), 6);
+
+        // Releasing the trailing nonces shrinks the next nonce.
+        state.release(6);
+        state.release(7);
+        assert_eq!(state.next, 6);
+        assert!(state.released.is_empty());
+        assert_eq!(state.reserve(), 6);
+
+        // Unknown nonces are ignored.
+        state.release(42);
+        assert_eq!(state.reserve(), 7);
+    }
+
+    #[test]
+    fn reconciles_gaps() {
+        let mut state = NonceState::new(0);
+        for _ in 0..3 {
+            let nonce = state.reserve();
+            state.confirm(nonce);
+        }
+
+        // The transaction with nonce 1 was dropped, leaving a gap.
+        state.reconcile(1);
+        assert_eq!(state.reserve(), 1);
+
+        // Pending reservations prevent resyncing down.
+        state.reconcile(0);
+        assert_eq!(state.next, 2);
+
+        // Transactions sent by someone else.
+        state.reconcile(10);
+        assert_eq!(state.reserve(), 10);
+    }
+
+    #[test]
+    fn resyncs_keeping_reservations() {
+        let mut state = NonceState::new(0);
+        let (first, second) = (state.reserve(), state.reserve());
+        state.confirm(first);
+
+        // The node is behind the reservation of the second nonce, which is still in flight.
+        state.stale = true;
+        state.resync(1);
+        assert!(!state.stale);
+        assert_eq!(state.reserve(), second + 1);
+
+        // Nonces below the pending transaction count are never handed out again.
+        state.release(second);
+        state.resync(5);
+        assert_eq!(state.reserve(), 5);
+    }
+
     async fn check_nonces<P, T, N, M>(
         filler: &NonceFiller<M>,
         provider: &P,
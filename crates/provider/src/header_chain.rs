@@ -0,0 +1,216 @@
+//! Verification of header chains served by untrusted RPC nodes.
+
+use crate::Provider;
+use alloy_consensus::{Header, HeaderValidationError};
+use alloy_eips::eip1559::BaseFeeParams;
+use alloy_network::{BlockResponse, HeaderResponse, Network};
+use alloy_primitives::{BlockHash, BlockNumber};
+use alloy_rpc_types_eth::{BlockNumberOrTag, ConversionError};
+use alloy_transport::{Transport, TransportError};
+use futures::{StreamExt, TryStreamExt};
+
+/// The number of blocks fetched concurrently by [`HeaderChainVerifier::verify_to`].
+const CONCURRENCY: usize = 16;
+
+/// An error that occurred while verifying a header chain.
+#[derive(Debug, thiserror::Error)]
+pub enum HeaderChainError {
+    /// The node did not return the block.
+    #[error("block {0} not found")]
+    MissingBlock(BlockNumber),
+    /// The hash reported by the node is not the hash of the header.
+    #[error("block {number} hash mismatch: reported {reported}, computed {computed}")]
+    HashMismatch {
+        /// The block number.
+        number: BlockNumber,
+        /// The hash reported by the node.
+        reported: BlockHash,
+        /// The hash of the header.
+        computed: BlockHash,
+    },
+    /// The header is not a valid child of its parent.
+    #[error("block {number} is invalid: {source}")]
+    Invalid {
+        /// The block number.
+        number: BlockNumber,
+        /// The validation error.
+        source: HeaderValidationError,
+    },
+    /// The header could not be converted to a consensus header.
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+}
+
+/// Verifies headers fetched from an untrusted RPC node, starting from a trusted checkpoint.
+///
+/// Every header is checked to hash to the hash reported by the node, and to be a valid child of the
+/// previous one, see [`Header::validate_against_parent`]. This detects nodes that lie about the
+/// chain, or that follow a fork of it.
+///
+/// The verifier advances its tip as headers are verified, so that later calls continue from the
+/// last verified header. The verified headers can be trusted for state reads with
+/// [`TrustedHeaders`](crate::layers::TrustedHeaders).
+///
+/// # Example
+///
+/// ```no_run
+/// # use alloy_consensus::Header;
+/// # use alloy_provider::{HeaderChainVerifier, Provider, ProviderBuilder};
+/// # async fn test(url: url::Url, checkpoint: Header) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = ProviderBuilder::new().on_http(url);
+/// let mut verifier = HeaderChainVerifier::new(checkpoint);
+///
+/// let latest = provider.get_block_number().await?;
+/// let headers = verifier.verify_to(&provider, latest).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeaderChainVerifier {
+    tip: Header,
+    base_fee_params: BaseFeeParams,
+}
+
+impl HeaderChainVerifier {
+    /// Creates a new verifier starting from the given trusted checkpoint, with the Ethereum base
+    /// fee parameters.
+    pub const fn new(checkpoint: Header) -> Self {
+        Self { tip: checkpoint, base_fee_params: BaseFeeParams::ethereum() }
+    }
+
+    /// Sets the base fee parameters of the chain.
+    pub const fn with_base_fee_params(mut self, base_fee_params: BaseFeeParams) -> Self {
+        self.base_fee_params = base_fee_params;
+        self
+    }
+
+    /// Returns the last verified header, or the checkpoint if none was verified yet.
+    pub const fn tip(&self) -> &Header {
+        &self.tip
+    }
+
+    /// Verifies the header against the current tip, and makes it the new tip.
+    ///
+    /// `reported_hash` is the hash of the header as reported by the node.
+    pub fn verify_next(
+        &mut self,
+        header: Header,
+        reported_hash: BlockHash,
+    ) -> Result<(), HeaderChainError> {
+        let computed = header.hash_slow();
+        if computed != reported_hash {
+            return Err(HeaderChainError::HashMismatch {
+                number: header.number,
+                reported: reported_hash,
+                computed,
+            });
+        }
+        header
+            .validate_against_parent(&self.tip, self.base_fee_params)
+            .map_err(|source| HeaderChainError::Invalid { number: header.number, source })?;
+        self.tip = header;
+        Ok(())
+    }
+
+    /// Fetches the headers after the tip up to and including `to` with `eth_getBlockByNumber`,
+    /// and verifies them.
+    ///
+    /// Returns the verified headers in ascending order. On error, the tip is the last header that
+    /// was verified.
+    pub async fn verify_to<P, T, N>(
+        &mut self,
+        provider: &P,
+        to: BlockNumber,
+    ) -> Result<Vec<Header>, HeaderChainError>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+        N::HeaderResponse: Clone + TryInto<Header, Error = ConversionError>,
+    {
+        let from = self.tip.number + 1;
+        let mut blocks = futures::stream::iter(from..=to)
+            .map(|number| async move {
+                provider
+                    .get_block_by_number(BlockNumberOrTag::Number(number), false)
+                    .await?
+                    .ok_or(HeaderChainError::MissingBlock(number))
+            })
+            .buffered(CONCURRENCY);
+
+        let mut headers = Vec::with_capacity(to.saturating_sub(from - 1) as usize);
+        while let Some(block) = blocks.try_next().await? {
+            let reported_hash = block.header().hash();
+            let header = block.header().clone().try_into()?;
+            self.verify_next(header, reported_hash)?;
+            headers.push(self.tip.clone());
+        }
+        Ok(headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    fn child(parent: &Header) -> Header {
+        Header {
+            number: parent.number + 1,
+            parent_hash: parent.hash_slow(),
+            timestamp: parent.timestamp + 12,
+            gas_limit: parent.gas_limit,
+            base_fee_per_gas: parent.next_block_base_fee(BaseFeeParams::ethereum()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn verifies_next_headers() {
+        let checkpoint =
+            Header { gas_limit: 30_000_000, base_fee_per_gas: Some(7), ..Default::default() };
+        let mut verifier = HeaderChainVerifier::new(checkpoint.clone());
+
+        let first = child(&checkpoint);
+        verifier.verify_next(first.clone(), first.hash_slow()).unwrap();
+        assert_eq!(verifier.tip(), &first);
+
+        // A lying node reporting another hash.
+        let second = child(&first);
+        let err = verifier.verify_next(second.clone(), B256::ZERO).unwrap_err();
+        assert!(matches!(err, HeaderChainError::HashMismatch { number: 2, .. }));
+
+        // A forked node serving a header that does not link to the tip.
+        let fork = Header { parent_hash: B256::repeat_byte(1), ..second.clone() };
+        let err = verifier.verify_next(fork.clone(), fork.hash_slow()).unwrap_err();
+        assert!(matches!(
+            err,
+            HeaderChainError::Invalid {
+                number: 2,
+                source: HeaderValidationError::ParentHashMismatch { .. }
+            }
+        ));
+        assert_eq!(verifier.tip(), &first);
+
+        verifier.verify_next(second.clone(), second.hash_slow()).unwrap();
+        assert_eq!(verifier.tip(), &second);
+    }
+
+    #[cfg(all(feature = "reqwest", feature = "anvil-api"))]
+    #[tokio::test]
+    async fn verifies_anvil_headers() {
+        use crate::{ext::AnvilApi, ProviderBuilder};
+
+        let provider = ProviderBuilder::new().on_anvil();
+        let genesis = provider.get_block_by_number(0.into(), false).await.unwrap().unwrap();
+        let mut verifier = HeaderChainVerifier::new(genesis.header.try_into().unwrap());
+
+        provider.anvil_mine(Some(3.try_into().unwrap()), None).await.unwrap();
+        let headers = verifier.verify_to(&provider, 3).await.unwrap();
+        assert_eq!(headers.iter().map(|header| header.number).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(verifier.tip().number, 3);
+    }
+}
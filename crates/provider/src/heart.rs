@@ -3,14 +3,20 @@
 use crate::{Provider, RootProvider};
 use alloy_json_rpc::RpcError;
 use alloy_network::Network;
-use alloy_primitives::{TxHash, B256};
-use alloy_rpc_types_eth::Block;
-use alloy_transport::{utils::Spawnable, Transport, TransportError};
-use futures::{stream::StreamExt, FutureExt, Stream};
+use alloy_primitives::{Address, TxHash, B256, U64};
+use alloy_rpc_client::{RpcClientInner, WeakClient};
+use alloy_rpc_types_eth::{Block, BlockNumberOrTag, Transaction};
+use alloy_transport::{utils::Spawnable, Transport, TransportError, TransportResult};
+use futures::{
+    stream::{FuturesUnordered, StreamExt},
+    FutureExt, Stream,
+};
+use futures_utils_wasm::BoxFuture;
 use std::{
     collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fmt,
     future::Future,
+    sync::Arc,
     time::{Duration, Instant},
 };
 use tokio::{
@@ -167,6 +173,47 @@ impl<'a, T: Transport + Clone, N: Network> PendingTransactionBuilder<'a, T, N> {
         self
     }
 
+    /// Returns the sender and nonce of the transaction, if known.
+    pub const fn sender_nonce(&self) -> Option<(Address, u64)> {
+        self.config.sender_nonce()
+    }
+
+    /// Sets the sender and nonce of the transaction.
+    ///
+    /// See [`PendingTransactionConfig::set_sender_nonce`].
+    pub fn set_sender_nonce(&mut self, sender: Address, nonce: u64) {
+        self.config.set_sender_nonce(sender, nonce);
+    }
+
+    /// Sets the sender and nonce of the transaction.
+    ///
+    /// See [`PendingTransactionConfig::set_sender_nonce`].
+    pub const fn with_sender_nonce(mut self, sender: Address, nonce: u64) -> Self {
+        self.config.sender_nonce = Some((sender, nonce));
+        self
+    }
+
+    /// Returns the number of blocks searched for a replacement, if replacement detection is
+    /// enabled.
+    pub const fn replacement_detection(&self) -> Option<u64> {
+        self.config.replacement_detection()
+    }
+
+    /// Enables or disables replacement detection.
+    ///
+    /// See [`PendingTransactionConfig::set_replacement_detection`].
+    pub fn set_replacement_detection(&mut self, search_depth: Option<u64>) {
+        self.config.set_replacement_detection(search_depth);
+    }
+
+    /// Enables or disables replacement detection.
+    ///
+    /// See [`PendingTransactionConfig::set_replacement_detection`].
+    pub const fn with_replacement_detection(mut self, search_depth: Option<u64>) -> Self {
+        self.config.replacement_detection = search_depth;
+        self
+    }
+
     /// Registers the watching configuration with the provider.
     ///
     /// This does not wait for the transaction to be confirmed, but returns a [`PendingTransaction`]
@@ -252,12 +299,24 @@ pub struct PendingTransactionConfig {
 
     /// Optional timeout for the transaction.
     timeout: Option<Duration>,
+
+    /// The sender and nonce of the transaction, if known.
+    sender_nonce: Option<(Address, u64)>,
+
+    /// The number of blocks searched for a replacement, if replacement detection is enabled.
+    replacement_detection: Option<u64>,
 }
 
 impl PendingTransactionConfig {
     /// Create a new watch for a transaction.
     pub const fn new(tx_hash: TxHash) -> Self {
-        Self { tx_hash, required_confirmations: 1, timeout: None }
+        Self {
+            tx_hash,
+            required_confirmations: 1,
+            timeout: None,
+            sender_nonce: None,
+            replacement_detection: None,
+        }
     }
 
     /// Returns the transaction hash.
@@ -314,6 +373,55 @@ impl PendingTransactionConfig {
         self
     }
 
+    /// Returns the sender and nonce of the transaction, if known.
+    pub const fn sender_nonce(&self) -> Option<(Address, u64)> {
+        self.sender_nonce
+    }
+
+    /// Sets the sender and nonce of the transaction.
+    ///
+    /// These are used to detect whether the transaction was replaced or dropped, see
+    /// [`set_replacement_detection`](Self::set_replacement_detection). If unset, they are looked
+    /// up with `eth_getTransactionByHash` while the transaction is pending.
+    pub fn set_sender_nonce(&mut self, sender: Address, nonce: u64) {
+        self.sender_nonce = Some((sender, nonce));
+    }
+
+    /// Sets the sender and nonce of the transaction.
+    ///
+    /// See [`set_sender_nonce`](Self::set_sender_nonce).
+    pub const fn with_sender_nonce(mut self, sender: Address, nonce: u64) -> Self {
+        self.sender_nonce = Some((sender, nonce));
+        self
+    }
+
+    /// Returns the number of blocks searched for a replacement, if replacement detection is
+    /// enabled.
+    pub const fn replacement_detection(&self) -> Option<u64> {
+        self.replacement_detection
+    }
+
+    /// Enables replacement detection, searching the given number of most recent blocks for a
+    /// replacement, or disables it with `None`. Disabled by default.
+    ///
+    /// When enabled, the heartbeat compares the nonce of the sender with the nonce of the
+    /// transaction on every block. Once the nonce is used without the transaction being mined,
+    /// the transaction that used it is searched for in the most recent blocks, and the watch
+    /// fails with [`WatchTxError::Replaced`], or with [`WatchTxError::Dropped`] if it wasn't
+    /// found. This costs a few extra requests per block and sender while the transaction is
+    /// pending.
+    pub fn set_replacement_detection(&mut self, search_depth: Option<u64>) {
+        self.replacement_detection = search_depth;
+    }
+
+    /// Enables or disables replacement detection.
+    ///
+    /// See [`set_replacement_detection`](Self::set_replacement_detection).
+    pub const fn with_replacement_detection(mut self, search_depth: Option<u64>) -> Self {
+        self.replacement_detection = search_depth;
+        self
+    }
+
     /// Wraps this configuration with a provider to expose watching methods.
     pub const fn with_provider<T: Transport + Clone, N: Network>(
         self,
@@ -325,10 +433,23 @@ impl PendingTransactionConfig {
 
 /// Errors which may occur in heartbeat when watching a transaction.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum WatchTxError {
     /// Transaction was not confirmed after configured timeout.
     #[error("transaction was not confirmed within the timeout")]
     Timeout,
+
+    /// Transaction was replaced by another transaction with the same sender and nonce.
+    #[error("transaction was replaced by {by}")]
+    Replaced {
+        /// The hash of the transaction that was mined instead.
+        by: TxHash,
+    },
+
+    /// Transaction was dropped: its nonce was used without it being mined, and the transaction
+    /// that used it could not be found.
+    #[error("transaction was dropped")]
+    Dropped,
 }
 
 #[doc(alias = "TransactionWatcher")]
@@ -337,14 +458,111 @@ struct TxWatcher {
     /// The block at which the transaction was received. To be filled once known.
     /// Invariant: any confirmed transaction in `Heart` has this value set.
     received_at_block: Option<u64>,
-    tx: oneshot::Sender<Result<(), WatchTxError>>,
+    tx: oneshot::Sender<Result<Option<u64>, WatchTxError>>,
 }
 
 impl TxWatcher {
     /// Notify the waiter.
     fn notify(self, result: Result<(), WatchTxError>) {
         debug!(tx=%self.config.tx_hash, "notifying");
-        let _ = self.tx.send(result);
+        let _ = self.tx.send(result.map(|()| self.received_at_block));
+    }
+
+    /// Notify the waiter that the transaction was confirmed, having been included in the given
+    /// block.
+    fn notify_included(mut self, block_height: u64) {
+        self.received_at_block = Some(block_height);
+        self.notify(Ok(()));
+    }
+}
+
+/// What happened to a watched transaction whose nonce was used.
+#[derive(Debug)]
+enum Resolution {
+    /// The transaction was mined in the given block.
+    Mined(u64),
+    /// The transaction was replaced by the given transaction.
+    Replaced(TxHash),
+    /// The transaction that used the nonce could not be found.
+    Dropped,
+}
+
+/// The outcome of an RPC check made by the heartbeat to detect replaced transactions.
+///
+/// Each variant carries the key of the in-flight check, and a `None` value if the check failed.
+#[derive(Debug)]
+enum ReplacementCheck {
+    /// The sender and nonce of a watched transaction.
+    SenderNonce { tx_hash: TxHash, sender_nonce: Option<(Address, u64)> },
+    /// The nonce of a sender at the latest block.
+    AccountNonce { sender: Address, nonce: Option<u64> },
+    /// What happened to a watched transaction whose nonce was used.
+    Resolution { tx_hash: TxHash, resolution: Option<Resolution> },
+}
+
+impl ReplacementCheck {
+    /// Fetches the sender and nonce of a transaction.
+    async fn sender_nonce<T: Transport + Clone>(
+        client: Arc<RpcClientInner<T>>,
+        tx_hash: TxHash,
+    ) -> Self {
+        let tx: TransportResult<Option<Transaction>> =
+            client.request("eth_getTransactionByHash", (tx_hash,)).await;
+        let sender_nonce = tx.ok().flatten().map(|tx| (tx.from, tx.nonce));
+        Self::SenderNonce { tx_hash, sender_nonce }
+    }
+
+    /// Fetches the nonce of a sender at the latest block.
+    async fn account_nonce<T: Transport + Clone>(
+        client: Arc<RpcClientInner<T>>,
+        sender: Address,
+    ) -> Self {
+        let nonce: TransportResult<U64> =
+            client.request("eth_getTransactionCount", (sender, BlockNumberOrTag::Latest)).await;
+        Self::AccountNonce { sender, nonce: nonce.ok().map(|nonce| nonce.to()) }
+    }
+
+    /// Finds out what happened to a transaction whose nonce was used.
+    async fn resolution<T: Transport + Clone>(
+        client: Arc<RpcClientInner<T>>,
+        tx_hash: TxHash,
+        sender: Address,
+        nonce: u64,
+        search_depth: u64,
+    ) -> Self {
+        let resolution = Self::resolve(client, tx_hash, sender, nonce, search_depth).await.ok();
+        Self::Resolution { tx_hash, resolution }
+    }
+
+    async fn resolve<T: Transport + Clone>(
+        client: Arc<RpcClientInner<T>>,
+        tx_hash: TxHash,
+        sender: Address,
+        nonce: u64,
+        search_depth: u64,
+    ) -> TransportResult<Resolution> {
+        // The transaction may have been mined in a block we haven't seen yet.
+        let tx: Option<Transaction> =
+            client.request("eth_getTransactionByHash", (tx_hash,)).await?;
+        if let Some(block_number) = tx.and_then(|tx| tx.block_number) {
+            return Ok(Resolution::Mined(block_number));
+        }
+
+        // Otherwise, look for the transaction that used the nonce in the most recent blocks.
+        let latest: U64 = client.request_noparams("eth_blockNumber").await?;
+        let latest = latest.to::<u64>();
+        for number in ((latest + 1).saturating_sub(search_depth)..=latest).rev() {
+            let block: Option<Block> =
+                client.request("eth_getBlockByNumber", (U64::from(number), true)).await?;
+            let replacement = block.as_ref().and_then(|block| {
+                block.transactions.txns().find(|tx| tx.from == sender && tx.nonce == nonce)
+            });
+            if let Some(replacement) = replacement {
+                return Ok(Resolution::Replaced(replacement.hash));
+            }
+        }
+
+        Ok(Resolution::Dropped)
     }
 }
 
@@ -359,8 +577,9 @@ pub struct PendingTransaction {
     #[doc(alias = "transaction_hash")]
     pub(crate) tx_hash: TxHash,
     /// The receiver for the notification.
+    /// Carries the number of the block that included the transaction, if known.
     // TODO: send a receipt?
-    pub(crate) rx: oneshot::Receiver<Result<(), WatchTxError>>,
+    pub(crate) rx: oneshot::Receiver<Result<Option<u64>, WatchTxError>>,
 }
 
 impl fmt::Debug for PendingTransaction {
@@ -373,7 +592,7 @@ impl PendingTransaction {
     /// Creates a ready pending transaction.
     pub fn ready(tx_hash: TxHash) -> Self {
         let (tx, rx) = oneshot::channel();
-        tx.send(Ok(())).ok(); // Make sure that the receiver is notified already.
+        tx.send(Ok(None)).ok(); // Make sure that the receiver is notified already.
         Self { tx_hash, rx }
     }
 
@@ -382,6 +601,12 @@ impl PendingTransaction {
     pub const fn tx_hash(&self) -> &TxHash {
         &self.tx_hash
     }
+
+    /// Waits for the transaction to be confirmed, returning the number of the block that included
+    /// it, if known.
+    pub(crate) async fn included_at(self) -> Result<Option<u64>, PendingTransactionError> {
+        Ok(self.rx.await??)
+    }
 }
 
 impl Future for PendingTransaction {
@@ -422,7 +647,6 @@ impl HeartbeatHandle {
     }
 
     /// Returns a watcher that always sees the latest block.
-    #[allow(dead_code)]
     pub(crate) const fn latest(&self) -> &watch::Receiver<Option<Block>> {
         &self.latest
     }
@@ -430,10 +654,13 @@ impl HeartbeatHandle {
 
 // TODO: Parameterize with `Network`
 /// A heartbeat task that receives blocks and watches for transactions.
-pub(crate) struct Heartbeat<S> {
+pub(crate) struct Heartbeat<T, S> {
     /// The stream of incoming blocks to watch.
     stream: futures::stream::Fuse<S>,
 
+    /// The client used to detect replaced and dropped transactions.
+    client: WeakClient<T>,
+
     /// Lookbehind blocks in form of mapping block number -> vector of transaction hashes.
     past_blocks: VecDeque<(u64, HashSet<B256>)>,
 
@@ -445,22 +672,35 @@ pub(crate) struct Heartbeat<S> {
 
     /// Ordered map of transactions to reap at a certain time.
     reap_at: BTreeMap<Instant, B256>,
+
+    /// In-flight checks for replaced and dropped transactions.
+    checks: FuturesUnordered<BoxFuture<'static, ReplacementCheck>>,
+
+    /// Transactions with an in-flight check.
+    checking_txs: HashSet<TxHash>,
+
+    /// Senders with an in-flight nonce check.
+    checking_senders: HashSet<Address>,
 }
 
-impl<S: Stream<Item = Block> + Unpin + 'static> Heartbeat<S> {
+impl<T, S: Stream<Item = Block> + Unpin + 'static> Heartbeat<T, S> {
     /// Create a new heartbeat task.
-    pub(crate) fn new(stream: S) -> Self {
+    pub(crate) fn new(stream: S, client: WeakClient<T>) -> Self {
         Self {
             stream: stream.fuse(),
+            client,
             past_blocks: Default::default(),
             unconfirmed: Default::default(),
             waiting_confs: Default::default(),
             reap_at: Default::default(),
+            checks: Default::default(),
+            checking_txs: Default::default(),
+            checking_senders: Default::default(),
         }
     }
 }
 
-impl<S> Heartbeat<S> {
+impl<T: Transport + Clone, S> Heartbeat<T, S> {
     /// Check if any transactions have enough confirmations to notify.
     fn check_confirmations(&mut self, current_height: u64) {
         let to_keep = self.waiting_confs.split_off(&(current_height + 1));
@@ -515,7 +755,7 @@ impl<S> Heartbeat<S> {
 
     /// Handle a watch instruction by adding it to the watch list, and
     /// potentially adding it to our `reap_at` list.
-    fn handle_watch_ix(&mut self, to_watch: TxWatcher) {
+    fn handle_watch_ix(&mut self, mut to_watch: TxWatcher) {
         // Start watching for the transaction.
         debug!(tx=%to_watch.config.tx_hash, "watching");
         trace!(?to_watch.config, ?to_watch.received_at_block);
@@ -540,9 +780,10 @@ impl<S> Heartbeat<S> {
                 let current_height = self.past_blocks.back().map(|(h, _)| *h).unwrap();
 
                 if confirmed_at <= current_height {
-                    to_watch.notify(Ok(()));
+                    to_watch.notify_included(*block_height);
                 } else {
                     debug!(tx=%to_watch.config.tx_hash, %block_height, confirmations, "adding to waiting list");
+                    to_watch.received_at_block = Some(*block_height);
                     self.waiting_confs.entry(confirmed_at).or_default().push(to_watch);
                 }
                 return;
@@ -552,6 +793,82 @@ impl<S> Heartbeat<S> {
         self.unconfirmed.insert(to_watch.config.tx_hash, to_watch);
     }
 
+    /// Handle a watched transaction that was included in the given block.
+    fn handle_included(&mut self, mut watcher: TxWatcher, block_height: u64) {
+        // If `confirmations` is not more than 1 we can notify the watcher immediately.
+        let confirmations = watcher.config.required_confirmations;
+        if confirmations <= 1 {
+            watcher.notify_included(block_height);
+            return;
+        }
+        // Otherwise add it to the waiting list.
+
+        // Set the block at which the transaction was received.
+        if let Some(set_block) = watcher.received_at_block {
+            warn!(tx=%watcher.config.tx_hash, set_block=%set_block, new_block=%block_height, "received_at_block already set");
+            // We don't override the set value.
+        } else {
+            watcher.received_at_block = Some(block_height);
+        }
+        self.add_to_waiting_list(watcher, block_height);
+    }
+
+    /// Handle the outcome of a replacement check.
+    fn handle_check(&mut self, check: ReplacementCheck) {
+        match check {
+            ReplacementCheck::SenderNonce { tx_hash, sender_nonce } => {
+                self.checking_txs.remove(&tx_hash);
+                if let (Some(watcher), Some((sender, nonce))) =
+                    (self.unconfirmed.get_mut(&tx_hash), sender_nonce)
+                {
+                    watcher.config.set_sender_nonce(sender, nonce);
+                }
+            }
+            ReplacementCheck::AccountNonce { sender, nonce } => {
+                self.checking_senders.remove(&sender);
+                let Some(account_nonce) = nonce else { return };
+                let Some(client) = self.client.upgrade() else { return };
+
+                // Any transaction with a lower nonce is either mined, replaced or dropped.
+                for (tx_hash, watcher) in &self.unconfirmed {
+                    let Some((tx_sender, tx_nonce)) = watcher.config.sender_nonce else { continue };
+                    let Some(search_depth) = watcher.config.replacement_detection else {
+                        continue;
+                    };
+                    if tx_sender == sender
+                        && tx_nonce < account_nonce
+                        && self.checking_txs.insert(*tx_hash)
+                    {
+                        debug!(tx=%tx_hash, %sender, tx_nonce, account_nonce, "nonce used, resolving");
+                        self.checks.push(Box::pin(ReplacementCheck::resolution(
+                            client.clone(),
+                            *tx_hash,
+                            sender,
+                            tx_nonce,
+                            search_depth,
+                        )));
+                    }
+                }
+            }
+            ReplacementCheck::Resolution { tx_hash, resolution } => {
+                self.checking_txs.remove(&tx_hash);
+                let Some(resolution) = resolution else { return };
+                let Some(watcher) = self.unconfirmed.remove(&tx_hash) else { return };
+                match resolution {
+                    Resolution::Mined(block_height) => self.handle_included(watcher, block_height),
+                    Resolution::Replaced(by) => {
+                        debug!(tx=%tx_hash, %by, "replaced");
+                        watcher.notify(Err(WatchTxError::Replaced { by }));
+                    }
+                    Resolution::Dropped => {
+                        debug!(tx=%tx_hash, "dropped");
+                        watcher.notify(Err(WatchTxError::Dropped));
+                    }
+                }
+            }
+        }
+    }
+
     fn add_to_waiting_list(&mut self, watcher: TxWatcher, block_height: u64) {
         let confirmations = watcher.config.required_confirmations;
         debug!(tx=%watcher.config.tx_hash, %block_height, confirmations, "adding to waiting list");
@@ -587,29 +904,17 @@ impl<S> Heartbeat<S> {
         }
         self.past_blocks.push_back((*block_height, block.transactions.hashes().collect()));
 
+        // Stop watching transactions whose `PendingTransaction` has been dropped.
+        self.unconfirmed.retain(|_, watcher| !watcher.tx.is_closed());
+
         // Check if we are watching for any of the transactions in this block.
         let to_check: Vec<_> = block
             .transactions
             .hashes()
             .filter_map(|tx_hash| self.unconfirmed.remove(&tx_hash))
             .collect();
-        for mut watcher in to_check {
-            // If `confirmations` is not more than 1 we can notify the watcher immediately.
-            let confirmations = watcher.config.required_confirmations;
-            if confirmations <= 1 {
-                watcher.notify(Ok(()));
-                continue;
-            }
-            // Otherwise add it to the waiting list.
-
-            // Set the block at which the transaction was received.
-            if let Some(set_block) = watcher.received_at_block {
-                warn!(tx=%watcher.config.tx_hash, set_block=%set_block, new_block=%block_height, "received_at_block already set");
-                // We don't override the set value.
-            } else {
-                watcher.received_at_block = Some(*block_height);
-            }
-            self.add_to_waiting_list(watcher, *block_height);
+        for watcher in to_check {
+            self.handle_included(watcher, *block_height);
         }
 
         self.check_confirmations(*block_height);
@@ -620,10 +925,46 @@ impl<S> Heartbeat<S> {
         debug!(%block_height, "updating latest block");
         let _ = latest.send_replace(Some(block));
     }
+
+    /// Schedule checks to detect whether any of the unconfirmed transactions were replaced or
+    /// dropped.
+    ///
+    /// Only transactions with replacement detection enabled are checked. Those without a known
+    /// sender and nonce are looked up first. Then, once per sender, the account nonce is compared
+    /// with the nonces of its unconfirmed transactions.
+    fn schedule_replacement_checks(&mut self) {
+        let Some(client) = self.client.upgrade() else { return };
+
+        let mut senders = HashSet::new();
+        for (tx_hash, watcher) in &self.unconfirmed {
+            if watcher.config.replacement_detection.is_none() {
+                continue;
+            }
+            match watcher.config.sender_nonce {
+                Some((sender, _)) => {
+                    senders.insert(sender);
+                }
+                None => {
+                    if self.checking_txs.insert(*tx_hash) {
+                        self.checks.push(Box::pin(ReplacementCheck::sender_nonce(
+                            client.clone(),
+                            *tx_hash,
+                        )));
+                    }
+                }
+            }
+        }
+
+        for sender in senders {
+            if self.checking_senders.insert(sender) {
+                self.checks.push(Box::pin(ReplacementCheck::account_nonce(client.clone(), sender)));
+            }
+        }
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
-impl<S: Stream<Item = Block> + Unpin + 'static> Heartbeat<S> {
+impl<T: Transport + Clone, S: Stream<Item = Block> + Unpin + 'static> Heartbeat<T, S> {
     /// Spawn the heartbeat task, returning a [`HeartbeatHandle`].
     pub(crate) fn spawn(self) -> HeartbeatHandle {
         let (latest, latest_rx) = watch::channel(None::<Block>);
@@ -636,7 +977,7 @@ impl<S: Stream<Item = Block> + Unpin + 'static> Heartbeat<S> {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-impl<S: Stream<Item = Block> + Unpin + Send + 'static> Heartbeat<S> {
+impl<T: Transport + Clone, S: Stream<Item = Block> + Unpin + Send + 'static> Heartbeat<T, S> {
     /// Spawn the heartbeat task, returning a [`HeartbeatHandle`].
     pub(crate) fn spawn(self) -> HeartbeatHandle {
         let (latest, latest_rx) = watch::channel(None::<Block>);
@@ -648,7 +989,7 @@ impl<S: Stream<Item = Block> + Unpin + Send + 'static> Heartbeat<S> {
     }
 }
 
-impl<S: Stream<Item = Block> + Unpin + 'static> Heartbeat<S> {
+impl<T: Transport + Clone, S: Stream<Item = Block> + Unpin + 'static> Heartbeat<T, S> {
     async fn into_future(
         mut self,
         latest: watch::Sender<Option<Block>>,
@@ -673,6 +1014,12 @@ impl<S: Stream<Item = Block> + Unpin + 'static> Heartbeat<S> {
                     // Wake up to handle new blocks.
                     Some(block) = self.stream.next() => {
                         self.handle_new_block(block, &latest);
+                        self.schedule_replacement_checks();
+                    },
+
+                    // Handle the outcome of replacement checks.
+                    Some(check) = self.checks.next(), if !self.checks.is_empty() => {
+                        self.handle_check(check);
                     },
 
                     // This arm ensures we always wake up to reap timeouts,
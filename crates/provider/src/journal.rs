@@ -0,0 +1,250 @@
+//! Write-ahead journal of signed but unconfirmed transactions.
+//!
+//! The journal records every transaction broadcast through a [`JournalLayer`] before it reaches
+//! the node, so that in-flight transactions survive restarts. See [`JournalLayer::replay`] for how
+//! to resume them.
+//!
+//! [`JournalLayer`]: crate::layers::JournalLayer
+//! [`JournalLayer::replay`]: crate::layers::JournalLayer::replay
+
+use alloy_primitives::{Address, Bytes, TxHash};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, io, sync::Mutex};
+
+/// A signed transaction recorded in the journal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    /// The sender of the transaction.
+    pub sender: Address,
+    /// The nonce of the transaction.
+    pub nonce: u64,
+    /// The hash of the transaction.
+    pub tx_hash: TxHash,
+    /// The EIP-2718 encoded signed transaction.
+    pub raw: Bytes,
+}
+
+/// Storage backend of the transaction journal.
+///
+/// Implementations must persist every change before returning, as the journal is written ahead of
+/// broadcasting transactions. The methods are called from the async send path, so they block the
+/// executor thread for the duration of the write.
+pub trait JournalStorage: fmt::Debug + Send + Sync + 'static {
+    /// Records a transaction.
+    fn append(&self, entry: JournalEntry) -> io::Result<()>;
+
+    /// Removes the transaction with the given hash. Does nothing if it is not recorded.
+    fn remove(&self, tx_hash: TxHash) -> io::Result<()>;
+
+    /// Returns all recorded transactions, ordered by sender and nonce.
+    fn entries(&self) -> io::Result<Vec<JournalEntry>>;
+}
+
+/// Sorts entries by sender and nonce.
+fn sorted(entries: impl Iterator<Item = JournalEntry>) -> Vec<JournalEntry> {
+    let mut entries = entries.collect::<Vec<_>>();
+    entries.sort_by_key(|entry| (entry.sender, entry.nonce));
+    entries
+}
+
+/// An in-memory [`JournalStorage`], which does not survive restarts.
+#[derive(Debug, Default)]
+pub struct MemoryJournal {
+    entries: Mutex<BTreeMap<TxHash, JournalEntry>>,
+}
+
+impl JournalStorage for MemoryJournal {
+    fn append(&self, entry: JournalEntry) -> io::Result<()> {
+        self.entries.lock().unwrap().insert(entry.tx_hash, entry);
+        Ok(())
+    }
+
+    fn remove(&self, tx_hash: TxHash) -> io::Result<()> {
+        self.entries.lock().unwrap().remove(&tx_hash);
+        Ok(())
+    }
+
+    fn entries(&self) -> io::Result<Vec<JournalEntry>> {
+        Ok(sorted(self.entries.lock().unwrap().values().cloned()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use file::FileJournal;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file {
+    use super::*;
+    use std::{
+        fs::{self, File, OpenOptions},
+        io::{BufRead, BufReader, Write},
+        path::{Path, PathBuf},
+    };
+
+    /// A record of the journal file.
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    enum Record {
+        Append(JournalEntry),
+        Remove(TxHash),
+    }
+
+    #[derive(Debug)]
+    struct Inner {
+        file: File,
+        entries: BTreeMap<TxHash, JournalEntry>,
+    }
+
+    /// A [`JournalStorage`] backed by an append-only file of JSON records, one per line.
+    ///
+    /// The file is compacted when opened. A trailing record that was only partially written, e.g.
+    /// because of a crash, is ignored.
+    ///
+    /// Every change is flushed to disk with `fsync` before returning, which blocks the sending
+    /// task, usually for a few milliseconds, but possibly much longer on slow or busy disks.
+    /// Sending from a multi-threaded runtime keeps other tasks running in the meantime.
+    #[derive(Debug)]
+    pub struct FileJournal {
+        path: PathBuf,
+        inner: Mutex<Inner>,
+    }
+
+    impl FileJournal {
+        /// Opens the journal at the given path, creating it if it doesn't exist.
+        pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+            let path = path.as_ref().to_path_buf();
+            let entries = match File::open(&path) {
+                Ok(file) => Self::load(file)?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+                Err(err) => return Err(err),
+            };
+
+            // Compact the journal by rewriting the live entries, then swapping the files.
+            let tmp = path.with_extension("tmp");
+            let mut file = File::create(&tmp)?;
+            for entry in entries.values() {
+                Self::write(&mut file, &Record::Append(entry.clone()))?;
+            }
+            file.sync_all()?;
+            fs::rename(&tmp, &path)?;
+
+            let file = OpenOptions::new().append(true).open(&path)?;
+            Ok(Self { path, inner: Mutex::new(Inner { file, entries }) })
+        }
+
+        /// Returns the path of the journal file.
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        fn load(file: File) -> io::Result<BTreeMap<TxHash, JournalEntry>> {
+            let mut entries = BTreeMap::new();
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                match serde_json::from_str(&line) {
+                    Ok(Record::Append(entry)) => {
+                        entries.insert(entry.tx_hash, entry);
+                    }
+                    Ok(Record::Remove(tx_hash)) => {
+                        entries.remove(&tx_hash);
+                    }
+                    Err(err) => {
+                        warn!(%err, "skipping corrupted journal record");
+                    }
+                }
+            }
+            Ok(entries)
+        }
+
+        fn write(file: &mut File, record: &Record) -> io::Result<()> {
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            file.write_all(&line)
+        }
+
+        fn record(&self, record: Record) -> io::Result<()> {
+            let mut inner = self.inner.lock().unwrap();
+            Self::write(&mut inner.file, &record)?;
+            inner.file.sync_data()?;
+            match record {
+                Record::Append(entry) => {
+                    inner.entries.insert(entry.tx_hash, entry);
+                }
+                Record::Remove(tx_hash) => {
+                    inner.entries.remove(&tx_hash);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl JournalStorage for FileJournal {
+        fn append(&self, entry: JournalEntry) -> io::Result<()> {
+            self.record(Record::Append(entry))
+        }
+
+        fn remove(&self, tx_hash: TxHash) -> io::Result<()> {
+            if !self.inner.lock().unwrap().entries.contains_key(&tx_hash) {
+                return Ok(());
+            }
+            self.record(Record::Remove(tx_hash))
+        }
+
+        fn entries(&self) -> io::Result<Vec<JournalEntry>> {
+            Ok(sorted(self.inner.lock().unwrap().entries.values().cloned()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, bytes, B256};
+    use std::{fs::OpenOptions, io::Write};
+
+    fn entry(nonce: u64) -> JournalEntry {
+        JournalEntry {
+            sender: address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266"),
+            nonce,
+            tx_hash: B256::with_last_byte(nonce as u8),
+            raw: bytes!("02f8"),
+        }
+    }
+
+    #[test]
+    fn file_journal_survives_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+
+        let journal = FileJournal::open(&path).unwrap();
+        journal.append(entry(1)).unwrap();
+        journal.append(entry(0)).unwrap();
+        journal.append(entry(2)).unwrap();
+        journal.remove(entry(1).tx_hash).unwrap();
+        drop(journal);
+
+        // Simulate a record torn by a crash.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"append\":{\"sen").unwrap();
+        drop(file);
+
+        let journal = FileJournal::open(&path).unwrap();
+        assert_eq!(journal.entries().unwrap(), vec![entry(0), entry(2)]);
+
+        // The journal was compacted.
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(lines.lines().count(), 2);
+    }
+
+    #[test]
+    fn memory_journal_sorts_entries() {
+        let journal = MemoryJournal::default();
+        journal.append(entry(3)).unwrap();
+        journal.append(entry(1)).unwrap();
+        assert_eq!(journal.entries().unwrap(), vec![entry(1), entry(3)]);
+        journal.remove(entry(3).tx_hash).unwrap();
+        journal.remove(entry(3).tx_hash).unwrap();
+        assert_eq!(journal.entries().unwrap(), vec![entry(1)]);
+    }
+}
@@ -0,0 +1,504 @@
+use crate::{
+    provider::SendableTx, PendingTransactionBuilder, PendingTransactionConfig,
+    PendingTransactionError, Provider, ProviderLayer, RootProvider,
+};
+use alloy_json_rpc::RpcError;
+use alloy_network::{Ethereum, Network, TransactionBuilder};
+use alloy_primitives::{Address, TxHash};
+use alloy_transport::{Transport, TransportResult};
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::marker::PhantomData;
+
+/// The minimum fee bump, in percent, that geth-style mempools require to accept a replacement
+/// transaction with the same nonce.
+const MIN_REPLACEMENT_BUMP_PERCENT: u128 = 10;
+
+/// The number of most recent blocks searched for the version of a transaction that replaced
+/// another one.
+const REPLACEMENT_SEARCH_DEPTH: u64 = 10;
+
+/// How fees are raised each time a transaction is rebroadcast.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Escalation {
+    /// Add a fixed amount of wei to the fees on every rebroadcast.
+    Linear(u128),
+    /// Multiply the fees by the given coefficient on every rebroadcast.
+    Geometric(f64),
+}
+
+impl Escalation {
+    /// Returns the escalated value of `fee`.
+    ///
+    /// The result is never lower than the minimum replacement bump accepted by the mempool.
+    pub fn apply(&self, fee: u128) -> u128 {
+        let escalated = match *self {
+            Self::Linear(increment) => fee.saturating_add(increment),
+            Self::Geometric(coefficient) => (fee as f64 * coefficient) as u128,
+        };
+        escalated.max(min_replacement_fee(fee))
+    }
+}
+
+/// Returns the lowest fee that the mempool accepts to replace a transaction paying `fee`.
+const fn min_replacement_fee(fee: u128) -> u128 {
+    fee.saturating_mul(100 + MIN_REPLACEMENT_BUMP_PERCENT).div_ceil(100)
+}
+
+/// The policy used by the [`GasEscalatorProvider`] to rebroadcast transactions.
+///
+/// Every `interval` blocks without inclusion, the transaction is re-signed with the same nonce
+/// and fees raised according to the [`Escalation`], until either version of it is mined or the
+/// fees reach `max_fee_per_gas`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EscalationPolicy {
+    /// How fees are raised on each rebroadcast.
+    escalation: Escalation,
+    /// The number of blocks to wait between rebroadcasts.
+    interval: u64,
+    /// The maximum `max_fee_per_gas` (or `gas_price` for legacy transactions) to escalate to.
+    max_fee_per_gas: u128,
+}
+
+impl EscalationPolicy {
+    /// Creates a new policy that rebroadcasts every block, escalating up to the given cap.
+    pub const fn new(escalation: Escalation, max_fee_per_gas: u128) -> Self {
+        Self { escalation, interval: 1, max_fee_per_gas }
+    }
+
+    /// Creates a new policy that adds `increment` wei to the fees on every rebroadcast.
+    pub const fn linear(increment: u128, max_fee_per_gas: u128) -> Self {
+        Self::new(Escalation::Linear(increment), max_fee_per_gas)
+    }
+
+    /// Creates a new policy that multiplies the fees by `coefficient` on every rebroadcast.
+    pub const fn geometric(coefficient: f64, max_fee_per_gas: u128) -> Self {
+        Self::new(Escalation::Geometric(coefficient), max_fee_per_gas)
+    }
+
+    /// Returns the escalation applied to the fees.
+    pub const fn escalation(&self) -> Escalation {
+        self.escalation
+    }
+
+    /// Returns the number of blocks to wait between rebroadcasts.
+    pub const fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Sets the number of blocks to wait between rebroadcasts.
+    ///
+    /// An interval of `0` is treated as `1`.
+    pub const fn with_interval(mut self, blocks: u64) -> Self {
+        self.interval = if blocks == 0 { 1 } else { blocks };
+        self
+    }
+
+    /// Returns the fee cap.
+    pub const fn max_fee_per_gas(&self) -> u128 {
+        self.max_fee_per_gas
+    }
+
+    /// Sets the fee cap.
+    pub const fn with_max_fee_per_gas(mut self, max_fee_per_gas: u128) -> Self {
+        self.max_fee_per_gas = max_fee_per_gas;
+        self
+    }
+
+    /// Escalates `fee`, clamped to the cap.
+    ///
+    /// Returns `None` if the clamped fee is below the minimum replacement bump, as the mempool
+    /// would reject the replacement as underpriced.
+    fn escalate(&self, fee: u128) -> Option<u128> {
+        let escalated = self.escalation.apply(fee).min(self.max_fee_per_gas);
+        (escalated >= min_replacement_fee(fee)).then_some(escalated)
+    }
+}
+
+/// The fees of a transaction being escalated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EscalatedFees {
+    Legacy { gas_price: u128 },
+    Eip1559 { max_fee_per_gas: u128, max_priority_fee_per_gas: u128 },
+}
+
+impl EscalatedFees {
+    /// Returns the next fees according to the policy, or `None` if the cap was reached.
+    ///
+    /// Both the max fee and the priority fee of EIP-1559 transactions must be bumped for the
+    /// replacement to be accepted, so the cap is reached once either of them can no longer be.
+    fn escalate(self, policy: &EscalationPolicy) -> Option<Self> {
+        match self {
+            Self::Legacy { gas_price } => {
+                policy.escalate(gas_price).map(|gas_price| Self::Legacy { gas_price })
+            }
+            Self::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+                let max_fee_per_gas = policy.escalate(max_fee_per_gas)?;
+                let escalated =
+                    policy.escalation.apply(max_priority_fee_per_gas).min(max_fee_per_gas);
+                if escalated < min_replacement_fee(max_priority_fee_per_gas) {
+                    return None;
+                }
+                let max_priority_fee_per_gas = escalated;
+                Some(Self::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas })
+            }
+        }
+    }
+
+    fn apply<N: Network>(self, tx: &mut N::TransactionRequest) {
+        match self {
+            Self::Legacy { gas_price } => tx.set_gas_price(gas_price),
+            Self::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+                tx.set_max_fee_per_gas(max_fee_per_gas);
+                tx.set_max_priority_fee_per_gas(max_priority_fee_per_gas);
+            }
+        }
+    }
+}
+
+/// A layer that wraps a provider in a [`GasEscalatorProvider`].
+///
+/// Unlike most layers, the escalator needs to re-sign transactions, so it must wrap a provider
+/// stack that is able to sign them, i.e. it must be applied to the provider returned by the
+/// [`ProviderBuilder`](crate::ProviderBuilder) rather than added with
+/// [`ProviderBuilder::layer`](crate::ProviderBuilder::layer).
+///
+/// # Example
+///
+/// ```
+/// # use alloy_network::{NetworkWallet, EthereumWallet, Ethereum};
+/// # use alloy_rpc_types_eth::TransactionRequest;
+/// # use alloy_provider::{ProviderBuilder, ProviderLayer, layers::{EscalationPolicy, GasEscalatorLayer}};
+/// # async fn test<W: NetworkWallet<Ethereum> + Clone>(url: url::Url, wallet: W) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = ProviderBuilder::new()
+///     .with_recommended_fillers()
+///     .wallet(wallet)
+///     .on_http(url);
+///
+/// // Raise the fees by 12.5% every 2 blocks, up to 200 gwei.
+/// let policy = EscalationPolicy::geometric(1.125, 200_000_000_000).with_interval(2);
+/// let provider = GasEscalatorLayer::new(policy).layer(provider);
+///
+/// let tx_hash = provider.send_escalating(TransactionRequest::default()).await?.watch().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct GasEscalatorLayer {
+    policy: EscalationPolicy,
+}
+
+impl GasEscalatorLayer {
+    /// Creates a new layer with the given escalation policy.
+    pub const fn new(policy: EscalationPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// Returns the escalation policy.
+    pub const fn policy(&self) -> &EscalationPolicy {
+        &self.policy
+    }
+}
+
+impl From<EscalationPolicy> for GasEscalatorLayer {
+    fn from(policy: EscalationPolicy) -> Self {
+        Self::new(policy)
+    }
+}
+
+impl<P, T, N> ProviderLayer<P, T, N> for GasEscalatorLayer
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    type Provider = GasEscalatorProvider<P, T, N>;
+
+    fn layer(&self, inner: P) -> Self::Provider {
+        GasEscalatorProvider::new(inner, self.policy)
+    }
+}
+
+/// A provider that rebroadcasts transactions with increasing fees until they are included.
+///
+/// Transactions sent via [`Provider::send_transaction`] are forwarded to the inner provider as-is.
+/// Use [`GasEscalatorProvider::send_escalating`] to send a transaction with escalation.
+///
+/// See [`GasEscalatorLayer`] for how to construct this provider.
+#[derive(Clone, Debug)]
+pub struct GasEscalatorProvider<P, T, N = Ethereum> {
+    inner: P,
+    policy: EscalationPolicy,
+    _pd: PhantomData<fn() -> (T, N)>,
+}
+
+impl<P, T, N> GasEscalatorProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Creates a new `GasEscalatorProvider` with the given inner provider and policy.
+    pub const fn new(inner: P, policy: EscalationPolicy) -> Self {
+        Self { inner, policy, _pd: PhantomData }
+    }
+
+    /// Returns the escalation policy.
+    pub const fn policy(&self) -> &EscalationPolicy {
+        &self.policy
+    }
+
+    /// Returns a reference to the inner provider.
+    pub const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Broadcasts a transaction and rebroadcasts it with escalating fees until it is included.
+    ///
+    /// The nonce, gas limit and fees are filled in before the first broadcast if unset, so that
+    /// every version of the transaction shares the same nonce. The remaining properties are
+    /// filled and the transaction signed by the inner provider stack.
+    ///
+    /// Inclusion is observed through the provider's block heartbeat. Once any version of the
+    /// transaction is mined, all rebroadcasting stops and the returned
+    /// [`PendingTransactionBuilder`] points at the included transaction hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction has neither a nonce nor a sender, if filling or the
+    /// first broadcast fails, or if every broadcast version fails to be watched. Failed
+    /// rebroadcasts are logged and ignored, as they usually mean that an earlier version was
+    /// already mined.
+    pub async fn send_escalating(
+        &self,
+        mut tx: N::TransactionRequest,
+    ) -> Result<PendingTransactionBuilder<'_, T, N>, PendingTransactionError> {
+        let mut fees = self.prepare(&mut tx).await?;
+
+        // Subscribe to new blocks before broadcasting, so that we don't miss any.
+        let mut blocks = self.root().get_heart().latest().clone();
+        let mut last_broadcast = blocks.borrow_and_update().as_ref().map(|b| b.header.number);
+
+        let mut pending = FuturesUnordered::new();
+        let sender_nonce = tx.from().zip(tx.nonce());
+        let tx_hash = *self.inner.send_transaction(tx.clone()).await?.tx_hash();
+        pending.push(self.watch(tx_hash, sender_nonce));
+
+        let mut escalating = true;
+        loop {
+            tokio::select! {
+                biased;
+
+                Some(res) = pending.next() => match res {
+                    Ok(tx_hash) => {
+                        debug!(%tx_hash, "escalated transaction included");
+                        return Ok(PendingTransactionBuilder::new(self.root(), tx_hash));
+                    }
+                    Err(err) if pending.is_empty() => return Err(err),
+                    Err(err) => debug!(%err, "failed to watch escalated transaction"),
+                },
+
+                changed = blocks.changed(), if escalating => {
+                    if changed.is_err() {
+                        // The heartbeat has shut down, wait for what we have broadcast so far.
+                        escalating = false;
+                        continue;
+                    }
+
+                    let Some(block_number) = blocks.borrow_and_update().as_ref().map(|b| b.header.number) else {
+                        continue;
+                    };
+                    let last = *last_broadcast.get_or_insert(block_number);
+                    if block_number < last + self.policy.interval {
+                        continue;
+                    }
+
+                    let Some(next) = fees.escalate(&self.policy) else {
+                        debug!(?fees, "fee cap reached, no longer escalating");
+                        escalating = false;
+                        continue;
+                    };
+                    fees = next;
+                    fees.apply::<N>(&mut tx);
+                    last_broadcast = Some(block_number);
+
+                    match self.inner.send_transaction(tx.clone()).await {
+                        Ok(builder) => {
+                            debug!(tx_hash = %builder.tx_hash(), ?fees, "rebroadcast transaction");
+                            pending.push(self.watch(*builder.tx_hash(), sender_nonce));
+                        }
+                        Err(err) => debug!(%err, ?fees, "failed to rebroadcast transaction"),
+                    }
+                },
+
+                else => return Err(PendingTransactionError::FailedToRegister),
+            }
+        }
+    }
+
+    /// Fills the nonce, gas limit and fees on the request, returning the fees to escalate from.
+    async fn prepare(&self, tx: &mut N::TransactionRequest) -> TransportResult<EscalatedFees> {
+        if tx.nonce().is_none() {
+            let from = tx.from().ok_or_else(|| {
+                RpcError::local_usage_str("escalated transactions require `from` or `nonce`")
+            })?;
+            let nonce = self.inner.get_transaction_count(from).pending().await?;
+            tx.set_nonce(nonce);
+        }
+
+        if tx.gas_limit().is_none() {
+            let gas_limit = self.inner.estimate_gas(tx).await?;
+            tx.set_gas_limit(gas_limit);
+        }
+
+        let fees = if let Some(gas_price) = tx.gas_price() {
+            EscalatedFees::Legacy { gas_price }
+        } else if let (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) =
+            (tx.max_fee_per_gas(), tx.max_priority_fee_per_gas())
+        {
+            EscalatedFees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas }
+        } else {
+            match self.inner.estimate_eip1559_fees(None).await {
+                Ok(estimate) => EscalatedFees::Eip1559 {
+                    max_fee_per_gas: estimate.max_fee_per_gas,
+                    max_priority_fee_per_gas: estimate.max_priority_fee_per_gas,
+                },
+                Err(RpcError::UnsupportedFeature(_)) => {
+                    EscalatedFees::Legacy { gas_price: self.inner.get_gas_price().await? }
+                }
+                Err(err) => return Err(err),
+            }
+        };
+        fees.apply::<N>(tx);
+
+        Ok(fees)
+    }
+
+    /// Waits for the given version of the transaction to be included.
+    async fn watch(
+        &self,
+        tx_hash: TxHash,
+        sender_nonce: Option<(Address, u64)>,
+    ) -> Result<TxHash, PendingTransactionError> {
+        let mut config = PendingTransactionConfig::new(tx_hash)
+            .with_replacement_detection(Some(REPLACEMENT_SEARCH_DEPTH));
+        if let Some((sender, nonce)) = sender_nonce {
+            config.set_sender_nonce(sender, nonce);
+        }
+        self.inner.watch_pending_transaction(config).await?.await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl<P, T, N> Provider<T, N> for GasEscalatorProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    #[inline(always)]
+    fn root(&self) -> &RootProvider<T, N> {
+        self.inner.root()
+    }
+
+    async fn send_transaction_internal(
+        &self,
+        tx: SendableTx<N>,
+    ) -> TransportResult<PendingTransactionBuilder<'_, T, N>> {
+        self.inner.send_transaction_internal(tx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_escalation() {
+        let policy = EscalationPolicy::linear(100, 1_000);
+        assert_eq!(policy.escalate(100), Some(200));
+        // Never below the minimum replacement bump.
+        assert_eq!(policy.escalate(900), Some(1_000));
+        assert_eq!(policy.escalate(1_000), None);
+        // The cap is below the minimum replacement bump.
+        assert_eq!(policy.escalate(950), None);
+    }
+
+    #[test]
+    fn geometric_escalation() {
+        let policy = EscalationPolicy::geometric(1.5, 1_000);
+        assert_eq!(policy.escalate(100), Some(150));
+        assert_eq!(policy.escalate(800), Some(1_000));
+
+        // Coefficients below the minimum replacement bump are raised to it.
+        let policy = EscalationPolicy::geometric(1.01, 1_000);
+        assert_eq!(policy.escalate(100), Some(110));
+    }
+
+    #[test]
+    fn escalates_1559_fees() {
+        let policy = EscalationPolicy::geometric(2.0, 300);
+        let fees = EscalatedFees::Eip1559 { max_fee_per_gas: 100, max_priority_fee_per_gas: 10 };
+
+        let fees = fees.escalate(&policy).unwrap();
+        assert_eq!(
+            fees,
+            EscalatedFees::Eip1559 { max_fee_per_gas: 200, max_priority_fee_per_gas: 20 }
+        );
+
+        let fees = fees.escalate(&policy).unwrap();
+        assert_eq!(
+            fees,
+            EscalatedFees::Eip1559 { max_fee_per_gas: 300, max_priority_fee_per_gas: 40 }
+        );
+
+        assert_eq!(fees.escalate(&policy), None);
+    }
+
+    #[cfg(feature = "anvil-api")]
+    #[tokio::test]
+    async fn rebroadcasts_until_included() {
+        use crate::{ext::AnvilApi, ProviderBuilder, WalletProvider};
+        use alloy_primitives::{address, U256};
+        use alloy_rpc_types_eth::TransactionRequest;
+
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+        let provider =
+            GasEscalatorLayer::new(EscalationPolicy::geometric(1.125, u128::MAX)).layer(provider);
+        provider.anvil_set_auto_mine(false).await.unwrap();
+
+        let max_fee_per_gas = 2_000_000_000;
+        let tx = TransactionRequest {
+            from: Some(from),
+            value: Some(U256::from(100)),
+            to: Some(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045").into()),
+            max_fee_per_gas: Some(max_fee_per_gas),
+            max_priority_fee_per_gas: Some(1),
+            ..Default::default()
+        };
+
+        // Drop the first broadcast from the pool, as a node would ignore an underpriced
+        // transaction, so that only a replacement can be mined.
+        let miner = async {
+            while provider.get_transaction_count(from).pending().await.unwrap() == 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            provider.anvil_drop_all_transactions().await.unwrap();
+            while provider.get_transaction_count(from).await.unwrap() == 0 {
+                provider.anvil_mine(Some(U256::from(1)), None).await.unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+        };
+        let (builder, ()) = tokio::join!(provider.send_escalating(tx), miner);
+
+        let builder = builder.unwrap();
+        let tx_hash = *builder.tx_hash();
+        let receipt = builder.get_receipt().await.unwrap();
+        assert!(receipt.status());
+        assert_eq!(provider.get_transaction_count(from).await.unwrap(), 1);
+
+        let tx = provider.get_transaction_by_hash(tx_hash).await.unwrap().unwrap();
+        assert!(tx.max_fee_per_gas.unwrap() > max_fee_per_gas);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::{
+    fillers::ReservingNonceManager,
+    journal::{JournalEntry, JournalStorage},
+    provider::SendableTx,
+    utils::{is_known_tx_error, is_rejection},
+    PendingTransaction, PendingTransactionBuilder, PendingTransactionError, Provider,
+    ProviderLayer, RootProvider,
+};
+use alloy_eips::eip2718::{Decodable2718, Encodable2718};
+use alloy_network::{Ethereum, Network, TransactionBuilder};
+use alloy_primitives::{keccak256, Address, U64};
+use alloy_rpc_types_eth::BlockNumberOrTag;
+use alloy_transport::{utils::Spawnable, Transport, TransportErrorKind, TransportResult};
+use std::{collections::HashMap, io, marker::PhantomData, sync::Arc};
+
+/// The number of most recent blocks searched for the version of a rebroadcast transaction that
+/// replaced it.
+const REPLACEMENT_SEARCH_DEPTH: u64 = 10;
+
+fn storage_error(err: io::Error) -> alloy_transport::TransportError {
+    TransportErrorKind::custom(err)
+}
+
+/// Removes the entry from the journal once it is settled.
+///
+/// The transaction count of the sender is checked on every new block. Once it moves past the
+/// nonce of the entry, the transaction was either mined, replaced or dropped. This does not
+/// register a watcher with the heartbeat, which would replace any watcher the caller registers
+/// for the same transaction.
+fn remove_when_settled<T, N, S>(root: &RootProvider<T, N>, storage: Arc<S>, entry: JournalEntry)
+where
+    T: Transport + Clone,
+    N: Network,
+    S: JournalStorage,
+{
+    let mut latest = root.get_heart().latest().clone();
+    let client = root.weak_client();
+    async move {
+        while latest.changed().await.is_ok() {
+            let Some(client) = client.upgrade() else { return };
+            let count: TransportResult<U64> = client
+                .request("eth_getTransactionCount", (entry.sender, BlockNumberOrTag::Latest))
+                .await;
+            match count {
+                Ok(count) if entry.nonce < count.to::<u64>() => {
+                    if let Err(err) = storage.remove(entry.tx_hash) {
+                        warn!(tx_hash = %entry.tx_hash, %err, "failed to remove settled transaction from the journal");
+                    }
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    debug!(tx_hash = %entry.tx_hash, %err, "failed to fetch the transaction count");
+                }
+            }
+        }
+    }
+    .spawn_task();
+}
+
+/// A layer that records signed transactions in a write-ahead [`JournalStorage`] before
+/// broadcasting them.
+///
+/// Only transactions signed locally, e.g. by a [`WalletFiller`], are recorded, as the journal
+/// stores the signed transaction to rebroadcast it. The layer should therefore sit below the
+/// fillers, as is the case when added with [`ProviderBuilder::layer`].
+///
+/// Transactions are removed from the journal once they are mined, replaced or dropped, or if the
+/// node rejects them.
+/// After a restart, [`JournalLayer::replay`] resumes the recorded transactions.
+///
+/// [`WalletFiller`]: crate::fillers::WalletFiller
+/// [`ProviderBuilder::layer`]: crate::ProviderBuilder::layer
+///
+/// # Example
+///
+/// ```no_run
+/// # use alloy_network::EthereumWallet;
+/// # use alloy_provider::{fillers::ReservingNonceManager, journal::FileJournal, layers::JournalLayer, ProviderBuilder};
+/// # async fn test(url: url::Url, wallet: EthereumWallet) -> Result<(), Box<dyn std::error::Error>> {
+/// let nonce_manager = ReservingNonceManager::default();
+/// let journal = JournalLayer::new(FileJournal::open("journal.jsonl")?);
+/// let provider = ProviderBuilder::new()
+///     .with_nonce_management(nonce_manager.clone())
+///     .wallet(wallet)
+///     .layer(journal.clone())
+///     .on_http(url);
+///
+/// // Resume the transactions that were in flight before the restart.
+/// let replay = journal.replay(&provider).await?;
+/// replay.seed(&nonce_manager).await;
+/// for pending in replay.pending {
+///     tokio::spawn(pending);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct JournalLayer<S> {
+    storage: Arc<S>,
+}
+
+impl<S> JournalLayer<S> {
+    /// Creates a new `JournalLayer` recording transactions in the given storage.
+    pub fn new(storage: S) -> Self {
+        Self { storage: Arc::new(storage) }
+    }
+}
+
+impl<S: JournalStorage> JournalLayer<S> {
+    /// Returns the journal storage.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns `true` if the recorded transaction is settled, i.e. it was mined or its nonce was
+    /// used by another transaction. `counts` caches the transaction counts of the senders.
+    async fn is_settled<P, T, N>(
+        provider: &P,
+        entry: &JournalEntry,
+        counts: &mut HashMap<Address, u64>,
+    ) -> TransportResult<bool>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        if provider.get_transaction_receipt(entry.tx_hash).await?.is_some() {
+            return Ok(true);
+        }
+        let count = match counts.get(&entry.sender) {
+            Some(&count) => count,
+            None => {
+                let count = provider.get_transaction_count(entry.sender).await?;
+                *counts.entry(entry.sender).or_insert(count)
+            }
+        };
+        Ok(entry.nonce < count)
+    }
+
+    /// Removes the settled transactions from the journal, returning how many were removed.
+    ///
+    /// A transaction is settled once it was mined, or once its nonce was used by another
+    /// transaction.
+    pub async fn prune<P, T, N>(&self, provider: &P) -> TransportResult<usize>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        let mut counts = HashMap::new();
+        let mut pruned = 0;
+        for entry in self.storage.entries().map_err(storage_error)? {
+            if Self::is_settled(provider, &entry, &mut counts).await? {
+                self.storage.remove(entry.tx_hash).map_err(storage_error)?;
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Resumes the transactions recorded in the journal, typically after a restart.
+    ///
+    /// Settled transactions are removed from the journal. The others are rebroadcast and watched
+    /// again, and the next nonce of each sender is returned so that the nonce manager can be
+    /// seeded with [`JournalReplay::seed`].
+    pub async fn replay<P, T, N>(
+        &self,
+        provider: &P,
+    ) -> Result<JournalReplay, PendingTransactionError>
+    where
+        P: Provider<T, N>,
+        T: Transport + Clone,
+        N: Network,
+    {
+        let mut counts = HashMap::new();
+        let mut replay = JournalReplay::default();
+        for entry in self.storage.entries().map_err(storage_error)? {
+            if Self::is_settled(provider, &entry, &mut counts).await? {
+                self.storage.remove(entry.tx_hash).map_err(storage_error)?;
+                continue;
+            }
+
+            match provider.send_raw_transaction(&entry.raw).await {
+                Ok(_) => debug!(tx_hash = %entry.tx_hash, "rebroadcast journaled transaction"),
+                Err(err) if is_known_tx_error(&err) => {}
+                Err(err) => {
+                    debug!(tx_hash = %entry.tx_hash, %err, "failed to rebroadcast transaction");
+                }
+            }
+
+            let pending = PendingTransactionBuilder::new(provider.root(), entry.tx_hash)
+                .with_sender_nonce(entry.sender, entry.nonce)
+                .with_replacement_detection(Some(REPLACEMENT_SEARCH_DEPTH))
+                .register()
+                .await?;
+            replay.pending.push(pending);
+            remove_when_settled(provider.root(), Arc::clone(&self.storage), entry.clone());
+
+            let next = replay.next_nonces.entry(entry.sender).or_default();
+            *next = (*next).max(entry.nonce + 1);
+        }
+        Ok(replay)
+    }
+}
+
+impl<S> Clone for JournalLayer<S> {
+    fn clone(&self) -> Self {
+        Self { storage: Arc::clone(&self.storage) }
+    }
+}
+
+impl<P, T, N, S> ProviderLayer<P, T, N> for JournalLayer<S>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+    S: JournalStorage,
+{
+    type Provider = JournalProvider<P, T, S, N>;
+
+    fn layer(&self, inner: P) -> Self::Provider {
+        JournalProvider { inner, storage: Arc::clone(&self.storage), _pd: PhantomData }
+    }
+}
+
+/// The transactions resumed by [`JournalLayer::replay`].
+#[derive(Debug, Default)]
+pub struct JournalReplay {
+    /// The watchers of the rebroadcast transactions.
+    pub pending: Vec<PendingTransaction>,
+    /// The next nonce of each sender, following its rebroadcast transactions.
+    pub next_nonces: HashMap<Address, u64>,
+}
+
+impl JournalReplay {
+    /// Seeds the nonce manager with the next nonce of each sender.
+    pub async fn seed(&self, nonce_manager: &ReservingNonceManager) {
+        for (&sender, &nonce) in &self.next_nonces {
+            nonce_manager.seed(sender, nonce).await;
+        }
+    }
+}
+
+/// A provider that records signed transactions in a write-ahead journal.
+///
+/// See [`JournalLayer`] for more information.
+#[derive(Debug)]
+pub struct JournalProvider<P, T, S, N = Ethereum> {
+    inner: P,
+    storage: Arc<S>,
+    _pd: PhantomData<fn() -> (T, N)>,
+}
+
+impl<P: Clone, T, S, N> Clone for JournalProvider<P, T, S, N> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), storage: Arc::clone(&self.storage), _pd: PhantomData }
+    }
+}
+
+impl<P, T, N, S> JournalProvider<P, T, S, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+    S: JournalStorage,
+{
+    /// Returns a reference to the inner provider.
+    pub const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the journal storage.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Records the signed transaction, returning its journal entry.
+    fn record(&self, envelope: &N::TxEnvelope) -> TransportResult<Option<JournalEntry>> {
+        let raw = envelope.encoded_2718();
+        // The sender is recovered from the signature when converting into a request.
+        let request = <N::TransactionRequest as From<_>>::from(
+            N::TxEnvelope::decode_2718(&mut raw.as_slice()).map_err(TransportErrorKind::custom)?,
+        );
+        let (Some(sender), Some(nonce)) = (request.from(), request.nonce()) else {
+            warn!("unable to recover the sender of the transaction, not journaling it");
+            return Ok(None);
+        };
+
+        let entry = JournalEntry { sender, nonce, tx_hash: keccak256(&raw), raw: raw.into() };
+        self.storage.append(entry.clone()).map_err(storage_error)?;
+        Ok(Some(entry))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl<P, T, N, S> Provider<T, N> for JournalProvider<P, T, S, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+    S: JournalStorage,
+{
+    #[inline(always)]
+    fn root(&self) -> &RootProvider<T, N> {
+        self.inner.root()
+    }
+
+    async fn send_transaction_internal(
+        &self,
+        tx: SendableTx<N>,
+    ) -> TransportResult<PendingTransactionBuilder<'_, T, N>> {
+        let entry = match &tx {
+            SendableTx::Envelope(envelope) => self.record(envelope)?,
+            SendableTx::Builder(_) => None,
+        };
+
+        let res = self.inner.send_transaction_internal(tx).await;
+        if let Some(entry) = entry {
+            match &res {
+                Ok(_) => remove_when_settled(self.root(), Arc::clone(&self.storage), entry),
+                // Only definite rejections are removed, as the transaction may have reached the
+                // node otherwise.
+                Err(err) if is_rejection(err) => {
+                    self.storage.remove(entry.tx_hash).map_err(storage_error)?;
+                }
+                Err(_) => {}
+            }
+        }
+        res
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{journal::MemoryJournal, ProviderBuilder};
+    use alloy_primitives::{address, U256};
+    use alloy_rpc_types_eth::TransactionRequest;
+
+    #[cfg(feature = "anvil-api")]
+    #[tokio::test]
+    async fn journals_and_replays() {
+        use crate::{ext::AnvilApi, WalletProvider};
+
+        let journal = JournalLayer::new(MemoryJournal::default());
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .layer(journal.clone())
+            .on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+        provider.anvil_set_auto_mine(false).await.unwrap();
+
+        let tx = TransactionRequest::default()
+            .to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .value(U256::from(100));
+        let pending = provider.send_transaction(tx).await.unwrap();
+
+        let entries = journal.storage().entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].sender, entries[0].nonce), (from, 0));
+        assert_eq!(entries[0].tx_hash, *pending.tx_hash());
+
+        // The transaction is pending, so replaying rebroadcasts it.
+        let replay = journal.replay(&provider).await.unwrap();
+        assert_eq!(replay.pending.len(), 1);
+        assert_eq!(replay.next_nonces[&from], 1);
+
+        // Once mined, the transaction is removed from the journal.
+        provider.anvil_mine(Some(U256::from(1)), None).await.unwrap();
+        pending.get_receipt().await.unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while !journal.storage().entries().unwrap().is_empty() {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[cfg(feature = "anvil-api")]
+    #[tokio::test]
+    async fn removes_dropped_tx() {
+        use crate::{ext::AnvilApi, WalletProvider};
+
+        let journal = JournalLayer::new(MemoryJournal::default());
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .layer(journal.clone())
+            .on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+        provider.anvil_set_auto_mine(false).await.unwrap();
+
+        let tx = TransactionRequest::default()
+            .to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .value(U256::from(100));
+        let pending = provider.send_transaction(tx).await.unwrap();
+        assert_eq!(journal.storage().entries().unwrap().len(), 1);
+
+        // The nonce of the dropped transaction is used, so it can never be mined.
+        provider.anvil_drop_transaction(*pending.tx_hash()).await.unwrap();
+        provider.anvil_set_nonce(from, U256::from(1)).await.unwrap();
+        provider.anvil_mine(Some(U256::from(1)), None).await.unwrap();
+
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while !journal.storage().entries().unwrap().is_empty() {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn removes_rejected_tx() {
+        let journal = JournalLayer::new(MemoryJournal::default());
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .layer(journal.clone())
+            .on_anvil_with_wallet();
+
+        // The sender cannot afford the value.
+        let tx = TransactionRequest::default()
+            .to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .value(U256::MAX)
+            .gas_limit(21_000);
+        provider.send_transaction(tx).await.unwrap_err();
+        assert!(journal.storage().entries().unwrap().is_empty());
+    }
+}
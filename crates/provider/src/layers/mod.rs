@@ -1,6 +1,7 @@
 //! Useful layer implementations for the provider. Currently this
-//! module contains the `AnvilLayer`, `AnvilProvider` and `ChainLayer`
-//! types.
+//! module contains the `AnvilLayer`, `AnvilProvider`, `ChainLayer`,
+//! `GasEscalatorLayer`, `JournalLayer`, `NonceRecoveryLayer`,
+//! `SimulationLayer` and `VerifiedStateLayer` types.
 
 #[cfg(any(test, feature = "anvil-node"))]
 mod anvil;
@@ -9,3 +10,20 @@ pub use anvil::{AnvilLayer, AnvilProvider};
 
 mod chain;
 pub use chain::ChainLayer;
+
+mod gas_escalator;
+pub use gas_escalator::{Escalation, EscalationPolicy, GasEscalatorLayer, GasEscalatorProvider};
+
+mod journal;
+pub use journal::{JournalLayer, JournalProvider, JournalReplay};
+
+mod nonce_recovery;
+pub use nonce_recovery::{NonceRecoveryLayer, NonceRecoveryProvider};
+
+mod simulation;
+pub use simulation::{SimulationError, SimulationLayer, SimulationProvider};
+
+mod verified_state;
+pub use verified_state::{
+    TrustedHeaders, VerificationError, VerifiedStateLayer, VerifiedStateProvider,
+};
@@ -0,0 +1,239 @@
+use crate::{
+    fillers::ReservingNonceManager, provider::SendableTx, utils::is_rejection,
+    PendingTransactionBuilder, Provider, ProviderLayer, RootProvider,
+};
+use alloy_json_rpc::RpcError;
+use alloy_network::{Ethereum, Network, TransactionBuilder};
+use alloy_transport::{Transport, TransportError, TransportResult};
+use std::marker::PhantomData;
+
+/// The default number of times a transaction rejected with a nonce error is retried.
+const DEFAULT_MAX_RETRIES: usize = 2;
+
+/// Returns `true` if the node rejected a transaction because of its nonce.
+///
+/// The messages were checked against geth and the clients sharing its error messages (reth,
+/// Erigon and anvil), which report "nonce too low" and "nonce too high". Nonce errors of other
+/// clients are only detected if they contain "invalid nonce", and are otherwise returned without
+/// retrying.
+fn is_nonce_error(err: &TransportError) -> bool {
+    let RpcError::ErrorResp(payload) = err else { return false };
+    let message = payload.message.to_lowercase();
+    ["nonce too low", "nonce too high", "invalid nonce"].iter().any(|m| message.contains(m))
+}
+
+/// A layer that assigns nonces with a [`ReservingNonceManager`] and recovers from failed sends.
+///
+/// Nonces of transactions rejected by the node are released, and transactions rejected with a
+/// nonce error ("nonce too low/high") trigger a resync of the sender's nonce before being
+/// retried. Nonces of transactions that failed with an ambiguous error, such as a transport
+/// error, are kept, as the transaction may have been broadcast.
+///
+/// Because retrying requires signing the transaction again, this layer must wrap the full
+/// provider stack, including the fillers. It is therefore applied manually with
+/// [`ProviderLayer::layer`], rather than with [`ProviderBuilder::layer`].
+///
+/// [`ProviderBuilder::layer`]: crate::ProviderBuilder::layer
+///
+/// # Example
+///
+/// ```
+/// # use alloy_network::EthereumWallet;
+/// # use alloy_provider::{layers::NonceRecoveryLayer, ProviderBuilder, ProviderLayer};
+/// # async fn test(url: url::Url, wallet: EthereumWallet) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = ProviderBuilder::new()
+///     .with_recommended_fillers()
+///     .wallet(wallet)
+///     .on_http(url);
+/// let provider = NonceRecoveryLayer::default().layer(provider);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct NonceRecoveryLayer {
+    manager: ReservingNonceManager,
+    max_retries: usize,
+}
+
+impl Default for NonceRecoveryLayer {
+    fn default() -> Self {
+        Self::new(ReservingNonceManager::default())
+    }
+}
+
+impl NonceRecoveryLayer {
+    /// Creates a new `NonceRecoveryLayer` with the given nonce manager.
+    pub const fn new(manager: ReservingNonceManager) -> Self {
+        Self { manager, max_retries: DEFAULT_MAX_RETRIES }
+    }
+
+    /// Sets how many times a transaction rejected with a nonce error is retried.
+    pub const fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+}
+
+impl<P, T, N> ProviderLayer<P, T, N> for NonceRecoveryLayer
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    type Provider = NonceRecoveryProvider<P, T, N>;
+
+    fn layer(&self, inner: P) -> Self::Provider {
+        NonceRecoveryProvider {
+            inner,
+            manager: self.manager.clone(),
+            max_retries: self.max_retries,
+            _pd: PhantomData,
+        }
+    }
+}
+
+/// A provider that assigns nonces with a [`ReservingNonceManager`] and recovers from failed sends.
+///
+/// Transactions that already have a nonce, and signed transactions, are forwarded as-is. Other
+/// transactions must have a sender.
+///
+/// See [`NonceRecoveryLayer`] for more information.
+#[derive(Clone, Debug)]
+pub struct NonceRecoveryProvider<P, T, N = Ethereum> {
+    inner: P,
+    manager: ReservingNonceManager,
+    max_retries: usize,
+    _pd: PhantomData<fn() -> (T, N)>,
+}
+
+impl<P, T, N> NonceRecoveryProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Returns a reference to the inner provider.
+    pub const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the nonce manager.
+    pub const fn manager(&self) -> &ReservingNonceManager {
+        &self.manager
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl<P, T, N> Provider<T, N> for NonceRecoveryProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    #[inline(always)]
+    fn root(&self) -> &RootProvider<T, N> {
+        self.inner.root()
+    }
+
+    async fn send_transaction_internal(
+        &self,
+        tx: SendableTx<N>,
+    ) -> TransportResult<PendingTransactionBuilder<'_, T, N>> {
+        let mut tx = match tx {
+            SendableTx::Builder(tx) if tx.nonce().is_none() => tx,
+            tx => return self.inner.send_transaction_internal(tx).await,
+        };
+        let from = tx.from().ok_or_else(|| {
+            RpcError::local_usage_str("nonce recovery requires `from` or `nonce`")
+        })?;
+
+        let mut retries = 0;
+        loop {
+            let nonce = self.manager.reserve(&self.inner, from).await?;
+            tx.set_nonce(nonce);
+
+            match self.inner.send_transaction_internal(SendableTx::Builder(tx.clone())).await {
+                Ok(pending) => {
+                    self.manager.confirm(from, nonce).await;
+                    return Ok(pending);
+                }
+                Err(err) if !is_rejection(&err) => {
+                    self.manager.confirm(from, nonce).await;
+                    return Err(err);
+                }
+                Err(err) => {
+                    self.manager.release(from, nonce).await;
+                    if !is_nonce_error(&err) || retries >= self.max_retries {
+                        return Err(err);
+                    }
+                    debug!(%from, nonce, %err, "nonce rejected, resyncing");
+                    self.manager.reset(from).await;
+                    retries += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::is_known_tx_error;
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_transport::TransportErrorKind;
+
+    fn error_resp(message: &str) -> TransportError {
+        RpcError::ErrorResp(ErrorPayload { code: -32000, message: message.into(), data: None })
+    }
+
+    #[test]
+    fn detects_rejections() {
+        assert!(is_rejection(&error_resp("insufficient funds for gas * price + value")));
+        assert!(is_rejection(&RpcError::local_usage_str("missing signer")));
+        assert!(!is_rejection(&RpcError::Transport(TransportErrorKind::BackendGone)));
+        assert!(!is_rejection(&RpcError::NullResp));
+
+        // The nonce is held by a transaction in the mempool.
+        assert!(!is_rejection(&error_resp("already known")));
+        assert!(!is_rejection(&error_resp("replacement transaction underpriced")));
+        assert!(is_known_tx_error(&error_resp("ALREADY KNOWN")));
+        assert!(!is_known_tx_error(&error_resp("nonce too low")));
+        assert!(!is_known_tx_error(&RpcError::NullResp));
+    }
+
+    #[test]
+    fn detects_nonce_errors() {
+        assert!(is_nonce_error(&error_resp("nonce too low: next nonce 5, tx nonce 4")));
+        assert!(is_nonce_error(&error_resp("Nonce too high")));
+        assert!(!is_nonce_error(&error_resp("insufficient funds for gas * price + value")));
+        assert!(!is_nonce_error(&RpcError::local_usage_str("nonce too low")));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn resyncs_stale_nonce() {
+        use crate::{ProviderBuilder, WalletProvider};
+        use alloy_primitives::{address, U256};
+        use alloy_rpc_types_eth::TransactionRequest;
+
+        let provider = ProviderBuilder::new().with_recommended_fillers().on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+        let tx = TransactionRequest::default()
+            .from(from)
+            .to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .value(U256::from(100));
+
+        let manager = ReservingNonceManager::default();
+        // Reserve nonce 0, so that the next transaction is assigned a stale nonce.
+        assert_eq!(manager.reserve(&provider, from).await.unwrap(), 0);
+        manager.confirm(from, 0).await;
+        provider.send_transaction(tx.clone().nonce(0)).await.unwrap().watch().await.unwrap();
+        provider.send_transaction(tx.clone().nonce(1)).await.unwrap().watch().await.unwrap();
+
+        let provider = NonceRecoveryLayer::new(manager).layer(provider);
+        let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+        let mined = provider.get_transaction_by_hash(receipt.transaction_hash).await.unwrap();
+        assert_eq!(mined.unwrap().nonce, 2);
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    provider::SendableTx, PendingTransactionBuilder, Provider, ProviderLayer, RootProvider,
+};
+use alloy_eips::{
+    eip2718::{Decodable2718, Encodable2718},
+    BlockId,
+};
+use alloy_json_rpc::{ErrorPayload, RpcError};
+use alloy_network::{Ethereum, Network};
+use alloy_primitives::Bytes;
+use alloy_sol_types::SolInterface;
+use alloy_transport::{Transport, TransportError, TransportErrorKind, TransportResult};
+use std::{fmt, marker::PhantomData, sync::Arc};
+
+/// The JSON-RPC error code returned by nodes for calls that revert with data.
+const EXECUTION_REVERTED_CODE: i64 = 3;
+
+/// A predicate deciding whether a transaction should be broadcast without being simulated.
+type SkipFn<N> = Arc<dyn Fn(&<N as Network>::TransactionRequest) -> bool + Send + Sync>;
+
+/// The error returned when a transaction reverts during the pre-send simulation.
+///
+/// It is returned by [`Provider::send_transaction`] wrapped in a
+/// [`TransportErrorKind::Custom`]. Use [`SimulationError::from_transport_error`] to retrieve it.
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("transaction reverted during simulation: {payload}")]
+pub struct SimulationError {
+    payload: ErrorPayload,
+}
+
+impl SimulationError {
+    /// Returns the simulation error wrapped in `err`, if any.
+    pub fn from_transport_error(err: &TransportError) -> Option<&Self> {
+        match err {
+            RpcError::Transport(TransportErrorKind::Custom(err)) => err.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the error payload returned by the node.
+    pub const fn payload(&self) -> &ErrorPayload {
+        &self.payload
+    }
+
+    /// Returns the revert data, if the node returned any.
+    pub fn revert_data(&self) -> Option<Bytes> {
+        self.payload.as_revert_data()
+    }
+
+    /// Decodes the revert data into the given set of custom errors.
+    pub fn as_decoded_error<E: SolInterface>(&self, validate: bool) -> Option<E> {
+        self.payload.as_decoded_error(validate)
+    }
+}
+
+/// A layer that simulates transactions with `eth_call` against the `pending` block before
+/// broadcasting them, and refuses to broadcast the ones that revert.
+///
+/// The simulation runs on the fully filled transaction, so this layer should sit below the
+/// fillers, as is the case when added with [`ProviderBuilder::layer`]. Reverting transactions
+/// fail with a [`SimulationError`]. Other simulation errors are returned as-is.
+///
+/// A simulation is considered to revert if the node answers with the `3` error code, or with
+/// revert data. Reverts without data that nodes report with a generic error code are returned
+/// as other errors, and are not broadcast either.
+///
+/// Simulation can be skipped for individual transactions with [`SimulationLayer::skip_if`].
+///
+/// [`ProviderBuilder::layer`]: crate::ProviderBuilder::layer
+///
+/// # Example
+///
+/// ```
+/// # use alloy_network::{EthereumWallet, Ethereum};
+/// # use alloy_rpc_types_eth::TransactionRequest;
+/// # use alloy_provider::{layers::{SimulationError, SimulationLayer}, ProviderBuilder, Provider};
+/// # async fn test(url: url::Url, wallet: EthereumWallet) -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = ProviderBuilder::new()
+///     .with_recommended_fillers()
+///     .wallet(wallet)
+///     .layer(SimulationLayer::new().skip_if(|tx: &TransactionRequest| tx.input.input().is_none()))
+///     .on_http(url);
+///
+/// if let Err(err) = provider.send_transaction(TransactionRequest::default()).await {
+///     if let Some(revert) = SimulationError::from_transport_error(&err) {
+///         println!("refused to send reverting transaction: {:?}", revert.revert_data());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SimulationLayer<N: Network = Ethereum> {
+    skip: Option<SkipFn<N>>,
+}
+
+impl<N: Network> SimulationLayer<N> {
+    /// Creates a new `SimulationLayer` that simulates every transaction.
+    pub const fn new() -> Self {
+        Self { skip: None }
+    }
+
+    /// Skips the simulation of transactions for which `skip` returns `true`.
+    pub fn skip_if<F>(mut self, skip: F) -> Self
+    where
+        F: Fn(&N::TransactionRequest) -> bool + Send + Sync + 'static,
+    {
+        self.skip = Some(Arc::new(skip));
+        self
+    }
+}
+
+impl<N: Network> Default for SimulationLayer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Clone for SimulationLayer<N> {
+    fn clone(&self) -> Self {
+        Self { skip: self.skip.clone() }
+    }
+}
+
+impl<N: Network> fmt::Debug for SimulationLayer<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimulationLayer").field("skip", &self.skip.is_some()).finish()
+    }
+}
+
+impl<P, T, N> ProviderLayer<P, T, N> for SimulationLayer<N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    type Provider = SimulationProvider<P, T, N>;
+
+    fn layer(&self, inner: P) -> Self::Provider {
+        SimulationProvider { inner, skip: self.skip.clone(), _pd: PhantomData }
+    }
+}
+
+/// A provider that simulates transactions before broadcasting them.
+///
+/// See [`SimulationLayer`] for more information.
+pub struct SimulationProvider<P, T, N: Network = Ethereum> {
+    inner: P,
+    skip: Option<SkipFn<N>>,
+    _pd: PhantomData<fn() -> T>,
+}
+
+impl<P, T, N> SimulationProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Returns a reference to the inner provider.
+    pub const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Simulates the transaction against the pending block, failing with a [`SimulationError`] if
+    /// it reverts.
+    pub async fn simulate(&self, tx: &N::TransactionRequest) -> TransportResult<()> {
+        if self.skip.as_ref().is_some_and(|skip| skip(tx)) {
+            return Ok(());
+        }
+
+        match self.inner.call(tx).block(BlockId::pending()).await {
+            Ok(_) => Ok(()),
+            Err(RpcError::ErrorResp(payload)) if is_revert(&payload) => {
+                Err(TransportErrorKind::custom(SimulationError { payload }))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Returns `true` if the error payload of a call denotes a revert.
+fn is_revert(payload: &ErrorPayload) -> bool {
+    payload.code == EXECUTION_REVERTED_CODE || payload.as_revert_data().is_some()
+}
+
+impl<P: Clone, T, N: Network> Clone for SimulationProvider<P, T, N> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), skip: self.skip.clone(), _pd: PhantomData }
+    }
+}
+
+impl<P: fmt::Debug, T, N: Network> fmt::Debug for SimulationProvider<P, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimulationProvider")
+            .field("inner", &self.inner)
+            .field("skip", &self.skip.is_some())
+            .finish()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl<P, T, N> Provider<T, N> for SimulationProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    #[inline(always)]
+    fn root(&self) -> &RootProvider<T, N> {
+        self.inner.root()
+    }
+
+    async fn send_transaction_internal(
+        &self,
+        tx: SendableTx<N>,
+    ) -> TransportResult<PendingTransactionBuilder<'_, T, N>> {
+        match &tx {
+            SendableTx::Builder(request) => self.simulate(request).await?,
+            SendableTx::Envelope(envelope) => {
+                // The envelope is copied through its encoding, and the sender is recovered from
+                // the signature when converting it into a request.
+                let envelope = N::TxEnvelope::decode_2718(&mut envelope.encoded_2718().as_slice())
+                    .map_err(TransportErrorKind::custom)?;
+                self.simulate(&envelope.into()).await?
+            }
+        }
+        self.inner.send_transaction_internal(tx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProviderBuilder, WalletProvider};
+    use alloy_primitives::{address, bytes, U256};
+    use alloy_rpc_types_eth::TransactionRequest;
+
+    fn reverting_tx(from: alloy_primitives::Address) -> TransactionRequest {
+        // PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 REVERT
+        TransactionRequest {
+            from: Some(from),
+            input: bytes!("602a60005260206000fd").into(),
+            gas: Some(100_000),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn detects_reverts() {
+        let revert = |json: &str| is_revert(&serde_json::from_str(json).unwrap());
+        assert!(revert(r#"{"code":3,"message":"execution reverted","data":"0x2a"}"#));
+        assert!(revert(r#"{"code":-32015,"message":"VM Exception: revert","data":"0x2a"}"#));
+        assert!(!revert(r#"{"code":-32000,"message":"irrevertible nonce too low"}"#));
+    }
+
+    #[test]
+    fn downcasts_simulation_error() {
+        let payload: ErrorPayload =
+            serde_json::from_str(r#"{"code":3,"message":"execution reverted","data":"0x2a"}"#)
+                .unwrap();
+        let err = TransportErrorKind::custom(SimulationError { payload });
+
+        let simulation = SimulationError::from_transport_error(&err).unwrap();
+        assert_eq!(simulation.revert_data(), Some(bytes!("2a")));
+        assert!(
+            SimulationError::from_transport_error(&TransportErrorKind::custom_str("x")).is_none()
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn refuses_reverting_tx() {
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .layer(SimulationLayer::new())
+            .on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+
+        let err = provider.send_transaction(reverting_tx(from)).await.unwrap_err();
+        let simulation = SimulationError::from_transport_error(&err).unwrap();
+        assert_eq!(
+            simulation.revert_data().map(|data| U256::from_be_slice(&data)),
+            Some(U256::from(42))
+        );
+
+        // Nothing was broadcast.
+        assert_eq!(provider.get_transaction_count(from).pending().await.unwrap(), 0);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn skips_simulation() {
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .layer(SimulationLayer::new().skip_if(|tx: &TransactionRequest| tx.to.is_none()))
+            .on_anvil_with_wallet();
+        let from = provider.default_signer_address();
+
+        let receipt = provider
+            .send_transaction(reverting_tx(from))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        assert!(!receipt.status());
+
+        let transfer = TransactionRequest::default()
+            .to(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"))
+            .value(U256::from(100));
+        let receipt =
+            provider.send_transaction(transfer).await.unwrap().get_receipt().await.unwrap();
+        assert!(receipt.status());
+    }
+}
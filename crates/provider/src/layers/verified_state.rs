@@ -0,0 +1,345 @@
+use crate::{
+    proof::{verify_account_proof, ProofError},
+    provider::SendableTx,
+    PendingTransactionBuilder, Provider, ProviderLayer, RootProvider,
+};
+use alloy_consensus::{constants::KECCAK_EMPTY, Header};
+use alloy_network::{Ethereum, Network};
+use alloy_primitives::{keccak256, Address, BlockHash, BlockNumber, Bytes, B256, U256};
+use alloy_rpc_types_eth::{BlockId, BlockNumberOrTag, EIP1186AccountProofResponse};
+use alloy_transport::{Transport, TransportError, TransportResult};
+use std::{
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+    sync::{Arc, RwLock},
+};
+
+/// An error that occurred while reading verified state.
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    /// The requested block is not trusted.
+    #[error("block {0} is not trusted")]
+    UntrustedBlock(BlockId),
+    /// The node returned the proof of another account.
+    #[error("requested the proof of account {expected}, got {actual}")]
+    AddressMismatch {
+        /// The requested account.
+        expected: Address,
+        /// The account of the returned proof.
+        actual: Address,
+    },
+    /// The node did not return the proof of a requested storage slot.
+    #[error("missing the proof of storage slot {0}")]
+    MissingStorageProof(B256),
+    /// The returned code does not match the proven code hash.
+    #[error("code hash mismatch: expected {expected}, got {actual}")]
+    CodeHashMismatch {
+        /// The proven code hash.
+        expected: B256,
+        /// The hash of the returned code.
+        actual: B256,
+    },
+    /// The proof is invalid.
+    #[error(transparent)]
+    Proof(#[from] ProofError),
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+}
+
+/// A trusted header, identified by its hash.
+#[derive(Clone, Copy, Debug)]
+struct TrustedHeader {
+    hash: BlockHash,
+    state_root: B256,
+}
+
+#[derive(Debug, Default)]
+struct TrustedHeadersInner {
+    by_number: BTreeMap<BlockNumber, TrustedHeader>,
+    by_hash: HashMap<BlockHash, TrustedHeader>,
+}
+
+/// A set of trusted headers, against whose state roots state reads are verified.
+///
+/// Headers are trusted by the user, e.g. after checking them against a light client or a header
+/// chain anchored in a known checkpoint. The set is cheaply cloneable and shared between clones.
+#[derive(Clone, Debug, Default)]
+pub struct TrustedHeaders {
+    inner: Arc<RwLock<TrustedHeadersInner>>,
+}
+
+impl TrustedHeaders {
+    /// Trusts the given header.
+    pub fn trust(&self, header: &Header) {
+        let trusted = TrustedHeader { hash: header.hash_slow(), state_root: header.state_root };
+        let mut inner = self.inner.write().unwrap();
+        if let Some(replaced) = inner.by_number.insert(header.number, trusted) {
+            inner.by_hash.remove(&replaced.hash);
+        }
+        inner.by_hash.insert(trusted.hash, trusted);
+    }
+
+    /// Returns the number of the latest trusted header, if any.
+    pub fn latest(&self) -> Option<BlockNumber> {
+        self.inner.read().unwrap().by_number.last_key_value().map(|(number, _)| *number)
+    }
+
+    /// Resolves the block to a trusted header. The `latest` tag resolves to the latest trusted
+    /// header.
+    fn resolve(&self, block: BlockId) -> Result<TrustedHeader, VerificationError> {
+        let inner = self.inner.read().unwrap();
+        let trusted = match block {
+            BlockId::Hash(hash) => inner.by_hash.get(&hash.block_hash),
+            BlockId::Number(BlockNumberOrTag::Number(number)) => inner.by_number.get(&number),
+            BlockId::Number(BlockNumberOrTag::Latest) => {
+                inner.by_number.last_key_value().map(|(_, trusted)| trusted)
+            }
+            BlockId::Number(_) => None,
+        };
+        trusted.copied().ok_or(VerificationError::UntrustedBlock(block))
+    }
+}
+
+/// A layer that verifies state reads with `eth_getProof` Merkle-Patricia proofs, against the state
+/// roots of [`TrustedHeaders`].
+///
+/// This reduces the trust placed in the RPC node: responses that do not match the proofs, or
+/// proofs that do not match the trusted state roots, are rejected with a [`VerificationError`].
+///
+/// The verified reads are exposed as inherent methods of the [`VerifiedStateProvider`], such as
+/// [`VerifiedStateProvider::get_balance_verified`]. The layer is therefore applied manually with
+/// [`ProviderLayer::layer`], so that the provider is the outermost one.
+///
+/// # Example
+///
+/// ```no_run
+/// # use alloy_consensus::Header;
+/// # use alloy_primitives::address;
+/// # use alloy_provider::{layers::{TrustedHeaders, VerifiedStateLayer}, ProviderBuilder, ProviderLayer};
+/// # use alloy_rpc_types_eth::BlockId;
+/// # async fn test(url: url::Url, checkpoint: Header) -> Result<(), Box<dyn std::error::Error>> {
+/// let headers = TrustedHeaders::default();
+/// headers.trust(&checkpoint);
+///
+/// let provider = VerifiedStateLayer::new(headers).layer(ProviderBuilder::new().on_http(url));
+/// let vitalik = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+/// let balance = provider.get_balance_verified(vitalik, BlockId::latest()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct VerifiedStateLayer {
+    headers: TrustedHeaders,
+}
+
+impl VerifiedStateLayer {
+    /// Creates a new `VerifiedStateLayer` verifying reads against the given headers.
+    pub const fn new(headers: TrustedHeaders) -> Self {
+        Self { headers }
+    }
+}
+
+impl<P, T, N> ProviderLayer<P, T, N> for VerifiedStateLayer
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    type Provider = VerifiedStateProvider<P, T, N>;
+
+    fn layer(&self, inner: P) -> Self::Provider {
+        VerifiedStateProvider { inner, headers: self.headers.clone(), _pd: PhantomData }
+    }
+}
+
+/// A provider that verifies state reads with `eth_getProof` Merkle-Patricia proofs.
+///
+/// See [`VerifiedStateLayer`] for more information.
+#[derive(Clone, Debug)]
+pub struct VerifiedStateProvider<P, T, N = Ethereum> {
+    inner: P,
+    headers: TrustedHeaders,
+    _pd: PhantomData<fn() -> (T, N)>,
+}
+
+impl<P, T, N> VerifiedStateProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    /// Returns a reference to the inner provider.
+    pub const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the trusted headers.
+    pub const fn trusted_headers(&self) -> &TrustedHeaders {
+        &self.headers
+    }
+
+    /// Fetches the account and storage proofs at the given trusted block, and verifies them
+    /// against its state root.
+    pub async fn get_proof_verified(
+        &self,
+        address: Address,
+        keys: Vec<B256>,
+        block: BlockId,
+    ) -> Result<EIP1186AccountProofResponse, VerificationError> {
+        let trusted = self.headers.resolve(block)?;
+        self.get_proof_at(address, keys, &trusted).await
+    }
+
+    /// Fetches the account and storage proofs at the trusted block, and verifies them against its
+    /// state root.
+    async fn get_proof_at(
+        &self,
+        address: Address,
+        keys: Vec<B256>,
+        trusted: &TrustedHeader,
+    ) -> Result<EIP1186AccountProofResponse, VerificationError> {
+        let proof = self
+            .inner
+            .get_proof(address, keys.clone())
+            .block_id(BlockId::hash(trusted.hash))
+            .await?;
+
+        if proof.address != address {
+            return Err(VerificationError::AddressMismatch {
+                expected: address,
+                actual: proof.address,
+            });
+        }
+        if let Some(missing) =
+            keys.iter().find(|key| !proof.storage_proof.iter().any(|p| p.key.0 == **key))
+        {
+            return Err(VerificationError::MissingStorageProof(*missing));
+        }
+        verify_account_proof(trusted.state_root, &proof)?;
+        Ok(proof)
+    }
+
+    /// Returns the verified balance of the account at the given trusted block.
+    pub async fn get_balance_verified(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Result<U256, VerificationError> {
+        Ok(self.get_proof_verified(address, vec![], block).await?.balance)
+    }
+
+    /// Returns the verified nonce of the account at the given trusted block.
+    pub async fn get_transaction_count_verified(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Result<u64, VerificationError> {
+        Ok(self.get_proof_verified(address, vec![], block).await?.nonce)
+    }
+
+    /// Returns the verified value of the storage slot at the given trusted block.
+    pub async fn get_storage_at_verified(
+        &self,
+        address: Address,
+        key: U256,
+        block: BlockId,
+    ) -> Result<U256, VerificationError> {
+        let key = B256::from(key);
+        let proof = self.get_proof_verified(address, vec![key], block).await?;
+        let storage_proof = proof.storage_proof.iter().find(|p| p.key.0 == key);
+        Ok(storage_proof.map(|p| p.value).unwrap_or_default())
+    }
+
+    /// Returns the verified code of the account at the given trusted block.
+    ///
+    /// The code returned by the node is checked against the proven code hash.
+    pub async fn get_code_at_verified(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Result<Bytes, VerificationError> {
+        let trusted = self.headers.resolve(block)?;
+        let proof = self.get_proof_at(address, vec![], &trusted).await?;
+        let code = self.inner.get_code_at(address).block_id(BlockId::hash(trusted.hash)).await?;
+
+        let expected = if proof.code_hash.is_zero() { KECCAK_EMPTY } else { proof.code_hash };
+        let actual = keccak256(&code);
+        if actual != expected {
+            return Err(VerificationError::CodeHashMismatch { expected, actual });
+        }
+        Ok(code)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl<P, T, N> Provider<T, N> for VerifiedStateProvider<P, T, N>
+where
+    P: Provider<T, N>,
+    T: Transport + Clone,
+    N: Network,
+{
+    #[inline(always)]
+    fn root(&self) -> &RootProvider<T, N> {
+        self.inner.root()
+    }
+
+    async fn send_transaction_internal(
+        &self,
+        tx: SendableTx<N>,
+    ) -> TransportResult<PendingTransactionBuilder<'_, T, N>> {
+        self.inner.send_transaction_internal(tx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_trusted_headers() {
+        let headers = TrustedHeaders::default();
+        assert!(matches!(
+            headers.resolve(BlockId::latest()),
+            Err(VerificationError::UntrustedBlock(_))
+        ));
+
+        let header = Header { number: 5, state_root: B256::repeat_byte(5), ..Default::default() };
+        headers.trust(&header);
+        headers.trust(&Header { number: 3, ..Default::default() });
+        assert_eq!(headers.latest(), Some(5));
+
+        let trusted = headers.resolve(BlockId::latest()).unwrap();
+        assert_eq!((trusted.hash, trusted.state_root), (header.hash_slow(), header.state_root));
+        assert_eq!(headers.resolve(BlockId::hash(header.hash_slow())).unwrap().hash, trusted.hash);
+        assert!(headers.resolve(BlockId::number(4)).is_err());
+        assert!(headers.resolve(BlockId::pending()).is_err());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn verifies_state_reads() {
+        use crate::ProviderBuilder;
+        use alloy_network::BlockResponse;
+        use alloy_rpc_types_eth::BlockTransactionsKind;
+
+        let provider = ProviderBuilder::new().on_anvil_with_wallet();
+        let block =
+            provider.get_block(BlockId::latest(), BlockTransactionsKind::Hashes).await.unwrap();
+        let header = block.unwrap().header().clone();
+
+        let headers = TrustedHeaders::default();
+        headers.trust(&Header::try_from(header).unwrap());
+        let provider = VerifiedStateLayer::new(headers).layer(provider);
+
+        let funded = provider.get_accounts().await.unwrap()[0];
+        let balance = provider.get_balance_verified(funded, BlockId::latest()).await.unwrap();
+        assert_eq!(balance, provider.get_balance(funded).await.unwrap());
+        assert!(provider.get_code_at_verified(funded, BlockId::latest()).await.unwrap().is_empty());
+
+        // Blocks that are not trusted are refused.
+        let err = provider.get_balance_verified(funded, BlockId::number(100)).await.unwrap_err();
+        assert!(matches!(err, VerificationError::UntrustedBlock(_)));
+    }
+}
@@ -25,6 +25,14 @@ pub type HyperProvider<N = alloy_network::Ethereum> =
 #[macro_use]
 extern crate tracing;
 
+mod batch;
+pub use batch::PendingBatch;
+
+#[cfg(feature = "blocking")]
+mod blocking;
+#[cfg(feature = "blocking")]
+pub use blocking::{BlockingIter, BlockingProvider};
+
 mod builder;
 pub use builder::{Identity, ProviderBuilder, ProviderLayer, Stack};
 
@@ -35,6 +43,14 @@ pub mod layers;
 
 mod chain;
 
+mod dispatcher;
+pub use dispatcher::{DispatchOutcome, Dispatcher, DispatcherError};
+
+pub mod journal;
+
+mod header_chain;
+pub use header_chain::{HeaderChainError, HeaderChainVerifier};
+
 mod heart;
 pub use heart::{
     PendingTransaction, PendingTransactionBuilder, PendingTransactionConfig,
@@ -47,6 +63,10 @@ pub use provider::{
     WalletProvider,
 };
 
+pub mod proof;
+
+pub mod registry;
+
 pub mod utils;
 
 #[doc(no_inline)]
@@ -0,0 +1,332 @@
+//! Verification of [EIP-1186] Merkle-Patricia proofs, as returned by `eth_getProof`.
+//!
+//! [EIP-1186]: https://eips.ethereum.org/EIPS/eip-1186
+
+use alloy_consensus::{constants::KECCAK_EMPTY, Account, EMPTY_ROOT_HASH};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_rlp::{Decodable, Header};
+use alloy_rpc_types_eth::{EIP1186AccountProofResponse, EIP1186StorageProof};
+
+/// An error that occurred while verifying a Merkle-Patricia proof.
+#[derive(Debug, thiserror::Error)]
+pub enum ProofError {
+    /// A proof node does not hash to the hash referencing it.
+    #[error("proof node hash mismatch: expected {expected}, got {actual}")]
+    HashMismatch {
+        /// The hash referencing the node.
+        expected: B256,
+        /// The hash of the node.
+        actual: B256,
+    },
+    /// The proof ends before reaching the key.
+    #[error("proof is incomplete")]
+    Incomplete,
+    /// A proof node is not a valid trie node.
+    #[error("invalid proof node")]
+    InvalidNode,
+    /// The proven account differs from the returned one.
+    #[error("proof does not match account {0}")]
+    AccountMismatch(Address),
+    /// The proven storage value differs from the returned one.
+    #[error("proof does not match storage slot {0}")]
+    StorageMismatch(B256),
+    /// A proof node or value could not be decoded.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+}
+
+/// A reference to a child node.
+enum NodeRef<'a> {
+    /// A node referenced by its hash, found in the proof.
+    Hash(B256),
+    /// A node shorter than 32 bytes, embedded in its parent.
+    Inline(&'a [u8]),
+}
+
+/// Splits an RLP-encoded list into its RLP-encoded items.
+fn decode_list(node: &[u8]) -> Result<Vec<&[u8]>, ProofError> {
+    let mut buf = node;
+    let header = Header::decode(&mut buf)?;
+    if !header.list || header.payload_length != buf.len() {
+        return Err(ProofError::InvalidNode);
+    }
+
+    let mut items = Vec::with_capacity(17);
+    while !buf.is_empty() {
+        let mut rest = buf;
+        let header = Header::decode(&mut rest)?;
+        let len = buf.len() - rest.len() + header.payload_length;
+        items.push(&buf[..len]);
+        buf = &buf[len..];
+    }
+    Ok(items)
+}
+
+/// Decodes a child reference of a branch or extension node. Returns `None` for an empty child.
+fn decode_child(item: &[u8]) -> Result<Option<NodeRef<'_>>, ProofError> {
+    let mut buf = item;
+    let header = Header::decode(&mut buf)?;
+    if header.list {
+        return Ok(Some(NodeRef::Inline(item)));
+    }
+    match header.payload_length {
+        0 => Ok(None),
+        32 => Ok(Some(NodeRef::Hash(B256::from_slice(&buf[..32])))),
+        _ => Err(ProofError::InvalidNode),
+    }
+}
+
+/// Decodes the value of a leaf or branch node. Returns `None` for an empty value.
+fn decode_value(item: &[u8]) -> Result<Option<Bytes>, ProofError> {
+    let value = Header::decode_bytes(&mut &item[..], false)?;
+    Ok((!value.is_empty()).then(|| Bytes::copy_from_slice(value)))
+}
+
+/// Decodes the hex-prefix encoded path of a leaf or extension node, returning its nibbles and
+/// whether the node is a leaf.
+fn decode_path(item: &[u8]) -> Result<(Vec<u8>, bool), ProofError> {
+    let encoded = Header::decode_bytes(&mut &item[..], false)?;
+    let (&first, rest) = encoded.split_first().ok_or(ProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::InvalidNode);
+    }
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+    Ok((nibbles, flag & 2 == 2))
+}
+
+/// Verifies a Merkle-Patricia proof of the given key against the trie root.
+///
+/// The key is the path in the trie, i.e. the hash of the address or storage slot in the state and
+/// storage tries. Returns the value stored at the key, or `None` if the proof shows that the key is
+/// absent.
+pub fn verify_proof(root: B256, key: B256, proof: &[Bytes]) -> Result<Option<Bytes>, ProofError> {
+    // An empty trie has no nodes.
+    if root == EMPTY_ROOT_HASH {
+        return Ok(None);
+    }
+
+    let nibbles = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect::<Vec<_>>();
+    let mut path = &nibbles[..];
+    let mut proof = proof.iter();
+    let mut next = NodeRef::Hash(root);
+    loop {
+        let node = match next {
+            NodeRef::Hash(expected) => {
+                let node = proof.next().ok_or(ProofError::Incomplete)?;
+                let actual = keccak256(node);
+                if actual != expected {
+                    return Err(ProofError::HashMismatch { expected, actual });
+                }
+                &node[..]
+            }
+            NodeRef::Inline(node) => node,
+        };
+
+        let items = decode_list(node)?;
+        match items.len() {
+            // Branch node.
+            17 => {
+                let Some((&nibble, rest)) = path.split_first() else {
+                    return decode_value(items[16]);
+                };
+                match decode_child(items[nibble as usize])? {
+                    Some(child) => next = child,
+                    None => return Ok(None),
+                }
+                path = rest;
+            }
+            // Leaf or extension node.
+            2 => {
+                let (node_path, is_leaf) = decode_path(items[0])?;
+                if is_leaf {
+                    return if node_path == path { decode_value(items[1]) } else { Ok(None) };
+                }
+                let Some(rest) = path.strip_prefix(&node_path[..]) else { return Ok(None) };
+                next = decode_child(items[1])?.ok_or(ProofError::InvalidNode)?;
+                path = rest;
+            }
+            _ => return Err(ProofError::InvalidNode),
+        }
+    }
+}
+
+/// Verifies the account proof against the state root, and the storage proofs against the proven
+/// storage root.
+pub fn verify_account_proof(
+    state_root: B256,
+    proof: &EIP1186AccountProofResponse,
+) -> Result<(), ProofError> {
+    let account = Account {
+        nonce: proof.nonce,
+        balance: proof.balance,
+        storage_root: proof.storage_hash,
+        code_hash: proof.code_hash,
+    };
+    let matches = match verify_proof(state_root, keccak256(proof.address), &proof.account_proof)? {
+        Some(value) => Account::decode(&mut &value[..])? == account,
+        // Nodes return either zero or empty hashes for absent accounts.
+        None => {
+            account.nonce == 0
+                && account.balance.is_zero()
+                && (account.storage_root == EMPTY_ROOT_HASH || account.storage_root.is_zero())
+                && (account.code_hash == KECCAK_EMPTY || account.code_hash.is_zero())
+        }
+    };
+    if !matches {
+        return Err(ProofError::AccountMismatch(proof.address));
+    }
+
+    for storage_proof in &proof.storage_proof {
+        verify_storage_proof(proof.storage_hash, storage_proof)?;
+    }
+    Ok(())
+}
+
+/// Verifies the storage proof against the storage root of the account.
+pub fn verify_storage_proof(
+    storage_root: B256,
+    proof: &EIP1186StorageProof,
+) -> Result<(), ProofError> {
+    let slot = proof.key.0;
+    let value = match verify_proof(storage_root, keccak256(slot), &proof.proof)? {
+        Some(value) => U256::decode(&mut &value[..])?,
+        None => U256::ZERO,
+    };
+    if value != proof.value {
+        return Err(ProofError::StorageMismatch(slot));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+    use alloy_rlp::Encodable;
+
+    /// Encodes RLP-encoded items as an RLP list.
+    fn list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload_length = items.iter().map(Vec::len).sum();
+        let mut out = Vec::new();
+        Header { list: true, payload_length }.encode(&mut out);
+        items.iter().for_each(|item| out.extend_from_slice(item));
+        out
+    }
+
+    /// Encodes bytes as an RLP string.
+    fn string(bytes: &[u8]) -> Vec<u8> {
+        alloy_rlp::encode(bytes)
+    }
+
+    /// Encodes a leaf node with the given nibbles, which must have an odd length.
+    fn leaf(nibbles: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut path = vec![0x30 | nibbles[0]];
+        path.extend(nibbles[1..].chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+        list(&[string(&path), string(value)])
+    }
+
+    fn nibbles(key: B256) -> Vec<u8> {
+        key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+    }
+
+    /// Builds a trie of two keys whose hashes differ in their first nibble, returning the root
+    /// and the proofs of both keys.
+    fn two_key_trie(keys: [B256; 2], values: [&[u8]; 2]) -> (B256, [Vec<Bytes>; 2]) {
+        let leaves = [0, 1].map(|i| leaf(&nibbles(keys[i])[1..], values[i]));
+        let mut children = vec![string(&[]); 17];
+        for (key, leaf) in keys.iter().zip(&leaves) {
+            children[(key[0] >> 4) as usize] = string(keccak256(leaf).as_slice());
+        }
+        let branch = list(&children);
+        let root = keccak256(&branch);
+        (root, [0, 1].map(|i| vec![branch.clone().into(), leaves[i].clone().into()]))
+    }
+
+    #[test]
+    fn verifies_inclusion_and_exclusion() {
+        let keys = [
+            b256!("1000000000000000000000000000000000000000000000000000000000000001"),
+            b256!("2000000000000000000000000000000000000000000000000000000000000002"),
+        ];
+        let (root, proofs) = two_key_trie(keys, [b"first", b"second"]);
+
+        assert_eq!(verify_proof(root, keys[0], &proofs[0]).unwrap(), Some(Bytes::from("first")));
+        assert_eq!(verify_proof(root, keys[1], &proofs[1]).unwrap(), Some(Bytes::from("second")));
+
+        // A key sharing the first nibble of another key, but not the rest of its path.
+        let absent = b256!("1000000000000000000000000000000000000000000000000000000000000003");
+        assert_eq!(verify_proof(root, absent, &proofs[0]).unwrap(), None);
+        // A key whose branch child is empty.
+        let absent = b256!("3000000000000000000000000000000000000000000000000000000000000003");
+        assert_eq!(verify_proof(root, absent, &proofs[0][..1]).unwrap(), None);
+
+        // The proof of a key does not prove another one.
+        assert!(matches!(
+            verify_proof(root, keys[0], &proofs[1]),
+            Err(ProofError::HashMismatch { .. })
+        ));
+        assert!(matches!(
+            verify_proof(root, keys[0], &proofs[0][..1]),
+            Err(ProofError::Incomplete)
+        ));
+        assert!(matches!(
+            verify_proof(B256::repeat_byte(1), keys[0], &proofs[0]),
+            Err(ProofError::HashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn verifies_account_proof() {
+        let address = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        let other = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        let account = Account {
+            nonce: 7,
+            balance: U256::from(1_000_000),
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+        let mut encoded = Vec::new();
+        account.encode(&mut encoded);
+
+        // The keys of the two accounts must differ in their first nibble.
+        let keys = [keccak256(address), keccak256(other)];
+        assert_ne!(keys[0][0] >> 4, keys[1][0] >> 4);
+        let (state_root, [account_proof, _]) = two_key_trie(keys, [&encoded, &[0xc0]]);
+
+        let mut proof = EIP1186AccountProofResponse {
+            address,
+            balance: account.balance,
+            code_hash: account.code_hash,
+            nonce: account.nonce,
+            storage_hash: account.storage_root,
+            account_proof,
+            storage_proof: vec![EIP1186StorageProof {
+                key: B256::ZERO.into(),
+                value: U256::ZERO,
+                proof: vec![],
+            }],
+        };
+        verify_account_proof(state_root, &proof).unwrap();
+
+        // A vendor lying about the balance is caught.
+        proof.balance += U256::from(1);
+        assert!(matches!(
+            verify_account_proof(state_root, &proof),
+            Err(ProofError::AccountMismatch(a)) if a == address
+        ));
+        proof.balance -= U256::from(1);
+
+        // A vendor lying about an empty storage slot is caught.
+        proof.storage_proof[0].value = U256::from(1);
+        assert!(matches!(
+            verify_account_proof(state_root, &proof),
+            Err(ProofError::StorageMismatch(_))
+        ));
+    }
+}
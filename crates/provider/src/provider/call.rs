@@ -234,6 +234,26 @@ where
         self.block = Some(block);
         self
     }
+
+    /// Returns the client used to make the call.
+    pub const fn client(&self) -> &WeakClient<T> {
+        &self.client
+    }
+
+    /// Returns the transaction request of the call.
+    pub const fn data(&self) -> &'req N::TransactionRequest {
+        self.data
+    }
+
+    /// Returns the state overrides of the call, if any.
+    pub const fn state_overrides(&self) -> Option<&'state StateOverride> {
+        self.overrides
+    }
+
+    /// Returns the block of the call, if any.
+    pub const fn block_id(&self) -> Option<BlockId> {
+        self.block
+    }
 }
 
 impl<'req, 'state, T, N, Resp, Output, Map> std::future::IntoFuture
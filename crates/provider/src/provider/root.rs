@@ -120,7 +120,7 @@ impl<T: Transport + Clone, N: Network> RootProvider<T, N> {
             let poller: ChainStreamPoller<T, N> =
                 ChainStreamPoller::from_weak_client(self.inner.weak_client());
             // TODO: Can we avoid `Box::pin` here?
-            Heartbeat::new(Box::pin(poller.into_stream())).spawn()
+            Heartbeat::new(Box::pin(poller.into_stream()), self.inner.weak_client()).spawn()
         })
     }
 }
@@ -690,8 +690,14 @@ pub trait Provider<T: Transport + Clone = BoxTransport, N: Network = Ethereum>:
         match tx {
             SendableTx::Builder(mut tx) => {
                 alloy_network::TransactionBuilder::prep_for_submission(&mut tx);
+                let sender_nonce = alloy_network::TransactionBuilder::from(&tx)
+                    .zip(alloy_network::TransactionBuilder::nonce(&tx));
                 let tx_hash = self.client().request("eth_sendTransaction", (tx,)).await?;
-                Ok(PendingTransactionBuilder::new(self.root(), tx_hash))
+                let mut builder = PendingTransactionBuilder::new(self.root(), tx_hash);
+                if let Some((sender, nonce)) = sender_nonce {
+                    builder.set_sender_nonce(sender, nonce);
+                }
+                Ok(builder)
             }
             SendableTx::Envelope(tx) => {
                 let mut encoded_tx = vec![];
@@ -1008,7 +1014,7 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
-    use crate::{builder, ProviderBuilder, WalletProvider};
+    use crate::{builder, ProviderBuilder, WalletProvider, WatchTxError};
     use alloy_network::AnyNetwork;
     use alloy_node_bindings::Anvil;
     use alloy_primitives::{address, b256, bytes, keccak256};
@@ -1242,6 +1248,83 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    #[tokio::test]
+    async fn test_watch_replaced_tx() {
+        init_tracing();
+        let provider = ProviderBuilder::new().on_anvil_with_config(|anvil| anvil.block_time(1));
+        let tx = TransactionRequest {
+            from: Some(address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266")),
+            value: Some(U256::from(100)),
+            to: Some(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045").into()),
+            nonce: Some(0),
+            gas_price: Some(20e9 as u128),
+            gas: Some(21000),
+            ..Default::default()
+        };
+
+        let builder = provider
+            .send_transaction(tx.clone())
+            .await
+            .expect("failed to send tx")
+            .with_replacement_detection(Some(5));
+        assert!(builder.sender_nonce().is_some());
+
+        // Replace the transaction with a higher gas price before it is mined.
+        let replacement = TransactionRequest { gas_price: Some(40e9 as u128), ..tx };
+        let replacement =
+            provider.send_transaction(replacement).await.expect("failed to send replacement");
+
+        let watch = tokio::time::timeout(Duration::from_secs(10), builder.watch());
+        let err = watch.await.expect("Watching tx timed out").unwrap_err();
+        assert!(matches!(
+            err,
+            PendingTransactionError::TxWatcher(WatchTxError::Replaced { by }) if by == *replacement.tx_hash()
+        ));
+    }
+
+    #[cfg(feature = "anvil-api")]
+    #[tokio::test]
+    async fn test_watch_dropped_tx() {
+        use crate::ext::AnvilApi;
+
+        init_tracing();
+        let provider = ProviderBuilder::new().on_anvil();
+        provider.anvil_set_auto_mine(false).await.unwrap();
+        let from = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        let tx = TransactionRequest {
+            from: Some(from),
+            value: Some(U256::from(100)),
+            to: Some(address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045").into()),
+            nonce: Some(0),
+            gas_price: Some(20e9 as u128),
+            gas: Some(21000),
+            ..Default::default()
+        };
+
+        let builder = provider
+            .send_transaction(tx)
+            .await
+            .expect("failed to send tx")
+            .with_replacement_detection(Some(5));
+
+        // Drop the transaction and use its nonce, without any transaction using it being mined.
+        provider.anvil_drop_transaction(*builder.tx_hash()).await.unwrap();
+        provider.anvil_set_nonce(from, U256::from(1)).await.unwrap();
+
+        let miner = async {
+            loop {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                provider.anvil_mine(Some(U256::from(1)), None).await.unwrap();
+            }
+        };
+        let watch = tokio::time::timeout(Duration::from_secs(10), builder.watch());
+        let err = tokio::select! {
+            res = watch => res.expect("Watching tx timed out").unwrap_err(),
+            () = miner => unreachable!(),
+        };
+        assert!(matches!(err, PendingTransactionError::TxWatcher(WatchTxError::Dropped)));
+    }
+
     #[tokio::test]
     async fn gets_block_number() {
         init_tracing();
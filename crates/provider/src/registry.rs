@@ -0,0 +1,499 @@
+//! A registry of providers for multiple chains.
+//!
+//! The registry is built from a declarative [`RegistryConfig`], which lists the RPC endpoints of
+//! each chain in fallback order, along with an optional rate limit:
+//!
+//! ```toml
+//! [[chains]]
+//! chain = "mainnet"
+//! urls = ["https://eth.llamarpc.com", "https://rpc.ankr.com/eth"]
+//! rate_limit = { compute_units_per_second = 330 }
+//!
+//! [[chains]]
+//! chain = 10
+//! urls = ["wss://optimism.publicnode.com"]
+//! ```
+//!
+//! Parsing TOML requires the `toml` feature. JSON is always supported.
+
+use crate::RootProvider;
+use alloy_chains::Chain;
+use alloy_json_rpc::{RequestPacket, ResponsePacket};
+use alloy_primitives::U64;
+use alloy_rpc_client::{BuiltInConnectionString, ClientBuilder, RpcClient};
+use alloy_transport::{
+    layers::RetryBackoffLayer, BoxTransport, BoxTransportConnect, Transport, TransportError,
+    TransportFut,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// The default number of times a rate limited request is retried.
+const fn default_max_retries() -> u32 {
+    10
+}
+
+/// The default initial backoff, in milliseconds, of a rate limited request.
+const fn default_initial_backoff() -> u64 {
+    1000
+}
+
+/// The configuration of a [`ProviderRegistry`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    /// The configuration of each chain.
+    #[serde(default)]
+    pub chains: Vec<ChainConfig>,
+}
+
+impl RegistryConfig {
+    /// Parses the configuration from JSON.
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    /// Parses the configuration from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+}
+
+/// The configuration of a chain in a [`ProviderRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// The chain, by name or id.
+    pub chain: Chain,
+    /// The connection strings of the RPC endpoints, in fallback order.
+    ///
+    /// HTTP, WebSocket and IPC endpoints are supported, provided the corresponding feature is
+    /// enabled.
+    pub urls: Vec<String>,
+    /// The rate limit of the endpoints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
+}
+
+/// The rate limit of a chain's endpoints, enforced with a [`RetryBackoffLayer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// The number of compute units per second allowed by the endpoints.
+    pub compute_units_per_second: u64,
+    /// The maximum number of times a rate limited request is retried.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// The initial backoff of a rate limited request, in milliseconds.
+    #[serde(default = "default_initial_backoff")]
+    pub initial_backoff: u64,
+}
+
+impl RateLimitConfig {
+    /// Returns the layer enforcing the rate limit.
+    pub const fn layer(&self) -> RetryBackoffLayer {
+        RetryBackoffLayer::new(
+            self.max_retries,
+            self.initial_backoff,
+            self.compute_units_per_second,
+        )
+    }
+}
+
+/// An error that occurred while building or querying a [`ProviderRegistry`].
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    /// The chain is not in the registry.
+    #[error("no provider registered for chain {0}")]
+    UnknownChain(Chain),
+
+    /// The chain is configured more than once.
+    #[error("chain {0} is configured more than once")]
+    DuplicateChain(Chain),
+
+    /// The chain has no endpoints.
+    #[error("no endpoints configured for chain {0}")]
+    NoEndpoints(Chain),
+
+    /// An endpoint of a chain returned a different chain id.
+    #[error("endpoint {url} of chain {chain} returned chain id {actual}")]
+    ChainIdMismatch {
+        /// The configured chain.
+        chain: Chain,
+        /// The connection string of the endpoint.
+        url: String,
+        /// The chain id returned by the endpoint.
+        actual: u64,
+    },
+
+    /// None of the endpoints of a chain could be reached. The error is the one of the last
+    /// endpoint.
+    #[error("failed to connect to chain {chain}: {source}")]
+    Transport {
+        /// The chain being connected.
+        chain: Chain,
+        /// The underlying transport error.
+        #[source]
+        source: TransportError,
+    },
+}
+
+/// A transport that sends each request through the first of its transports that does not fail.
+///
+/// Only transport failures trigger a fallback. Error responses returned by a node are passed
+/// through as-is.
+#[derive(Clone, Debug)]
+pub struct FallbackTransport {
+    transports: Arc<[BoxTransport]>,
+}
+
+impl FallbackTransport {
+    /// Creates a new `FallbackTransport` trying the given transports in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transports` is empty.
+    pub fn new(transports: impl IntoIterator<Item = BoxTransport>) -> Self {
+        let transports = transports.into_iter().collect::<Arc<[_]>>();
+        assert!(!transports.is_empty(), "fallback transport requires at least one transport");
+        Self { transports }
+    }
+}
+
+impl Service<RequestPacket> for FallbackTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The inner transports are polled when the request is sent.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let transports = Arc::clone(&self.transports);
+        Box::pin(async move {
+            let (last, fallbacks) = transports.split_last().expect("checked in `new`");
+            for transport in fallbacks {
+                match transport.clone().call(req.clone()).await {
+                    Ok(res) => return Ok(res),
+                    Err(err) => debug!(%err, "transport failed, falling back"),
+                }
+            }
+            last.clone().call(req).await
+        })
+    }
+}
+
+/// A registry of providers, keyed by chain.
+///
+/// Each chain has its own provider, with its own transport stack, fillers and wallet. The
+/// registry is usually built from a [`RegistryConfig`] with [`ProviderRegistry::connect_with`],
+/// which verifies the chain id of every reachable endpoint.
+///
+/// # Example
+///
+/// ```no_run
+/// # use alloy_provider::{registry::{ProviderRegistry, RegistryConfig}, Provider, ProviderBuilder};
+/// # use alloy_network::EthereumWallet;
+/// # use alloy_chains::NamedChain;
+/// # async fn test(wallet: EthereumWallet) -> Result<(), Box<dyn std::error::Error>> {
+/// let config = RegistryConfig::from_json(
+///     r#"{ "chains": [{ "chain": "mainnet", "urls": ["https://eth.llamarpc.com"] }] }"#,
+/// )?;
+/// let registry = ProviderRegistry::connect_with(&config, |_chain, client| {
+///     ProviderBuilder::new().with_recommended_fillers().wallet(wallet.clone()).on_client(client)
+/// })
+/// .await?;
+///
+/// let block = registry.try_get(NamedChain::Mainnet)?.get_block_number().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProviderRegistry<P> {
+    providers: HashMap<Chain, P>,
+}
+
+impl<P> Default for ProviderRegistry<P> {
+    fn default() -> Self {
+        Self { providers: HashMap::new() }
+    }
+}
+
+impl ProviderRegistry<RootProvider<BoxTransport>> {
+    /// Connects to every configured chain, with a [`RootProvider`] per chain.
+    ///
+    /// See [`ProviderRegistry::connect_with`] for more information.
+    pub async fn connect(config: &RegistryConfig) -> Result<Self, RegistryError> {
+        Self::connect_with(config, |_, client| RootProvider::new(client)).await
+    }
+}
+
+impl<P> ProviderRegistry<P> {
+    /// Creates a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connects to every configured chain, building each provider with `build`.
+    ///
+    /// The client passed to `build` sends requests to the chain's endpoints in fallback order,
+    /// subject to the chain's rate limit. Its poll interval is derived from the chain's average
+    /// block time, as with the [`ChainLayer`].
+    ///
+    /// The chain id of each endpoint is verified before it is used. Endpoints that cannot be
+    /// reached are left out, so that an unavailable fallback does not prevent connecting to the
+    /// chain.
+    ///
+    /// Fails if a chain is configured more than once, if none of its endpoints can be reached, or
+    /// if any of them returns a different chain id.
+    ///
+    /// [`ChainLayer`]: crate::layers::ChainLayer
+    pub async fn connect_with<F>(
+        config: &RegistryConfig,
+        mut build: F,
+    ) -> Result<Self, RegistryError>
+    where
+        F: FnMut(Chain, RpcClient<BoxTransport>) -> P,
+    {
+        let mut registry = Self::new();
+        for chain_config in &config.chains {
+            let chain = chain_config.chain;
+            if registry.providers.contains_key(&chain) {
+                return Err(RegistryError::DuplicateChain(chain));
+            }
+            let client = connect_chain(chain_config).await?;
+            registry.insert(chain, build(chain, client));
+        }
+        Ok(registry)
+    }
+
+    /// Registers the provider of a chain, returning the provider it replaces, if any.
+    pub fn insert(&mut self, chain: impl Into<Chain>, provider: P) -> Option<P> {
+        self.providers.insert(chain.into(), provider)
+    }
+
+    /// Removes the provider of a chain.
+    pub fn remove(&mut self, chain: impl Into<Chain>) -> Option<P> {
+        self.providers.remove(&chain.into())
+    }
+
+    /// Returns the provider of a chain, given by name or id.
+    pub fn get(&self, chain: impl Into<Chain>) -> Option<&P> {
+        self.providers.get(&chain.into())
+    }
+
+    /// Returns the provider of a chain, given by name or id, or an error if it is not registered.
+    pub fn try_get(&self, chain: impl Into<Chain>) -> Result<&P, RegistryError> {
+        let chain = chain.into();
+        self.providers.get(&chain).ok_or(RegistryError::UnknownChain(chain))
+    }
+
+    /// Returns `true` if the chain has a registered provider.
+    pub fn contains(&self, chain: impl Into<Chain>) -> bool {
+        self.providers.contains_key(&chain.into())
+    }
+
+    /// Returns the registered chains.
+    pub fn chains(&self) -> impl Iterator<Item = Chain> + '_ {
+        self.providers.keys().copied()
+    }
+
+    /// Returns the registered chains and their providers.
+    pub fn iter(&self) -> impl Iterator<Item = (Chain, &P)> {
+        self.providers.iter().map(|(chain, provider)| (*chain, provider))
+    }
+
+    /// Returns the number of registered chains.
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Returns `true` if no chain is registered.
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+/// Connects to the endpoints of a chain, and verifies their chain id.
+///
+/// Endpoints that cannot be reached are left out.
+async fn connect_chain(config: &ChainConfig) -> Result<RpcClient<BoxTransport>, RegistryError> {
+    let chain = config.chain;
+    if config.urls.is_empty() {
+        return Err(RegistryError::NoEndpoints(chain));
+    }
+
+    let mut transports = Vec::with_capacity(config.urls.len());
+    let mut is_local = true;
+    let mut last_error = None;
+    for url in &config.urls {
+        match connect_endpoint(url).await {
+            Ok((connect, transport, actual)) if actual == chain.id() => {
+                is_local &= connect.is_local();
+                transports.push(transport);
+            }
+            Ok((_, _, actual)) => {
+                return Err(RegistryError::ChainIdMismatch { chain, url: url.clone(), actual });
+            }
+            Err(err) => {
+                warn!(%chain, %url, %err, "endpoint unreachable, leaving it out");
+                last_error = Some(err);
+            }
+        }
+    }
+    if transports.is_empty() {
+        let source = last_error.expect("at least one endpoint failed");
+        return Err(RegistryError::Transport { chain, source });
+    }
+
+    let transport = FallbackTransport::new(transports);
+    let transport = match &config.rate_limit {
+        Some(rate_limit) => rate_limit.layer().layer(transport).boxed(),
+        None => transport.boxed(),
+    };
+    let client = ClientBuilder::default().transport(transport, is_local);
+
+    if !is_local {
+        if let Some(avg_block_time) = chain.named().and_then(|chain| chain.average_blocktime_hint())
+        {
+            client.set_poll_interval(avg_block_time.mul_f32(0.6));
+        }
+    }
+    Ok(client)
+}
+
+/// Connects to an endpoint, returning its transport and the chain id it reports.
+async fn connect_endpoint(
+    url: &str,
+) -> Result<(BuiltInConnectionString, BoxTransport, u64), TransportError> {
+    let connect = url.parse::<BuiltInConnectionString>()?;
+    let transport = connect.connect_boxed().await?;
+    let client = ClientBuilder::default().transport(transport.clone(), connect.is_local());
+    let chain_id = client.request_noparams::<U64>("eth_chainId").await?.to::<u64>();
+    Ok((connect, transport, chain_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_chains::NamedChain;
+
+    const CONFIG: &str = r#"{
+        "chains": [
+            {
+                "chain": "mainnet",
+                "urls": ["https://eth.llamarpc.com", "https://rpc.ankr.com/eth"],
+                "rate_limit": { "compute_units_per_second": 330 }
+            },
+            { "chain": 10, "urls": ["wss://optimism.publicnode.com"] }
+        ]
+    }"#;
+
+    #[test]
+    fn parses_json_config() {
+        let config = RegistryConfig::from_json(CONFIG).unwrap();
+        assert_eq!(config.chains.len(), 2);
+        assert_eq!(config.chains[0].chain, Chain::mainnet());
+        assert_eq!(config.chains[0].urls.len(), 2);
+        assert_eq!(
+            config.chains[0].rate_limit,
+            Some(RateLimitConfig {
+                compute_units_per_second: 330,
+                max_retries: default_max_retries(),
+                initial_backoff: default_initial_backoff(),
+            })
+        );
+        assert_eq!(config.chains[1].chain, Chain::from(NamedChain::Optimism));
+        assert_eq!(config.chains[1].rate_limit, None);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parses_toml_config() {
+        let config = RegistryConfig::from_toml(
+            r#"
+            [[chains]]
+            chain = "mainnet"
+            urls = ["https://eth.llamarpc.com", "https://rpc.ankr.com/eth"]
+            rate_limit = { compute_units_per_second = 330 }
+
+            [[chains]]
+            chain = 10
+            urls = ["wss://optimism.publicnode.com"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config, RegistryConfig::from_json(CONFIG).unwrap());
+    }
+
+    #[test]
+    fn routes_by_chain() {
+        let mut registry = ProviderRegistry::new();
+        registry.insert(NamedChain::Mainnet, "mainnet");
+        registry.insert(Chain::from_id(10), "optimism");
+
+        assert_eq!(registry.get(1), Some(&"mainnet"));
+        assert_eq!(registry.get(NamedChain::Optimism), Some(&"optimism"));
+        assert!(matches!(
+            registry.try_get(NamedChain::Base),
+            Err(RegistryError::UnknownChain(chain)) if chain == Chain::from(NamedChain::Base)
+        ));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn verifies_chain_id() {
+        use crate::Provider;
+        use alloy_node_bindings::Anvil;
+
+        let anvil = Anvil::new().spawn();
+        let config = |chain: u64| RegistryConfig {
+            chains: vec![ChainConfig {
+                chain: Chain::from_id(chain),
+                urls: vec!["http://localhost:1".into(), anvil.endpoint()],
+                rate_limit: None,
+            }],
+        };
+
+        // The first endpoint is unreachable, so it is left out.
+        let registry = ProviderRegistry::connect(&config(anvil.chain_id())).await.unwrap();
+        let provider = registry.try_get(anvil.chain_id()).unwrap();
+        assert_eq!(provider.get_chain_id().await.unwrap(), anvil.chain_id());
+
+        let err = ProviderRegistry::connect(&config(1)).await.unwrap_err();
+        assert!(
+            matches!(err, RegistryError::ChainIdMismatch { actual, .. } if actual == anvil.chain_id())
+        );
+
+        // Every endpoint is verified, not only the first reachable one.
+        let other = Anvil::new().chain_id(anvil.chain_id() + 1).spawn();
+        let config = RegistryConfig {
+            chains: vec![ChainConfig {
+                chain: Chain::from_id(anvil.chain_id()),
+                urls: vec![anvil.endpoint(), other.endpoint()],
+                rate_limit: None,
+            }],
+        };
+        let err = ProviderRegistry::connect(&config).await.unwrap_err();
+        assert!(matches!(
+            err,
+            RegistryError::ChainIdMismatch { url, actual, .. }
+                if url == other.endpoint() && actual == other.chain_id()
+        ));
+
+        let config = RegistryConfig {
+            chains: vec![ChainConfig {
+                chain: Chain::mainnet(),
+                urls: vec!["http://localhost:1".into()],
+                rate_limit: None,
+            }],
+        };
+        let err = ProviderRegistry::connect(&config).await.unwrap_err();
+        assert!(matches!(err, RegistryError::Transport { .. }));
+    }
+}
@@ -1,6 +1,8 @@
 //! Provider-related utilities.
 
+use alloy_json_rpc::RpcError;
 use alloy_primitives::{U128, U64};
+use alloy_transport::TransportError;
 
 /// The number of blocks from the past for which the fee rewards are fetched for fee estimation.
 pub const EIP1559_FEE_ESTIMATION_PAST_BLOCKS: u64 = 10;
@@ -66,6 +68,38 @@ pub(crate) fn convert_u64(r: U64) -> u64 {
     r.to::<u64>()
 }
 
+/// Returns `true` if a transaction was definitely not broadcast, because it was rejected by the
+/// node or failed before being sent.
+///
+/// Other errors, such as transport errors, are ambiguous: the transaction may have reached the
+/// node. Errors for which the nonce is held by a transaction in the mempool, see
+/// [`is_known_tx_error`], are not rejections either.
+pub(crate) fn is_rejection(err: &TransportError) -> bool {
+    match err {
+        RpcError::ErrorResp(_) => !is_known_tx_error(err),
+        RpcError::LocalUsageError(_) | RpcError::SerError(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns `true` if the node refused a transaction because it, or another transaction with the
+/// same nonce, is already in its mempool.
+///
+/// The messages were checked against geth and the clients sharing its error messages (reth,
+/// Erigon and anvil), and Nethermind.
+pub(crate) fn is_known_tx_error(err: &TransportError) -> bool {
+    let RpcError::ErrorResp(payload) = err else { return false };
+    let message = payload.message.to_lowercase();
+    [
+        "already known",
+        "known transaction",
+        "already imported",
+        "replacement transaction underpriced",
+    ]
+    .iter()
+    .any(|m| message.contains(m))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;